@@ -2,7 +2,8 @@ use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
 
 use crate::error::CuratorError;
 
@@ -28,6 +29,123 @@ pub struct CliArgs {
         default_value = "static/aliases.json"
     )]
     pub aliases: PathBuf,
+
+    /// Minimum AA intelligence index for free models (overrides MIN_FREE_AAII).
+    #[arg(long = "min-free-aaii", value_name = "SCORE")]
+    pub min_free_aaii: Option<f32>,
+
+    /// Minimum AA intelligence index for paid models (overrides MIN_PAID_AAII).
+    #[arg(long = "min-paid-aaii", value_name = "SCORE")]
+    pub min_paid_aaii: Option<f32>,
+
+    /// Maximum input price in USD per 1M tokens for the cheap tier (overrides CHEAP_IN_MAX_USD_PER_1M).
+    #[arg(long = "cheap-in-max", value_name = "USD")]
+    pub cheap_in_max: Option<f64>,
+
+    /// Maximum output price in USD per 1M tokens for the cheap tier (overrides CHEAP_OUT_MAX_USD_PER_1M).
+    #[arg(long = "cheap-out-max", value_name = "USD")]
+    pub cheap_out_max: Option<f64>,
+
+    /// Minimum context length in tokens (overrides MIN_CONTEXT_LENGTH).
+    #[arg(long = "min-context-length", value_name = "TOKENS")]
+    pub min_context_length: Option<u32>,
+
+    /// Similarity required for fuzzy AA-to-OpenRouter matches, 0-1 (overrides FUZZY_MATCH_THRESHOLD).
+    #[arg(long = "fuzzy-match-threshold", value_name = "RATIO")]
+    pub fuzzy_match_threshold: Option<f64>,
+
+    /// JSON list of `{ key, slug_terms, prefer_instruct }` model series that always keep their
+    /// newest free model (replaces the built-in series; overrides FREE_SERIES_FILE).
+    #[arg(long = "free-series", value_name = "FILE")]
+    pub free_series: Option<PathBuf>,
+
+    /// Order of the free tier in the snapshot (overrides FREE_SORT; default aaii).
+    #[arg(long = "free-sort", value_enum, value_name = "ORDER")]
+    pub free_sort: Option<ModelSort>,
+
+    /// Tiebreak between a provider's cheap candidates (overrides CHEAP_TIEBREAK; default price).
+    #[arg(long = "cheap-tiebreak", value_enum, value_name = "ORDER")]
+    pub cheap_tiebreak: Option<ModelSort>,
+
+    /// Per-attempt timeout for dataset requests in milliseconds (overrides CURATOR_REQUEST_TIMEOUT_MS).
+    #[arg(long = "request-timeout-ms", value_name = "MS")]
+    pub request_timeout_ms: Option<u64>,
+
+    /// Limit on connecting to a dataset host in milliseconds (overrides CURATOR_CONNECT_TIMEOUT_MS).
+    #[arg(long = "connect-timeout-ms", value_name = "MS")]
+    pub connect_timeout_ms: Option<u64>,
+
+    /// Limit on waiting for a response to start or continue in milliseconds (overrides
+    /// CURATOR_READ_TIMEOUT_MS).
+    #[arg(long = "read-timeout-ms", value_name = "MS")]
+    pub read_timeout_ms: Option<u64>,
+
+    /// Attempts per dataset request before giving up (overrides CURATOR_MAX_RETRIES).
+    #[arg(long = "max-retries", value_name = "COUNT")]
+    pub max_retries: Option<usize>,
+
+    /// Delay before the first retry in milliseconds, doubled on each further retry
+    /// (overrides CURATOR_RETRY_BACKOFF_MS).
+    #[arg(long = "retry-backoff-ms", value_name = "MS")]
+    pub retry_backoff_ms: Option<u64>,
+}
+
+/// How curated entries are ordered. Ties always fall back to the slug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ModelSort {
+    /// Highest Artificial Analysis intelligence index first.
+    Aaii,
+    /// Cheapest first, then newest.
+    Price,
+    /// Newest on OpenRouter first.
+    Recency,
+    /// Largest context window first.
+    Context,
+}
+
+/// A model family whose newest free OpenRouter model is added to the free tier when curation
+/// did not already pick one of its models.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FreeSeriesSpec {
+    pub key: String,
+    /// Matched case-insensitively against OpenRouter slugs and names.
+    pub slug_terms: Vec<String>,
+    /// Pick an `instruct` variant over a newer non-instruct model.
+    #[serde(default)]
+    pub prefer_instruct: bool,
+}
+
+impl FreeSeriesSpec {
+    fn new(key: &str, slug_terms: &[&str], prefer_instruct: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            slug_terms: slug_terms.iter().map(|term| term.to_string()).collect(),
+            prefer_instruct,
+        }
+    }
+}
+
+/// The series kept in the free tier unless `--free-series` or FREE_SERIES_FILE replaces them.
+pub fn default_free_series() -> Vec<FreeSeriesSpec> {
+    vec![
+        FreeSeriesSpec::new("meta-llama", &["meta-llama"], false),
+        FreeSeriesSpec::new("deepseek", &["deepseek"], false),
+        FreeSeriesSpec::new("qwen", &["qwen"], true),
+        FreeSeriesSpec::new("kimi", &["moonshotai/kimi", "kimi"], false),
+        FreeSeriesSpec::new("zai-glm", &["z-ai/glm", "glm"], false),
+    ]
+}
+
+/// Read a JSON list of [`FreeSeriesSpec`]s.
+pub fn load_free_series(path: &Path) -> Result<Vec<FreeSeriesSpec>, CuratorError> {
+    let raw = fs::read_to_string(path)?;
+    serde_json::from_str(&raw).map_err(|err| {
+        CuratorError::Config(format!(
+            "invalid free series file {}: {}",
+            path.display(),
+            err
+        ))
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +162,12 @@ pub struct Tunables {
     pub fuzzy_match_threshold: f64,
     pub max_retries: usize,
     pub retry_backoff_ms: u64,
+    pub request_timeout_ms: u64,
+    pub connect_timeout_ms: u64,
+    pub read_timeout_ms: u64,
+    pub free_series: Vec<FreeSeriesSpec>,
+    pub free_sort: ModelSort,
+    pub cheap_tiebreak: ModelSort,
 }
 
 #[derive(Debug, Clone)]
@@ -70,16 +194,132 @@ impl CliArgs {
             fs::write(&aliases, "{}\n")?;
         }
 
-        let tunables = Tunables::from_env()?;
+        let mut tunables = Tunables::from_env()?;
+        self.apply_overrides(&mut tunables);
+        if let Some(path) = &self.free_series {
+            tunables.free_series = load_free_series(&resolve_path(path)?)?;
+        }
+        tunables.validate()?;
 
         Ok(AppConfig {
             paths: Paths { snapshot, aliases },
             tunables,
         })
     }
+
+    /// Replace tunables with any values given on the command line.
+    pub fn apply_overrides(&self, tunables: &mut Tunables) {
+        if let Some(value) = self.min_free_aaii {
+            tunables.min_free_aaii = value;
+        }
+        if let Some(value) = self.min_paid_aaii {
+            tunables.min_paid_aaii = value;
+        }
+        if let Some(value) = self.cheap_in_max {
+            tunables.cheap_in_max = value;
+        }
+        if let Some(value) = self.cheap_out_max {
+            tunables.cheap_out_max = value;
+        }
+        if let Some(value) = self.min_context_length {
+            tunables.min_context_length = value;
+        }
+        if let Some(value) = self.fuzzy_match_threshold {
+            tunables.fuzzy_match_threshold = value;
+        }
+        if let Some(value) = self.free_sort {
+            tunables.free_sort = value;
+        }
+        if let Some(value) = self.cheap_tiebreak {
+            tunables.cheap_tiebreak = value;
+        }
+        if let Some(value) = self.request_timeout_ms {
+            tunables.request_timeout_ms = value;
+        }
+        if let Some(value) = self.connect_timeout_ms {
+            tunables.connect_timeout_ms = value;
+        }
+        if let Some(value) = self.read_timeout_ms {
+            tunables.read_timeout_ms = value;
+        }
+        if let Some(value) = self.max_retries {
+            tunables.max_retries = value;
+        }
+        if let Some(value) = self.retry_backoff_ms {
+            tunables.retry_backoff_ms = value;
+        }
+    }
 }
 
 impl Tunables {
+    /// Reject thresholds that would make curation meaningless.
+    pub fn validate(&self) -> Result<(), CuratorError> {
+        let thresholds = [
+            ("min_free_aaii", self.min_free_aaii as f64),
+            ("min_paid_aaii", self.min_paid_aaii as f64),
+            ("cheap_in_max", self.cheap_in_max),
+            ("cheap_out_max", self.cheap_out_max),
+            ("fuzzy_match_threshold", self.fuzzy_match_threshold),
+        ];
+        for (name, value) in thresholds {
+            if !value.is_finite() || value < 0.0 {
+                return Err(CuratorError::Config(format!(
+                    "{} must be a finite, non-negative number (got {})",
+                    name, value
+                )));
+            }
+        }
+        if self.fuzzy_match_threshold > 1.0 {
+            return Err(CuratorError::Config(format!(
+                "fuzzy_match_threshold must be at most 1.0 (got {})",
+                self.fuzzy_match_threshold
+            )));
+        }
+        let timeouts = [
+            ("request_timeout_ms", self.request_timeout_ms),
+            ("connect_timeout_ms", self.connect_timeout_ms),
+            ("read_timeout_ms", self.read_timeout_ms),
+        ];
+        for (name, value) in timeouts {
+            if value == 0 {
+                return Err(CuratorError::Config(format!(
+                    "{} must be greater than zero",
+                    name
+                )));
+            }
+        }
+        for spec in &self.free_series {
+            if spec.key.trim().is_empty()
+                || spec.slug_terms.is_empty()
+                || spec.slug_terms.iter().any(|term| term.trim().is_empty())
+            {
+                return Err(CuratorError::Config(format!(
+                    "free series '{}' needs a key and non-empty slug terms",
+                    spec.key
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// One-line summary of the curation thresholds, printed at the start of a run.
+    pub fn summary(&self) -> String {
+        format!(
+            "min_free_aaii={} min_paid_aaii={} cheap_in_max={} cheap_out_max={} min_context_length={} fuzzy_match_threshold={} free_series={}",
+            self.min_free_aaii,
+            self.min_paid_aaii,
+            self.cheap_in_max,
+            self.cheap_out_max,
+            self.min_context_length,
+            self.fuzzy_match_threshold,
+            self.free_series
+                .iter()
+                .map(|spec| spec.key.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+
     pub fn from_env() -> Result<Self, CuratorError> {
         let openrouter_models_url = env::var("OPENROUTER_MODELS_URL")
             .unwrap_or_else(|_| "https://openrouter.ai/api/v1/models".to_string());
@@ -104,6 +344,17 @@ impl Tunables {
             .ok()
             .and_then(|value| value.parse().ok())
             .unwrap_or(1_000u64);
+        let request_timeout_ms = parse_env("CURATOR_REQUEST_TIMEOUT_MS", 30_000u64, str::parse)?;
+        let connect_timeout_ms = parse_env("CURATOR_CONNECT_TIMEOUT_MS", 10_000u64, str::parse)?;
+        let read_timeout_ms = parse_env("CURATOR_READ_TIMEOUT_MS", 30_000u64, str::parse)?;
+        let free_series = match env::var("FREE_SERIES_FILE") {
+            Ok(path) => load_free_series(&resolve_path(Path::new(&path))?)?,
+            Err(_) => default_free_series(),
+        };
+
+        let parse_sort = |s: &str| ModelSort::from_str(s, true);
+        let free_sort = parse_env("FREE_SORT", ModelSort::Aaii, parse_sort)?;
+        let cheap_tiebreak = parse_env("CHEAP_TIEBREAK", ModelSort::Price, parse_sort)?;
 
         Ok(Self {
             openrouter_models_url,
@@ -118,6 +369,12 @@ impl Tunables {
             fuzzy_match_threshold,
             max_retries,
             retry_backoff_ms,
+            request_timeout_ms,
+            connect_timeout_ms,
+            read_timeout_ms,
+            free_series,
+            free_sort,
+            cheap_tiebreak,
         })
     }
 }
@@ -176,3 +433,98 @@ fn workspace_root() -> PathBuf {
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| PathBuf::from("."))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tunables() -> Tunables {
+        Tunables {
+            openrouter_models_url: String::new(),
+            openrouter_api_key: None,
+            aa_models_url: String::new(),
+            aa_api_key: None,
+            min_free_aaii: 60.0,
+            min_paid_aaii: 65.0,
+            cheap_in_max: 1.5,
+            cheap_out_max: 6.0,
+            min_context_length: 8_192,
+            fuzzy_match_threshold: 0.94,
+            max_retries: 3,
+            retry_backoff_ms: 1_000,
+            request_timeout_ms: 30_000,
+            connect_timeout_ms: 10_000,
+            read_timeout_ms: 30_000,
+            free_series: default_free_series(),
+            free_sort: ModelSort::Aaii,
+            cheap_tiebreak: ModelSort::Price,
+        }
+    }
+
+    #[test]
+    fn cli_flags_override_tunables() {
+        let cli = CliArgs::try_parse_from([
+            "curator_snapshot",
+            "--min-free-aaii",
+            "50",
+            "--cheap-out-max",
+            "10.5",
+            "--min-context-length",
+            "32000",
+            "--request-timeout-ms",
+            "5000",
+            "--read-timeout-ms",
+            "2500",
+            "--max-retries",
+            "5",
+        ])
+        .expect("parse");
+
+        let mut tunables = tunables();
+        cli.apply_overrides(&mut tunables);
+
+        assert_eq!(tunables.min_free_aaii, 50.0);
+        assert_eq!(tunables.min_paid_aaii, 65.0);
+        assert_eq!(tunables.cheap_out_max, 10.5);
+        assert_eq!(tunables.min_context_length, 32_000);
+        assert_eq!(tunables.request_timeout_ms, 5_000);
+        assert_eq!(tunables.read_timeout_ms, 2_500);
+        assert_eq!(tunables.connect_timeout_ms, 10_000);
+        assert_eq!(tunables.max_retries, 5);
+        assert_eq!(tunables.retry_backoff_ms, 1_000);
+        assert!(tunables.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_negative_or_non_finite_thresholds() {
+        let mut negative = tunables();
+        negative.cheap_in_max = -1.0;
+        assert!(matches!(
+            negative.validate(),
+            Err(CuratorError::Config(message)) if message.contains("cheap_in_max")
+        ));
+
+        let mut infinite = tunables();
+        infinite.min_paid_aaii = f32::INFINITY;
+        assert!(infinite.validate().is_err());
+
+        let mut fuzzy = tunables();
+        fuzzy.fuzzy_match_threshold = 1.5;
+        assert!(fuzzy.validate().is_err());
+
+        let mut series = tunables();
+        series.free_series[0].slug_terms.push(" ".to_string());
+        assert!(series.validate().is_err());
+
+        let mut no_timeout = tunables();
+        no_timeout.request_timeout_ms = 0;
+        assert!(no_timeout.validate().is_err());
+
+        let mut no_connect_timeout = tunables();
+        no_connect_timeout.connect_timeout_ms = 0;
+        assert!(matches!(
+            no_connect_timeout.validate(),
+            Err(CuratorError::Config(message)) if message.contains("connect_timeout_ms")
+        ));
+    }
+}
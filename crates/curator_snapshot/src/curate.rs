@@ -4,60 +4,29 @@ use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 
 use crate::alias::{AliasResolver, MatchResult, MatchStrategy};
-use crate::config::Tunables;
+use crate::config::{FreeSeriesSpec, ModelSort, Tunables};
 use crate::fetch::{AaModel, CheapestEndpoint, OpenRouterModel};
 
 const CHEAP_PROVIDER_ORDER: [&str; 4] = ["openai", "x-ai", "google", "anthropic"];
 const PRICE_EPSILON: f64 = 1e-9;
 
-#[derive(Debug, Clone, Copy)]
-struct FreeSeriesSpec {
-    key: &'static str,
-    slug_terms: &'static [&'static str],
-    prefer_instruct: bool,
-}
-
-const FREE_SERIES_SPECS: [FreeSeriesSpec; 5] = [
-    FreeSeriesSpec {
-        key: "meta-llama",
-        slug_terms: &["meta-llama"],
-        prefer_instruct: false,
-    },
-    FreeSeriesSpec {
-        key: "deepseek",
-        slug_terms: &["deepseek"],
-        prefer_instruct: false,
-    },
-    FreeSeriesSpec {
-        key: "qwen",
-        slug_terms: &["qwen"],
-        prefer_instruct: true,
-    },
-    FreeSeriesSpec {
-        key: "kimi",
-        slug_terms: &["moonshotai/kimi", "kimi"],
-        prefer_instruct: false,
-    },
-    FreeSeriesSpec {
-        key: "zai-glm",
-        slug_terms: &["z-ai/glm", "glm"],
-        prefer_instruct: false,
-    },
-];
-
 const FREE_TARGET_BASE: usize = 3;
-const FREE_TARGET_COUNT: usize = FREE_TARGET_BASE + FREE_SERIES_SPECS.len();
+
+/// Free-tier size: the base picks plus one slot per configured series.
+fn free_target_count(tunables: &Tunables) -> usize {
+    FREE_TARGET_BASE + tunables.free_series.len()
+}
 
 impl FreeSeriesSpec {
     fn matches_slug(&self, slug: &str) -> bool {
-        if self.slug_terms.is_empty() {
-            return false;
-        }
-        contains_any(slug, self.slug_terms)
+        let lower = slug.to_ascii_lowercase();
+        self.slug_terms
+            .iter()
+            .any(|term| lower.contains(&term.to_ascii_lowercase()))
     }
 
     fn matches_openrouter(&self, model: &OpenRouterModel) -> bool {
-        self.matches_slug(&model.slug) || contains_any(&model.name, self.slug_terms)
+        self.matches_slug(&model.slug) || self.matches_slug(&model.name)
     }
 }
 
@@ -302,15 +271,16 @@ pub fn curate_models<'a>(
         }
     }
 
-    promote_candidates(&mut free, &mut free_aaii_rejects, FREE_TARGET_COUNT);
-    promote_candidates(&mut free, &mut free_low_rejects, FREE_TARGET_COUNT);
+    let free_target = free_target_count(tunables);
+    promote_candidates(&mut free, &mut free_aaii_rejects, free_target);
+    promote_candidates(&mut free, &mut free_low_rejects, free_target);
     let cheap_target = CHEAP_PROVIDER_ORDER.len();
     promote_candidates(&mut cheap, &mut cheap_aaii_rejects, cheap_target);
     promote_candidates(&mut cheap, &mut cheap_price_rejects, cheap_target);
     promote_candidates(&mut cheap, &mut cheap_low_rejects, cheap_target);
 
-    apply_series_fallbacks(&mut free, openrouter);
-    finalize_free(&mut free);
+    apply_series_fallbacks(&mut free, &tunables.free_series, openrouter);
+    finalize_free(&mut free, free_target, tunables.free_sort);
     let cheap = finalize_cheap(
         cheap,
         &CHEAP_PROVIDER_ORDER,
@@ -343,8 +313,12 @@ pub fn curate_models<'a>(
     }
 }
 
-fn apply_series_fallbacks(free: &mut Vec<CuratedEntry>, openrouter: &[OpenRouterModel]) {
-    for spec in FREE_SERIES_SPECS.iter().copied() {
+fn apply_series_fallbacks(
+    free: &mut Vec<CuratedEntry>,
+    series: &[FreeSeriesSpec],
+    openrouter: &[OpenRouterModel],
+) {
+    for spec in series {
         if free.iter().any(|entry| spec.matches_slug(&entry.slug)) {
             continue;
         }
@@ -374,7 +348,7 @@ fn apply_series_fallbacks(free: &mut Vec<CuratedEntry>, openrouter: &[OpenRouter
 }
 
 fn select_latest_free_series_model<'a>(
-    spec: FreeSeriesSpec,
+    spec: &FreeSeriesSpec,
     openrouter: &'a [OpenRouterModel],
 ) -> Option<&'a OpenRouterModel> {
     let mut candidates: Vec<&OpenRouterModel> = openrouter
@@ -442,16 +416,13 @@ fn promote_candidates(
     *source = retained;
 }
 
-fn finalize_free(entries: &mut Vec<CuratedEntry>) {
-    entries.sort_by(
-        |a, b| match b.aaii.partial_cmp(&a.aaii).unwrap_or(Ordering::Equal) {
-            Ordering::Equal => a.slug.cmp(&b.slug),
-            other => other,
-        },
-    );
-    if entries.len() > FREE_TARGET_COUNT {
-        entries.truncate(FREE_TARGET_COUNT);
+/// Keep the `target` highest-AAII free entries, then order them by `sort`.
+fn finalize_free(entries: &mut Vec<CuratedEntry>, target: usize, sort: ModelSort) {
+    entries.sort_by(|a, b| compare_entries(ModelSort::Aaii, a, b));
+    if entries.len() > target {
+        entries.truncate(target);
     }
+    entries.sort_by(|a, b| compare_entries(sort, a, b));
 }
 
 fn finalize_cheap(
@@ -493,9 +464,13 @@ fn finalize_cheap(
     for provider in provider_order {
         let candidates = provider_pool.remove(*provider).unwrap_or_default();
         let candidates = dedupe_entries(candidates);
-        if let Some(entry) =
-            select_paid_candidate(provider, &candidates, openrouter, &openrouter_by_slug)
-        {
+        if let Some(entry) = select_paid_candidate(
+            provider,
+            &candidates,
+            openrouter,
+            &openrouter_by_slug,
+            tunables.cheap_tiebreak,
+        ) {
             winners.push(entry);
             continue;
         }
@@ -524,13 +499,14 @@ fn select_paid_candidate(
     candidates: &[CuratedEntry],
     openrouter: &[OpenRouterModel],
     openrouter_by_slug: &HashMap<&str, &OpenRouterModel>,
+    tiebreak: ModelSort,
 ) -> Option<CuratedEntry> {
     match provider {
-        "openai" => select_openai_paid(candidates, openrouter, openrouter_by_slug),
-        "google" => select_google_paid(candidates, openrouter, openrouter_by_slug),
-        "x-ai" => select_xai_paid(candidates, openrouter, openrouter_by_slug),
-        "anthropic" => select_anthropic_paid(candidates, openrouter, openrouter_by_slug),
-        _ => select_default_paid(candidates),
+        "openai" => select_openai_paid(candidates, openrouter, openrouter_by_slug, tiebreak),
+        "google" => select_google_paid(candidates, openrouter, openrouter_by_slug, tiebreak),
+        "x-ai" => select_xai_paid(candidates, openrouter, openrouter_by_slug, tiebreak),
+        "anthropic" => select_anthropic_paid(candidates, openrouter, openrouter_by_slug, tiebreak),
+        _ => select_default_paid(candidates, tiebreak),
     }
 }
 
@@ -538,6 +514,7 @@ fn select_openai_paid(
     candidates: &[CuratedEntry],
     openrouter: &[OpenRouterModel],
     _openrouter_by_slug: &HashMap<&str, &OpenRouterModel>,
+    tiebreak: ModelSort,
 ) -> Option<CuratedEntry> {
     let mut minis: Vec<CuratedEntry> = candidates
         .iter()
@@ -546,7 +523,7 @@ fn select_openai_paid(
         .cloned()
         .collect();
 
-    minis.sort_by(compare_paid_entries);
+    minis.sort_by(|left, right| compare_entries(tiebreak, left, right));
     if let Some(entry) = minis.into_iter().next() {
         return Some(entry);
     }
@@ -569,6 +546,7 @@ fn select_google_paid(
     candidates: &[CuratedEntry],
     openrouter: &[OpenRouterModel],
     _openrouter_by_slug: &HashMap<&str, &OpenRouterModel>,
+    tiebreak: ModelSort,
 ) -> Option<CuratedEntry> {
     let mut flash: Vec<(CuratedEntry, f64)> = candidates
         .iter()
@@ -585,7 +563,7 @@ fn select_google_paid(
             .partial_cmp(left_version)
             .unwrap_or(Ordering::Equal)
         {
-            Ordering::Equal => compare_paid_entries(left_entry, right_entry),
+            Ordering::Equal => compare_entries(tiebreak, left_entry, right_entry),
             other => other,
         }
     });
@@ -629,6 +607,7 @@ fn select_xai_paid(
     candidates: &[CuratedEntry],
     openrouter: &[OpenRouterModel],
     _openrouter_by_slug: &HashMap<&str, &OpenRouterModel>,
+    tiebreak: ModelSort,
 ) -> Option<CuratedEntry> {
     let mut grok_fast: Vec<(CuratedEntry, f64)> = candidates
         .iter()
@@ -645,7 +624,7 @@ fn select_xai_paid(
             .partial_cmp(left_version)
             .unwrap_or(Ordering::Equal)
         {
-            Ordering::Equal => compare_paid_entries(left_entry, right_entry),
+            Ordering::Equal => compare_entries(tiebreak, left_entry, right_entry),
             other => other,
         },
     );
@@ -685,6 +664,7 @@ fn select_anthropic_paid(
     candidates: &[CuratedEntry],
     openrouter: &[OpenRouterModel],
     _openrouter_by_slug: &HashMap<&str, &OpenRouterModel>,
+    tiebreak: ModelSort,
 ) -> Option<CuratedEntry> {
     let mut haiku: Vec<(CuratedEntry, f64)> = candidates
         .iter()
@@ -701,7 +681,7 @@ fn select_anthropic_paid(
             .partial_cmp(left_version)
             .unwrap_or(Ordering::Equal)
         {
-            Ordering::Equal => compare_paid_entries(left_entry, right_entry),
+            Ordering::Equal => compare_entries(tiebreak, left_entry, right_entry),
             other => other,
         }
     });
@@ -737,13 +717,13 @@ fn select_anthropic_paid(
         .map(|(model, _)| curated_entry_from_model(model, "provider-heuristic:anthropic-haiku"))
 }
 
-fn select_default_paid(candidates: &[CuratedEntry]) -> Option<CuratedEntry> {
+fn select_default_paid(candidates: &[CuratedEntry], tiebreak: ModelSort) -> Option<CuratedEntry> {
     let mut filtered: Vec<CuratedEntry> = candidates
         .iter()
         .filter(|entry| has_valid_price(entry))
         .cloned()
         .collect();
-    filtered.sort_by(compare_paid_entries);
+    filtered.sort_by(|left, right| compare_entries(tiebreak, left, right));
     filtered.into_iter().next()
 }
 
@@ -814,6 +794,27 @@ fn has_valid_model_price(model: &OpenRouterModel) -> bool {
     model.prompt_price_per_million.is_some() || model.completion_price_per_million.is_some()
 }
 
+/// Order entries by `sort`, falling back to the slug so the order is always total.
+fn compare_entries(sort: ModelSort, left: &CuratedEntry, right: &CuratedEntry) -> Ordering {
+    let order = match sort {
+        ModelSort::Aaii => right
+            .aaii
+            .partial_cmp(&left.aaii)
+            .unwrap_or(Ordering::Equal),
+        ModelSort::Price => return compare_paid_entries(left, right),
+        ModelSort::Recency => {
+            compare_created_desc(left.openrouter_created_at, right.openrouter_created_at)
+        }
+        ModelSort::Context => match (left.context_length, right.context_length) {
+            (Some(left), Some(right)) => right.cmp(&left),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    };
+    order.then_with(|| left.slug.cmp(&right.slug))
+}
+
 fn compare_paid_entries(left: &CuratedEntry, right: &CuratedEntry) -> Ordering {
     let price_order = compare_price_pairs(
         left.price_in_per_million,
@@ -889,10 +890,12 @@ fn compare_created_desc(left: Option<DateTime<Utc>>, right: Option<DateTime<Utc>
     }
 }
 
-fn has_term(value: &str, term: &str) -> bool {
+/// Whether `term` is one whole segment of a slug or display name, split on `/`, `-`, `:`, `.` and
+/// whitespace, ignoring ASCII case. "mini" matches `openai/gpt-4o-mini` but not `google/gemini-2.5-pro`.
+pub fn has_term(value: &str, term: &str) -> bool {
     value
-        .to_ascii_lowercase()
-        .contains(&term.to_ascii_lowercase())
+        .split(|c: char| matches!(c, '/' | '-' | ':' | '.') || c.is_whitespace())
+        .any(|segment| segment.eq_ignore_ascii_case(term))
 }
 
 fn parse_gemini_flash_version(value: &str) -> Option<f64> {
@@ -1055,6 +1058,7 @@ fn contains_any(haystack: &str, terms: &[&str]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::default_free_series;
     use chrono::TimeZone;
 
     #[test]
@@ -1069,6 +1073,15 @@ mod tests {
         assert!(!meets_pricing_thresholds(Some(1.5), Some(4.5), &tunables));
     }
 
+    #[test]
+    fn test_has_term_matches_whole_segments_only() {
+        assert!(has_term("openai/gpt-4o-mini", "mini"));
+        assert!(has_term("OpenAI: GPT-4.1 Mini", "mini"));
+        assert!(has_term("google/gemini-2.0-flash-lite:free", "lite"));
+        assert!(!has_term("google/gemini-2.5-pro", "mini"));
+        assert!(!has_term("vendor/elite-70b", "lite"));
+    }
+
     #[test]
     fn test_openrouter_free_status_requires_pricing() {
         let mut model = OpenRouterModel {
@@ -1377,7 +1390,10 @@ mod tests {
             &aa_models,
             &tunables,
         );
-        assert_eq!(computation.free.len(), std::cmp::min(FREE_TARGET_COUNT, 5));
+        assert_eq!(
+            computation.free.len(),
+            std::cmp::min(free_target_count(&tunables), 5)
+        );
         for index in 0..(computation.free.len() - 1) {
             assert!(
                 computation.free[index].aaii >= computation.free[index + 1].aaii,
@@ -1437,6 +1453,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn free_sort_recency_orders_newest_first() {
+        let free_model = |slug: &str, created: i64| OpenRouterModel {
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            created_at: Some(
+                Utc.timestamp_opt(created, 0)
+                    .single()
+                    .expect("valid timestamp"),
+            ),
+            context_length: Some(128_000),
+            prompt_price_per_million: Some(0.0),
+            completion_price_per_million: Some(0.0),
+            cheapest_endpoint: None,
+        };
+        let openrouter = vec![
+            free_model("meta-llama/llama-4:free", 1_700_000_000),
+            free_model("qwen/qwen3-instruct:free", 1_900_000_000),
+            free_model("deepseek/deepseek-r2:free", 1_800_000_000),
+        ];
+        let slugs = |tunables: &Tunables| {
+            curate_models(HashMap::new(), &openrouter, &[], tunables)
+                .free
+                .into_iter()
+                .map(|entry| entry.slug)
+                .collect::<Vec<_>>()
+        };
+
+        let mut tunables = sample_tunables();
+        assert_eq!(
+            slugs(&tunables),
+            vec![
+                "deepseek/deepseek-r2:free",
+                "meta-llama/llama-4:free",
+                "qwen/qwen3-instruct:free",
+            ]
+        );
+
+        tunables.free_sort = ModelSort::Recency;
+        assert_eq!(
+            slugs(&tunables),
+            vec![
+                "qwen/qwen3-instruct:free",
+                "deepseek/deepseek-r2:free",
+                "meta-llama/llama-4:free",
+            ]
+        );
+    }
+
+    #[test]
+    fn cheap_tiebreak_recency_picks_the_newest_candidate() {
+        let model = |slug: &str, created: i64, price: f64| {
+            let mut entry = curated_entry_from_model(
+                &OpenRouterModel {
+                    slug: slug.to_string(),
+                    name: slug.to_string(),
+                    created_at: Some(
+                        Utc.timestamp_opt(created, 0)
+                            .single()
+                            .expect("valid timestamp"),
+                    ),
+                    context_length: Some(128_000),
+                    prompt_price_per_million: Some(price),
+                    completion_price_per_million: Some(price),
+                    cheapest_endpoint: None,
+                },
+                "provided-slug",
+            );
+            entry.aaii = 70.0;
+            entry
+        };
+        let candidates = vec![
+            model("mistral/older-cheap", 1_700_000_000, 0.1),
+            model("mistral/newer-pricier", 1_800_000_000, 0.5),
+        ];
+
+        let by_price = select_default_paid(&candidates, ModelSort::Price).expect("candidate");
+        assert_eq!(by_price.slug, "mistral/older-cheap");
+        let by_recency = select_default_paid(&candidates, ModelSort::Recency).expect("candidate");
+        assert_eq!(by_recency.slug, "mistral/newer-pricier");
+    }
+
+    #[test]
+    fn series_fallback_honors_configured_series() {
+        let mut tunables = sample_tunables();
+        tunables.free_series = serde_json::from_str(
+            r#"[{ "key": "gemma", "slug_terms": ["google/gemma"], "prefer_instruct": false }]"#,
+        )
+        .expect("series json");
+        let openrouter = vec![
+            OpenRouterModel {
+                slug: "google/gemma-3-27b-it:free".to_string(),
+                name: "Gemma 3 27B".to_string(),
+                created_at: Some(
+                    Utc.timestamp_opt(1_800_000_000, 0)
+                        .single()
+                        .expect("valid timestamp"),
+                ),
+                context_length: Some(128_000),
+                prompt_price_per_million: Some(0.0),
+                completion_price_per_million: Some(0.0),
+                cheapest_endpoint: None,
+            },
+            OpenRouterModel {
+                slug: "meta-llama/llama-4:free".to_string(),
+                name: "Meta Llama 4".to_string(),
+                created_at: Some(
+                    Utc.timestamp_opt(1_800_000_000, 0)
+                        .single()
+                        .expect("valid timestamp"),
+                ),
+                context_length: Some(256_000),
+                prompt_price_per_million: Some(0.0),
+                completion_price_per_million: Some(0.0),
+                cheapest_endpoint: None,
+            },
+        ];
+
+        let computation = curate_models(
+            std::collections::HashMap::<String, String>::new(),
+            &openrouter,
+            &[],
+            &tunables,
+        );
+
+        let slugs: Vec<(&str, Option<&str>)> = computation
+            .free
+            .iter()
+            .map(|entry| (entry.slug.as_str(), entry.match_strategy.as_deref()))
+            .collect();
+        assert_eq!(
+            slugs,
+            vec![("google/gemma-3-27b-it:free", Some("manual-series:gemma"))],
+            "only the configured series should be used for fallbacks"
+        );
+    }
+
     #[test]
     fn series_fallback_prefers_instruct_variants() {
         let tunables = sample_tunables();
@@ -1499,6 +1652,12 @@ mod tests {
             fuzzy_match_threshold: 0.94,
             max_retries: 3,
             retry_backoff_ms: 1_000,
+            request_timeout_ms: 30_000,
+            connect_timeout_ms: 10_000,
+            read_timeout_ms: 30_000,
+            free_series: default_free_series(),
+            free_sort: ModelSort::Aaii,
+            cheap_tiebreak: ModelSort::Price,
         }
     }
 
@@ -16,10 +16,27 @@ pub enum CuratorError {
     OpenRouter(#[from] openrouter_rs::error::OpenRouterError),
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("{label} request timed out after {after_ms} ms")]
+    Timeout { label: String, after_ms: u64 },
+    #[error("{label} responded with HTTP {status}")]
+    HttpStatus { label: String, status: u16 },
+    #[error("could not parse {label} response: {message}")]
+    Parse { label: String, message: String },
 }
 
 impl CuratorError {
     pub fn message<T: Into<String>>(message: T) -> Self {
         CuratorError::Message(message.into())
     }
+
+    /// Whether a failed fetch is worth another attempt: timeouts, dropped connections,
+    /// rate limiting and server errors are; client errors and bad payloads are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            CuratorError::Timeout { .. } => true,
+            CuratorError::HttpStatus { status, .. } => *status >= 500 || *status == 429,
+            CuratorError::Http(err) => err.is_timeout() || err.is_connect() || err.is_request(),
+            _ => false,
+        }
+    }
 }
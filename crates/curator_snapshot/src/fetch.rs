@@ -1,17 +1,14 @@
 use std::collections::BTreeMap;
-use std::sync::Arc;
 use std::time::Duration;
 
 use chrono::{DateTime, NaiveDate, Utc};
 use futures_util::stream::{FuturesUnordered, StreamExt};
-use openrouter_rs::{
-    OpenRouterClient,
-    api::models::{Endpoint, Model as OrModel},
-};
-use reqwest::{Client, StatusCode};
+use openrouter_rs::api::models::{Endpoint, EndpointData, Model as OrModel};
+use reqwest::{Client, RequestBuilder};
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use serde::de::IgnoredAny;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 
 use crate::config::Tunables;
 use crate::error::CuratorError;
@@ -59,28 +56,44 @@ pub async fn fetch_datasets(
     client: &Client,
     tunables: &Tunables,
 ) -> Result<FetchResults, CuratorError> {
-    let openrouter = fetch_openrouter_models(tunables).await?;
-    let aa = fetch_aa_models(client, tunables).await?;
-    Ok(FetchResults { openrouter, aa })
+    let (openrouter, aa) = tokio::join!(
+        fetch_openrouter_models(client, tunables),
+        fetch_aa_models(client, tunables)
+    );
+    Ok(FetchResults {
+        openrouter: openrouter?,
+        aa: aa?,
+    })
+}
+
+/// OpenRouter wraps every API response in a `data` field.
+#[derive(Debug, Deserialize)]
+struct OpenRouterResponse<T> {
+    data: T,
 }
 
 async fn fetch_openrouter_models(
+    client: &Client,
     tunables: &Tunables,
 ) -> Result<Vec<OpenRouterModel>, CuratorError> {
+    const LABEL: &str = "openrouter";
     let api_key = tunables
         .openrouter_api_key
-        .clone()
+        .as_deref()
         .ok_or_else(|| CuratorError::Config("OPENROUTER_API_KEY is required".to_string()))?;
 
     let base_url = derive_openrouter_base_url(&tunables.openrouter_models_url)?;
-    let client = OpenRouterClient::builder()
-        .base_url(base_url.clone())
-        .api_key(api_key.clone())
-        .build()?;
-
-    let models = client.list_models().await?;
+    let models_url = format!("{}/models", base_url);
+    let body = with_retries(LABEL, tunables, || {
+        read_body(
+            LABEL,
+            tunables,
+            client.get(&models_url).bearer_auth(api_key),
+        )
+    })
+    .await?;
+    let models: Vec<OrModel> = parse_openrouter_data(LABEL, &body)?;
 
-    let shared_client = Arc::new(client);
     let mut enriched: Vec<(usize, OpenRouterModel)> = Vec::new();
     let mut pending = FuturesUnordered::new();
     let mut iter = models.into_iter().enumerate();
@@ -89,8 +102,9 @@ async fn fetch_openrouter_models(
 
     while pending.len() < MAX_CONCURRENT_ENDPOINT_FETCHES {
         if let Some((index, model)) = iter.next() {
-            let client = Arc::clone(&shared_client);
-            pending.push(enrich_future(client, model, index));
+            pending.push(enrich_future(
+                client, &base_url, api_key, tunables, model, index,
+            ));
         } else {
             break;
         }
@@ -105,8 +119,9 @@ async fn fetch_openrouter_models(
         }
 
         if let Some((index, model)) = iter.next() {
-            let client = Arc::clone(&shared_client);
-            pending.push(enrich_future(client, model, index));
+            pending.push(enrich_future(
+                client, &base_url, api_key, tunables, model, index,
+            ));
         }
     }
 
@@ -129,7 +144,10 @@ fn derive_openrouter_base_url(models_url: &str) -> Result<String, CuratorError>
 }
 
 async fn enrich_openrouter_model(
-    client: Arc<OpenRouterClient>,
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    tunables: &Tunables,
     model: OrModel,
 ) -> Result<OpenRouterModel, CuratorError> {
     let slug = model.id.clone();
@@ -148,16 +166,17 @@ async fn enrich_openrouter_model(
         }
     };
 
-    let endpoint_data = match client.list_model_endpoints(author, slug_part).await {
-        Ok(data) => Some(data),
-        Err(err) => {
-            eprintln!(
-                "[curator] failed to fetch endpoints for {}: {}",
-                model.id, err
-            );
-            None
-        }
-    };
+    let endpoint_data =
+        match fetch_model_endpoints(client, base_url, api_key, tunables, author, slug_part).await {
+            Ok(data) => Some(data),
+            Err(err) => {
+                eprintln!(
+                    "[curator] failed to fetch endpoints for {}: {}",
+                    model.id, err
+                );
+                None
+            }
+        };
 
     let cheapest_endpoint = endpoint_data
         .as_ref()
@@ -185,15 +204,41 @@ async fn enrich_openrouter_model(
     })
 }
 
-fn enrich_future(
-    client: Arc<OpenRouterClient>,
+async fn enrich_future(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    tunables: &Tunables,
     model: OrModel,
     index: usize,
-) -> impl std::future::Future<Output = Result<(usize, OpenRouterModel), CuratorError>> {
-    async move {
-        let enriched = enrich_openrouter_model(client, model).await;
-        enriched.map(|model| (index, model))
-    }
+) -> Result<(usize, OpenRouterModel), CuratorError> {
+    let enriched = enrich_openrouter_model(client, base_url, api_key, tunables, model).await;
+    enriched.map(|model| (index, model))
+}
+
+/// Fetch the provider endpoints of one model, within the same connect and read limits as the
+/// model list.
+async fn fetch_model_endpoints(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    tunables: &Tunables,
+    author: &str,
+    slug: &str,
+) -> Result<EndpointData, CuratorError> {
+    const LABEL: &str = "openrouter endpoints";
+    let url = format!("{}/models/{}/{}/endpoints", base_url, author, slug);
+    let body = read_body(LABEL, tunables, client.get(&url).bearer_auth(api_key)).await?;
+    parse_openrouter_data(LABEL, &body)
+}
+
+fn parse_openrouter_data<T: DeserializeOwned>(label: &str, body: &str) -> Result<T, CuratorError> {
+    serde_json::from_str::<OpenRouterResponse<T>>(body)
+        .map(|response| response.data)
+        .map_err(|err| CuratorError::Parse {
+            label: label.to_string(),
+            message: err.to_string(),
+        })
 }
 
 fn split_author_slug(id: &str) -> Option<(&str, &str)> {
@@ -299,61 +344,105 @@ async fn fetch_aa_models(
     client: &Client,
     tunables: &Tunables,
 ) -> Result<Vec<AaModel>, CuratorError> {
-    let request = || async {
+    const LABEL: &str = "artificial-analysis";
+    let body = with_retries(LABEL, tunables, || {
         let mut builder = client.get(&tunables.aa_models_url);
         if let Some(key) = &tunables.aa_api_key {
             builder = builder.header("x-api-key", key.as_str()).bearer_auth(key);
         }
-        builder.send().await
-    };
+        read_body(LABEL, tunables, builder)
+    })
+    .await?;
+
+    let payload: AaResponse = serde_json::from_str(&body).map_err(|err| CuratorError::Parse {
+        label: LABEL.to_string(),
+        message: err.to_string(),
+    })?;
+    Ok(payload.into_vec())
+}
 
-    let response = fetch_with_retries("artificial-analysis", tunables, request).await?;
-    if !response.status().is_success() {
-        return Err(CuratorError::Message(format!(
-            "AA responded with {}",
-            response.status()
-        )));
+/// Send `request` and read the whole body, failing on a non-success status or when the server
+/// goes quiet for `read_timeout_ms`.
+async fn read_body(
+    label: &str,
+    tunables: &Tunables,
+    request: RequestBuilder,
+) -> Result<String, CuratorError> {
+    let mut response = within_read_timeout(label, tunables, request.send()).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CuratorError::HttpStatus {
+            label: label.to_string(),
+            status: status.as_u16(),
+        });
     }
+    let mut body = Vec::new();
+    while let Some(chunk) = within_read_timeout(label, tunables, response.chunk()).await? {
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
 
-    let payload: AaResponse = response.json().await?;
-    Ok(payload.into_vec())
+/// Await one step of a response, failing when the server sends nothing for `read_timeout_ms`.
+async fn within_read_timeout<T>(
+    label: &str,
+    tunables: &Tunables,
+    step: impl std::future::Future<Output = Result<T, reqwest::Error>>,
+) -> Result<T, CuratorError> {
+    match timeout(Duration::from_millis(tunables.read_timeout_ms), step).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(CuratorError::Timeout {
+            label: label.to_string(),
+            after_ms: tunables.read_timeout_ms,
+        }),
+    }
 }
 
-async fn fetch_with_retries<F, Fut>(
+/// Run `op` with a per-attempt timeout, retrying transient failures with exponential backoff
+/// (`retry_backoff_ms`, then twice that, and so on) up to `max_retries` attempts in total.
+async fn with_retries<T, F, Fut>(
     label: &str,
     tunables: &Tunables,
     mut op: F,
-) -> Result<reqwest::Response, CuratorError>
+) -> Result<T, CuratorError>
 where
     F: FnMut() -> Fut,
-    Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
+    Fut: std::future::Future<Output = Result<T, CuratorError>>,
 {
     let max = tunables.max_retries.max(1);
-    let backoff = Duration::from_millis(tunables.retry_backoff_ms);
+    let limit = Duration::from_millis(tunables.request_timeout_ms);
+    let mut backoff = Duration::from_millis(tunables.retry_backoff_ms);
     let mut attempt = 0usize;
 
     loop {
-        match op().await {
-            Ok(response) => return Ok(response),
-            Err(err) => {
-                attempt += 1;
-                let should_retry = match err.status() {
-                    Some(status) => {
-                        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
-                    }
-                    None => err.is_timeout() || err.is_connect() || err.is_request(),
-                };
-
-                if attempt >= max || !should_retry {
-                    return Err(CuratorError::Message(format!(
-                        "{} request failed after {} attempts: {}",
-                        label, attempt, err
-                    )));
-                }
+        attempt += 1;
+        let err = match timeout(limit, op()).await {
+            Ok(Ok(value)) => return Ok(value),
+            Ok(Err(CuratorError::Http(err))) if err.is_timeout() => CuratorError::Timeout {
+                label: label.to_string(),
+                after_ms: tunables.request_timeout_ms,
+            },
+            Ok(Err(err)) => err,
+            Err(_) => CuratorError::Timeout {
+                label: label.to_string(),
+                after_ms: tunables.request_timeout_ms,
+            },
+        };
 
-                sleep(backoff).await;
-            }
+        if attempt >= max || !err.is_transient() {
+            return Err(err);
         }
+
+        eprintln!(
+            "[curator] {} attempt {}/{} failed ({}); retrying in {} ms",
+            label,
+            attempt,
+            max,
+            err,
+            backoff.as_millis()
+        );
+        sleep(backoff).await;
+        backoff = backoff.saturating_mul(2);
     }
 }
 
@@ -649,3 +738,187 @@ impl PriceObject {
             .or(self.usd_per_1m_snake)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::config::{ModelSort, default_free_series};
+
+    /// One scripted reply from the mock server: wait `delay_ms`, then answer with `status`.
+    struct Reply {
+        delay_ms: u64,
+        status: u16,
+        body: &'static str,
+    }
+
+    /// Serve `replies` in order, one per connection, and count the requests received.
+    async fn mock_server(replies: Vec<Reply>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let url = format!("http://{}/models", listener.local_addr().expect("addr"));
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        tokio::spawn(async move {
+            for reply in replies {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                tokio::spawn(async move {
+                    sleep(Duration::from_millis(reply.delay_ms)).await;
+                    let response = format!(
+                        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        reply.status,
+                        reply.body.len(),
+                        reply.body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (url, hits)
+    }
+
+    fn tunables(aa_models_url: String) -> Tunables {
+        Tunables {
+            openrouter_models_url: String::new(),
+            openrouter_api_key: None,
+            aa_models_url,
+            aa_api_key: None,
+            min_free_aaii: 60.0,
+            min_paid_aaii: 65.0,
+            cheap_in_max: 1.5,
+            cheap_out_max: 6.0,
+            min_context_length: 8_192,
+            fuzzy_match_threshold: 0.94,
+            max_retries: 3,
+            retry_backoff_ms: 10,
+            request_timeout_ms: 200,
+            connect_timeout_ms: 200,
+            read_timeout_ms: 200,
+            free_series: default_free_series(),
+            free_sort: ModelSort::Aaii,
+            cheap_tiebreak: ModelSort::Price,
+        }
+    }
+
+    const MODELS: &str = r#"{"data":[{"slug":"m","name":"Model"}]}"#;
+
+    #[tokio::test]
+    async fn slow_responses_time_out_after_every_attempt() {
+        let slow = || Reply {
+            delay_ms: 2_000,
+            status: 200,
+            body: MODELS,
+        };
+        let (url, hits) = mock_server(vec![slow(), slow()]).await;
+        let tunables = Tunables {
+            max_retries: 2,
+            ..tunables(url)
+        };
+
+        let err = fetch_aa_models(&Client::new(), &tunables)
+            .await
+            .expect_err("should time out");
+
+        assert!(
+            matches!(&err, CuratorError::Timeout { after_ms: 200, .. }),
+            "{err}"
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn openrouter_model_list_stops_waiting_after_the_read_timeout() {
+        let (url, hits) = mock_server(vec![Reply {
+            delay_ms: 2_000,
+            status: 200,
+            body: r#"{"data":[]}"#,
+        }])
+        .await;
+        let tunables = Tunables {
+            openrouter_models_url: url,
+            openrouter_api_key: Some("key".to_string()),
+            max_retries: 1,
+            request_timeout_ms: 5_000,
+            ..tunables(String::new())
+        };
+
+        let err = fetch_openrouter_models(&Client::new(), &tunables)
+            .await
+            .expect_err("should time out");
+
+        assert!(
+            matches!(&err, CuratorError::Timeout { label, after_ms: 200 } if label == "openrouter"),
+            "{err}"
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn server_errors_and_timeouts_are_retried() {
+        let (url, hits) = mock_server(vec![
+            Reply {
+                delay_ms: 0,
+                status: 503,
+                body: "{}",
+            },
+            Reply {
+                delay_ms: 2_000,
+                status: 200,
+                body: MODELS,
+            },
+            Reply {
+                delay_ms: 0,
+                status: 200,
+                body: MODELS,
+            },
+        ])
+        .await;
+
+        let models = fetch_aa_models(&Client::new(), &tunables(url))
+            .await
+            .expect("third attempt succeeds");
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "Model");
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn client_errors_and_bad_payloads_fail_without_retrying() {
+        let (url, hits) = mock_server(vec![Reply {
+            delay_ms: 0,
+            status: 401,
+            body: "{}",
+        }])
+        .await;
+        let err = fetch_aa_models(&Client::new(), &tunables(url))
+            .await
+            .expect_err("401 is not retried");
+        assert!(
+            matches!(&err, CuratorError::HttpStatus { status: 401, .. }),
+            "{err}"
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (url, hits) = mock_server(vec![Reply {
+            delay_ms: 0,
+            status: 200,
+            body: "not json",
+        }])
+        .await;
+        let err = fetch_aa_models(&Client::new(), &tunables(url))
+            .await
+            .expect_err("bad JSON is not retried");
+        assert!(matches!(&err, CuratorError::Parse { .. }), "{err}");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -5,6 +5,8 @@ pub mod error;
 pub mod fetch;
 pub mod snapshot;
 
+use std::time::Duration;
+
 use reqwest::Client;
 
 use alias::load_alias_map;
@@ -16,9 +18,11 @@ use snapshot::{materialize_snapshot, write_snapshot};
 
 pub async fn run(cli: CliArgs) -> Result<(), CuratorError> {
     let AppConfig { paths, tunables } = cli.resolve()?;
+    println!("Effective tunables: {}", tunables.summary());
 
     let client = Client::builder()
         .user_agent("rconv-curator-snapshot/0.1")
+        .connect_timeout(Duration::from_millis(tunables.connect_timeout_ms))
         .build()?;
 
     let aliases = load_alias_map(&paths.aliases)?;
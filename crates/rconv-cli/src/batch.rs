@@ -0,0 +1,214 @@
+//! `--batch`: run the processing pipeline once per entry of a manifest file.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use rconv_core::{ConvocationsConfig, run_cli};
+
+/// One manifest line: a chat log and, optionally, where to write its transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchEntry {
+    pub infile: String,
+    pub outfile: Option<String>,
+}
+
+/// Outcome of one manifest entry.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub entry: BatchEntry,
+    pub error: Option<String>,
+}
+
+/// Parse a manifest: one `infile[:outfile]` per line. Blank lines and lines starting with `#`
+/// are ignored. A drive-letter colon (`C:\logs\ChatLog.log`) is not treated as a separator.
+pub fn parse_manifest(contents: &str) -> Result<Vec<BatchEntry>, String> {
+    let mut entries = Vec::new();
+    for (index, raw) in contents.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (infile, outfile) = match separator_index(line) {
+            Some(idx) => (line[..idx].trim(), Some(line[idx + 1..].trim())),
+            None => (line, None),
+        };
+        if infile.is_empty() {
+            return Err(format!(
+                "Manifest line {} has no input file: '{}'",
+                index + 1,
+                raw
+            ));
+        }
+        entries.push(BatchEntry {
+            infile: infile.to_string(),
+            outfile: outfile.filter(|out| !out.is_empty()).map(str::to_string),
+        });
+    }
+    Ok(entries)
+}
+
+/// Position of the `:` between infile and outfile, skipping drive-letter colons.
+fn separator_index(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let mut segment_start = 0;
+    for (idx, &byte) in bytes.iter().enumerate() {
+        if byte != b':' {
+            continue;
+        }
+        let is_drive = idx == segment_start + 1
+            && bytes[segment_start].is_ascii_alphabetic()
+            && matches!(bytes.get(idx + 1), Some(b'\\') | Some(b'/'));
+        if !is_drive {
+            return Some(idx);
+        }
+        segment_start = idx + 1;
+    }
+    None
+}
+
+/// Read and parse the manifest at `path`.
+pub fn load_manifest(path: &str) -> Result<Vec<BatchEntry>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read batch manifest {}: {}", path, e))?;
+    let mut entries = parse_manifest(&contents)?;
+    if entries.is_empty() {
+        return Err(format!("Batch manifest {} lists no input files.", path));
+    }
+    assign_outfiles(&mut entries)?;
+    Ok(entries)
+}
+
+/// Give every entry without an outfile `<infile stem>.txt`, so entries don't all fall back to the
+/// same dated default, then reject manifests where two entries would write the same file.
+pub fn assign_outfiles(entries: &mut [BatchEntry]) -> Result<(), String> {
+    for entry in entries.iter_mut() {
+        if entry.outfile.is_none() {
+            let stem = Path::new(&entry.infile)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .ok_or_else(|| format!("Cannot derive an output name from '{}'", entry.infile))?;
+            entry.outfile = Some(format!("{}.txt", stem));
+        }
+    }
+    let mut seen = HashSet::new();
+    for entry in entries.iter() {
+        let outfile = entry.outfile.as_deref().unwrap_or_default();
+        if !seen.insert(outfile) {
+            return Err(format!(
+                "Batch manifest writes '{}' more than once; give those entries distinct outfiles.",
+                outfile
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Run each entry through the pipeline in order, using `base` for everything but the input
+/// and output paths. Stops at the first failure when `fail_fast` is set.
+pub async fn run_batch(
+    base: &ConvocationsConfig,
+    entries: Vec<BatchEntry>,
+    fail_fast: bool,
+) -> Vec<BatchResult> {
+    let total = entries.len();
+    let mut results = Vec::with_capacity(total);
+    for (index, entry) in entries.into_iter().enumerate() {
+        eprintln!("[{}/{}] {}", index + 1, total, entry.infile);
+        let mut config = base.clone();
+        config.infile = entry.infile.clone();
+        config.outfile = entry.outfile.clone();
+        let error = run_cli(config).await.err().map(|err| err.to_string());
+        if let Some(ref error) = error {
+            eprintln!("Error: {}: {}", entry.infile, error);
+        }
+        let failed = error.is_some();
+        results.push(BatchResult { entry, error });
+        if failed && fail_fast {
+            break;
+        }
+    }
+    results
+}
+
+/// Print the per-entry outcome table and a totals line.
+pub fn print_batch_summary(results: &[BatchResult], total: usize) {
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    let succeeded = results.len() - failed;
+    println!();
+    println!("Status  Input -> Output");
+    for result in results {
+        let output = result.entry.outfile.as_deref().unwrap_or("(default)");
+        match result.error {
+            None => println!("ok      {} -> {}", result.entry.infile, output),
+            Some(ref error) => println!("FAILED  {}: {}", result.entry.infile, error),
+        }
+    }
+    println!(
+        "{} succeeded, {} failed, {} not run",
+        succeeded,
+        failed,
+        total - results.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_lines_split_into_infile_and_optional_outfile() {
+        let entries = parse_manifest(
+            "# archive\n\
+logs/2024-01-06.log:out/jan06.txt\n\
+\n\
+logs/2024-01-13.log\n\
+C:\\logs\\ChatLog.log:D:\\out\\conv.txt\n\
+C:\\logs\\Other.log\n\
+logs/trailing.log:\n",
+        )
+        .expect("parse");
+        let pairs: Vec<(&str, Option<&str>)> = entries
+            .iter()
+            .map(|e| (e.infile.as_str(), e.outfile.as_deref()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("logs/2024-01-06.log", Some("out/jan06.txt")),
+                ("logs/2024-01-13.log", None),
+                ("C:\\logs\\ChatLog.log", Some("D:\\out\\conv.txt")),
+                ("C:\\logs\\Other.log", None),
+                ("logs/trailing.log", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_without_outfile_get_distinct_names_from_their_infile() {
+        let mut entries =
+            parse_manifest("logs/2024-01-06.log\nlogs/2024-01-13.log\n").expect("parse");
+        assign_outfiles(&mut entries).expect("assign");
+        let outfiles: Vec<_> = entries.iter().map(|e| e.outfile.as_deref()).collect();
+        assert_eq!(
+            outfiles,
+            vec![Some("2024-01-06.txt"), Some("2024-01-13.txt")]
+        );
+    }
+
+    #[test]
+    fn entries_writing_the_same_outfile_are_rejected() {
+        let mut entries = parse_manifest("a/ChatLog.log\nb/ChatLog.log\n").expect("parse");
+        let err = assign_outfiles(&mut entries).unwrap_err();
+        assert!(err.contains("ChatLog.txt"), "{err}");
+
+        let mut entries = parse_manifest("one.log:out.txt\ntwo.log:out.txt\n").expect("parse");
+        assert!(assign_outfiles(&mut entries).is_err());
+    }
+
+    #[test]
+    fn manifest_line_without_infile_is_an_error() {
+        let err = parse_manifest("ok.log\n:out.txt\n").unwrap_err();
+        assert!(err.contains("line 2"), "{err}");
+    }
+}
@@ -1,11 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use clap::{ArgAction, Args, Parser, Subcommand, ValueHint};
 use rconv_core::config::{
-    DurationOverride, FRIDAY_6_PRESET_NAME, OutputTarget, RuntimeOverrides, TUESDAY_7_PRESET_NAME,
-    TUESDAY_8_PRESET_NAME,
+    ChannelStyle, ConfigWarning, DurationOverride, EllipsisStyle, FRIDAY_6_PRESET_ID,
+    MAX_LLM_CONCURRENCY, MAX_LLM_MAX_RETRIES, NAMED_CHANNEL_PATTERN, OocLinePattern, OocMode,
+    OutputQuotes, OutputTarget, RuntimeOverrides, SayPunctuation, TUESDAY_7_PRESET_ID,
+    TUESDAY_8_PRESET_ID,
 };
-use rconv_core::curator::AUTO_SENTINEL;
+use rconv_core::curator::{AUTO_SENTINEL, AutoStrategy};
+use rconv_core::runtime::{DEFAULT_HEADER_TEMPLATE, OutputFormat};
+use rconv_core::timestamp::validate_timestamp_format;
 
 /// Top-level CLI entrypoint.
 #[derive(Parser, Debug, Clone)]
@@ -25,6 +29,16 @@ pub enum Command {
     Preset(PresetCommand),
     #[command(subcommand)]
     Secret(SecretCommand),
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    #[command(subcommand)]
+    Curated(CuratedCommand),
+    /// List the dates that have chat messages in the log, newest first.
+    ScanDates(ScanDatesArgs),
+    /// Check the configuration, with any processing flags applied, without running.
+    Validate(ValidateArgs),
+    /// Show past runs: when, what window, where the output went and whether it worked.
+    History(HistoryArgs),
 }
 
 /// Preset management subcommands.
@@ -34,6 +48,8 @@ pub enum PresetCommand {
     List,
     /// Show detailed information about a preset.
     Show(PresetShowArgs),
+    /// Show when a preset's event next starts, in its own timezone (all presets by default).
+    Next(PresetShowArgs),
     /// Create a new preset stored in config.toml.
     #[command(alias = "add")]
     Create(PresetCreateArgs),
@@ -42,6 +58,10 @@ pub enum PresetCommand {
     /// Delete a preset by ID (builtin presets cannot be removed).
     #[command(alias = "remove")]
     Delete(PresetDeleteArgs),
+    /// Report overlapping schedules and other preset problems without changing anything.
+    Doctor,
+    /// Check the presets in a shared preset file without importing them.
+    Validate(PresetValidateArgs),
 }
 
 /// Arguments for showing preset details.
@@ -69,6 +89,113 @@ pub enum SecretCommand {
     ClearOpenRouterKey,
 }
 
+/// Configuration file maintenance commands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Apply the fixes made on load to config.toml and write it back.
+    Sanitize(ConfigSanitizeArgs),
+}
+
+/// Curated model catalog commands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum CuratedCommand {
+    /// Show how each curated model was chosen and which thresholds it meets.
+    Explain(CuratedExplainArgs),
+    /// Compare two curated snapshot files: models added, removed, moved between tiers or repriced.
+    Diff(CuratedDiffArgs),
+}
+
+/// Arguments for comparing curated snapshots.
+#[derive(Debug, Clone, Args)]
+pub struct CuratedDiffArgs {
+    /// The earlier snapshot JSON.
+    #[arg(value_name = "OLD", value_hint = ValueHint::FilePath)]
+    pub old: String,
+
+    /// The newer snapshot JSON.
+    #[arg(value_name = "NEW", value_hint = ValueHint::FilePath)]
+    pub new: String,
+
+    /// Print the result as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+/// Arguments for explaining curated catalog entries.
+#[derive(Debug, Clone, Args)]
+pub struct CuratedExplainArgs {
+    /// Only explain this model slug.
+    #[arg(value_name = "SLUG")]
+    pub slug: Option<String>,
+
+    /// Print the result as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+/// Arguments for sanitizing the configuration file.
+#[derive(Debug, Clone, Args)]
+pub struct ConfigSanitizeArgs {
+    /// Show the warnings and a diff of the changes without writing anything.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+/// Arguments for scanning a log for event dates.
+#[derive(Debug, Clone, Args)]
+pub struct ScanDatesArgs {
+    /// Chat log file path (defaults to the configured log).
+    #[arg(short, long, value_hint = ValueHint::FilePath)]
+    pub infile: Option<String>,
+
+    /// Only list dates falling on this weekday (e.g. `saturday`).
+    #[arg(long, value_name = "DAY", value_parser = parse_weekday)]
+    pub weekday: Option<chrono::Weekday>,
+
+    /// Count messages on this channel number instead of say/emote.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32))]
+    pub channel: Option<u32>,
+
+    /// Timestamp layout or regex to try (defaults to the configured formats); repeatable.
+    #[arg(
+        long = "timestamp-format",
+        value_name = "PRESET|REGEX",
+        action = ArgAction::Append
+    )]
+    pub timestamp_format: Vec<String>,
+
+    /// Print the result as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+/// Arguments for showing the run history.
+#[derive(Debug, Clone, Args)]
+pub struct HistoryArgs {
+    /// Only show the N most recent runs.
+    #[arg(long, value_name = "N")]
+    pub last: Option<usize>,
+
+    /// Print the records as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+/// Arguments for validating the configuration.
+#[derive(Debug, Clone, Args)]
+pub struct ValidateArgs {
+    /// Print the result as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+fn parse_weekday(value: &str) -> Result<chrono::Weekday, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Unknown weekday '{}'.", value))
+}
+
 /// Arguments for the main processing flow (default command).
 #[derive(Debug, Clone, Args, Default)]
 pub struct ProcessArgs {
@@ -85,10 +212,26 @@ pub struct ProcessArgs {
     #[arg(long, action = ArgAction::SetTrue)]
     pub dry_run: bool,
 
+    /// With --dry-run, send the first filtered line through the LLM and print the result.
+    #[arg(long = "llm-sample", action = ArgAction::SetTrue, requires = "dry_run")]
+    pub llm_sample: bool,
+
     /// Chat log file path.
     #[arg(short, long, value_hint = ValueHint::FilePath)]
     pub infile: Option<String>,
 
+    /// Use the most recently modified file matching this glob as the chat log.
+    #[arg(
+        long = "infile-glob",
+        value_name = "PATTERN",
+        conflicts_with_all = ["infile", "batch"]
+    )]
+    pub infile_glob: Option<String>,
+
+    /// Compute event windows and default file names as if today were this date (YYYY-MM-DD).
+    #[arg(long = "today", value_name = "DATE", value_parser = parse_date)]
+    pub today: Option<chrono::NaiveDate>,
+
     /// Override the start timestamp (ISO 8601).
     #[arg(long = "start")]
     pub start: Option<String>,
@@ -151,14 +294,40 @@ pub struct ProcessArgs {
     )]
     pub use_llm: Option<bool>,
 
+    /// Skip AI corrections for this run, whatever the config says. Same as `--llm false`.
+    #[arg(long = "no-llm", action = ArgAction::SetTrue, conflicts_with = "use_llm")]
+    pub no_llm: bool,
+
     /// Keep the original file when AI corrections run.
     #[arg(long = "keep-orig", action = ArgAction::SetTrue)]
     pub keep_orig: bool,
 
+    /// Put kept originals in DIR (relative to the output's directory) instead of beside the
+    /// output. Use "none" to clear a configured directory.
+    #[arg(long = "originals-dir", value_name = "DIR", value_hint = ValueHint::DirPath)]
+    pub originals_dir: Option<String>,
+
     /// Skip diff generation when AI corrections run.
     #[arg(long = "no-diff", action = ArgAction::SetTrue)]
     pub no_diff: bool,
 
+    /// Show diffs as unified hunks with N lines of context instead of the full rendering.
+    #[arg(long = "diff-context", value_name = "N", conflicts_with = "no_diff")]
+    pub diff_context: Option<usize>,
+
+    /// Show diffs as a count of changed hunks and only their changed lines.
+    #[arg(long = "diff-summary", action = ArgAction::SetTrue, conflicts_with = "no_diff")]
+    pub diff_summary: bool,
+
+    /// End output files with exactly one newline (default true).
+    #[arg(
+        long = "final-newline",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub final_newline: Option<bool>,
+
     /// Override the output file name.
     #[arg(value_name = "OUTFILE")]
     pub outfile: Option<String>,
@@ -171,6 +340,22 @@ pub struct ProcessArgs {
     #[arg(long = "list-curated", action = ArgAction::SetTrue)]
     pub list_curated: bool,
 
+    /// List presets (same table as `preset list`) and exit.
+    #[arg(long = "list-presets", action = ArgAction::SetTrue)]
+    pub list_presets: bool,
+
+    /// Print the resolved pipeline plan (dates, output, model, diff) and exit without processing.
+    #[arg(long = "explain", action = ArgAction::SetTrue, conflicts_with = "explain_json")]
+    pub explain: bool,
+
+    /// Like --explain, but print the plan as JSON.
+    #[arg(long = "explain-json", action = ArgAction::SetTrue)]
+    pub explain_json: bool,
+
+    /// Print the effective configuration as TOML on stderr (exits when combined with --dry-run).
+    #[arg(long = "print-config", action = ArgAction::SetTrue)]
+    pub print_config: bool,
+
     /// Select output target mode (`file` or `directory`).
     #[arg(long = "output-target", value_name = "MODE")]
     pub output_target: Option<String>,
@@ -178,6 +363,328 @@ pub struct ProcessArgs {
     /// Override the directory used when in `directory` mode.
     #[arg(long = "output-directory", value_hint = ValueHint::DirPath)]
     pub output_directory: Option<String>,
+
+    /// Merge consecutive messages from the same speaker within this many seconds (0 disables).
+    #[arg(long = "merge-gap", value_name = "SECS")]
+    pub merge_gap: Option<u64>,
+
+    /// Drop messages left blank after cleanup (default true).
+    #[arg(
+        long = "drop-empty-messages",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub drop_empty_messages: Option<bool>,
+
+    /// Keep single-paren `( )` asides as in-character text (default true); false strips them
+    /// like `(( ))` OOC.
+    #[arg(
+        long = "keep-parentheticals",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub keep_parentheticals: Option<bool>,
+
+    /// Group the transcript into Dialogue, Actions and Other sections by channel style.
+    #[arg(
+        long = "section-by-channel",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub section_by_channel: Option<bool>,
+
+    /// Drop a message that repeats the previous one's speaker and text, as when the game logs
+    /// a line twice.
+    #[arg(
+        long = "dedupe",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub dedupe: Option<bool>,
+
+    /// Start the transcript with an index of the speakers and how many messages each sent.
+    #[arg(
+        long = "with-index",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub with_index: Option<bool>,
+
+    /// Parse the chat log on several threads, for very large logs.
+    #[arg(
+        long = "parallel-parse",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub parallel_parse: Option<bool>,
+
+    /// Also write the filtered, pre-LLM text (defaults to `<outfile_stem>.filtered.txt`).
+    #[arg(
+        long = "dump-filtered",
+        num_args = 0..=1,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath
+    )]
+    pub dump_filtered: Option<Option<String>>,
+
+    /// Write per-speaker counts, the date window and stage timings as JSON (defaults to
+    /// `<outfile_stem>.stats.json`).
+    #[arg(
+        long = "stats-json",
+        num_args = 0..=1,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath
+    )]
+    pub stats_json: Option<Option<String>>,
+
+    /// Treat messages matching this marker as whole-line OOC (`//` or `'<< >>'`); repeatable.
+    #[arg(long = "ooc-line", value_name = "PATTERN", action = ArgAction::Append)]
+    pub ooc_line: Vec<String>,
+
+    /// Inline OOC handling: `strip`, `keep-inline` or `footnote`.
+    #[arg(long = "ooc-mode", value_name = "MODE")]
+    pub ooc_mode: Option<String>,
+
+    /// Punctuation after "says": `comma`, `colon` or `none`.
+    #[arg(long = "say-punct", value_name = "STYLE")]
+    pub say_punct: Option<String>,
+
+    /// Quotes around speech in the transcript: `straight` or `curly`.
+    #[arg(long = "output-quotes", value_name = "STYLE")]
+    pub output_quotes: Option<String>,
+
+    /// Transcript layout: `prose`, or `tsv` for `channel<TAB>speaker<TAB>message` rows.
+    #[arg(long = "format", value_name = "FORMAT")]
+    pub format: Option<String>,
+
+    /// How ellipses are written: `three-dots` (`...`), `unicode` (`…`) or `as-is`.
+    #[arg(long = "ellipsis", value_name = "STYLE")]
+    pub ellipsis: Option<String>,
+
+    /// Keep chat channel CH and render it as `say`, `emote` or `raw` (text only); repeat for
+    /// several channels.
+    #[arg(long = "channel-style", value_name = "CH=STYLE", action = ArgAction::Append)]
+    pub channel_style: Vec<String>,
+
+    /// Regex for the channel field of each chat log line (default `\d+`).
+    #[arg(
+        long = "channel-pattern",
+        value_name = "REGEX",
+        conflicts_with = "named_channels"
+    )]
+    pub channel_pattern: Option<String>,
+
+    /// Accept channel names such as `say` and `emote` as well as numeric codes.
+    #[arg(long = "named-channels", action = ArgAction::SetTrue)]
+    pub named_channels: bool,
+
+    /// Chat log timestamp layout (`eso`, `iso8601`, `bracketed`, `us`, `eu`) or a regex with
+    /// named groups year/month/day/hour/minute; repeat to try several in order.
+    #[arg(
+        long = "timestamp-format",
+        value_name = "PRESET|REGEX",
+        action = ArgAction::Append
+    )]
+    pub timestamp_format: Vec<String>,
+
+    /// Per-request timeout for AI correction calls, in seconds.
+    #[arg(long = "llm-timeout", value_name = "SECS")]
+    pub llm_timeout: Option<u64>,
+
+    /// Give up connecting to OpenRouter after SECS.
+    #[arg(long = "connect-timeout", value_name = "SECS")]
+    pub connect_timeout: Option<u64>,
+
+    /// Give up when OpenRouter sends no response data for SECS.
+    #[arg(long = "read-timeout", value_name = "SECS")]
+    pub read_timeout: Option<u64>,
+
+    /// Sampling temperature for AI correction calls (0.0-2.0).
+    #[arg(long = "temperature", value_name = "T")]
+    pub temperature: Option<f32>,
+
+    /// Ask reasoning models to skip their thinking phase.
+    #[arg(long = "no-reasoning", action = ArgAction::SetTrue)]
+    pub no_reasoning: bool,
+
+    /// Number of AI correction requests to run at once (1-16).
+    #[arg(long = "llm-concurrency", value_name = "N")]
+    pub llm_concurrency: Option<usize>,
+
+    /// Extra attempts for an AI correction request that fails.
+    #[arg(long = "llm-max-retries", value_name = "N")]
+    pub llm_max_retries: Option<u32>,
+
+    /// Stop before AI corrections when the transcript would need more than N chunk requests
+    /// (0 disables the check; --force skips it).
+    #[arg(long = "llm-max-chunks", value_name = "N")]
+    pub llm_max_chunks: Option<usize>,
+
+    /// Comma-separated fallback models tried in order if the selected model is missing or
+    /// failing (empty clears the configured chain).
+    #[arg(long = "model-chain", value_name = "IDS")]
+    pub model_chain: Option<String>,
+
+    /// How `auto` picks a curated model: `cheapest`, `best-quality` or `fastest`.
+    #[arg(long = "auto-strategy", value_name = "STRATEGY")]
+    pub auto_strategy: Option<String>,
+
+    /// Keep the original text for any chunk whose AI correction retains less than this
+    /// share (0-1) of its lines or characters.
+    #[arg(long = "llm-min-retain-ratio", value_name = "RATIO")]
+    pub llm_min_retain_ratio: Option<f64>,
+
+    /// Share (0-1) of the model's context window to keep free when sizing chunks for AI
+    /// correction.
+    #[arg(long = "context-safety-margin", value_name = "FRACTION")]
+    pub context_safety_margin: Option<f64>,
+
+    /// Retry chunks whose AI correction failed or was rejected once more after the first
+    /// pass (default true).
+    #[arg(
+        long = "llm-retry-failed",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_parser = clap::value_parser!(bool)
+    )]
+    pub llm_retry_failed: Option<bool>,
+
+    /// Stop before AI correction when the transcript has more lines than this (0 disables).
+    #[arg(long = "max-lines", value_name = "N")]
+    pub max_lines: Option<u64>,
+
+    /// Stop before AI correction when the transcript is larger than this many bytes
+    /// (0 disables).
+    #[arg(long = "max-bytes", value_name = "N")]
+    pub max_bytes: Option<u64>,
+
+    /// Prepend a metadata header (preset, date, message count) to the output file.
+    #[arg(long = "header", action = ArgAction::SetTrue)]
+    pub header: bool,
+
+    /// Header template; placeholders: {preset} {date} {start} {end} {window} {count}.
+    #[arg(long = "header-template", value_name = "TEMPLATE")]
+    pub header_template: Option<String>,
+
+    /// Write one output file per speaker instead of a single file.
+    #[arg(long = "split-by-speaker", action = ArgAction::SetTrue)]
+    pub split_by_speaker: bool,
+
+    /// Directory for --split-by-speaker files (defaults to `<outfile_stem>_speakers`).
+    #[arg(
+        long = "dir",
+        value_name = "PATH",
+        value_hint = ValueHint::DirPath,
+        requires = "split_by_speaker"
+    )]
+    pub split_dir: Option<String>,
+
+    /// Replace speaker names with stable pseudonyms ("Speaker A", "Speaker B", ...).
+    #[arg(long = "anonymize", action = ArgAction::SetTrue)]
+    pub anonymize: bool,
+
+    /// With --anonymize, write the pseudonym-to-name mapping to this JSON file.
+    #[arg(
+        long = "anon-map",
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        requires = "anonymize"
+    )]
+    pub anon_map: Option<String>,
+
+    /// Write every chat log line that failed to parse, with the reason, to PATH.
+    #[arg(long = "debug-unparsed", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub debug_unparsed: Option<String>,
+
+    /// Stop after the first N messages, for quick trial runs.
+    #[arg(long = "limit", value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Keep only messages whose text matches REGEX, e.g. `--grep 'dragon|wyrm'`.
+    #[arg(long = "grep", value_name = "REGEX")]
+    pub grep: Option<String>,
+
+    /// Match `--grep` case-insensitively.
+    #[arg(long = "grep-ignore-case", action = ArgAction::SetTrue, requires = "grep")]
+    pub grep_ignore_case: bool,
+
+    /// Append each line's source channel and time, e.g. ` [ch0 21:04]`.
+    #[arg(long = "annotate-source", action = ArgAction::SetTrue)]
+    pub annotate_source: bool,
+
+    /// Fail when most lines in the window do not look like chat log lines.
+    #[arg(long = "strict-parse", action = ArgAction::SetTrue)]
+    pub strict_parse: bool,
+
+    /// Fail when the window or filters leave nothing to write.
+    #[arg(long = "fail-on-empty", action = ArgAction::SetTrue)]
+    pub fail_on_empty: bool,
+
+    /// Tell the AI never to change this name or term; repeatable.
+    #[arg(long = "protect", value_name = "TERM", action = ArgAction::Append)]
+    pub protect: Vec<String>,
+
+    /// Give the AI a glossary of in-world terms from a TSV file of `term<TAB>definition` lines.
+    #[arg(long = "glossary-file", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub glossary_file: Option<String>,
+
+    /// Append stage output for this run to a log file.
+    #[arg(long = "log-file", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub log_file: Option<String>,
+
+    /// After processing, show a diff between the new output and this earlier transcript.
+    #[arg(long = "diff-against", value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub diff_against: Option<String>,
+
+    /// First day (YYYY-MM-DD) of the range processed by --split-events.
+    #[arg(long = "from", value_name = "DATE", value_parser = parse_date, requires = "split_events")]
+    pub from: Option<chrono::NaiveDate>,
+
+    /// Last day (YYYY-MM-DD) of the range processed by --split-events.
+    #[arg(long = "to", value_name = "DATE", value_parser = parse_date, requires = "split_events")]
+    pub to: Option<chrono::NaiveDate>,
+
+    /// Write one output file per preset occurrence between --from and --to.
+    #[arg(
+        long = "split-events",
+        action = ArgAction::SetTrue,
+        requires_all = ["from", "to"],
+        conflicts_with_all = ["last", "start", "end", "process_file", "outfile", "explain", "explain_json"]
+    )]
+    pub split_events: bool,
+
+    /// Go past safety limits: the --split-events cap on the number of events, the
+    /// --max-lines/--max-bytes output size guard and the --llm-max-chunks chunk guard.
+    #[arg(long = "force", action = ArgAction::SetTrue)]
+    pub force: bool,
+
+    /// Create the output directory if it does not exist.
+    #[arg(long = "mkdir-output", action = ArgAction::SetTrue)]
+    pub mkdir_output: bool,
+
+    /// Process every `infile[:outfile]` line of this manifest in turn, sharing all other flags.
+    #[arg(
+        long = "batch",
+        value_name = "MANIFEST",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["infile", "outfile", "split_events", "explain", "explain_json"]
+    )]
+    pub batch: Option<String>,
+
+    /// With --batch, stop at the first entry that fails.
+    #[arg(long = "fail-fast", action = ArgAction::SetTrue, requires = "batch")]
+    pub fail_fast: bool,
+}
+
+fn parse_date(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}'; expected YYYY-MM-DD.", value))
 }
 
 impl ProcessArgs {
@@ -185,7 +692,9 @@ impl ProcessArgs {
     pub fn is_empty(&self) -> bool {
         self.last.is_none()
             && !self.dry_run
+            && !self.llm_sample
             && self.infile.is_none()
+            && self.infile_glob.is_none()
             && self.start.is_none()
             && self.end.is_none()
             && self.preset.is_none()
@@ -199,17 +708,85 @@ impl ProcessArgs {
             && self.process_file.is_none()
             && self.cleanup.is_none()
             && self.use_llm.is_none()
+            && !self.no_llm
             && !self.keep_orig
+            && self.originals_dir.is_none()
             && !self.no_diff
+            && self.diff_context.is_none()
+            && !self.diff_summary
+            && self.final_newline.is_none()
             && self.outfile.is_none()
             && self.model.is_none()
             && !self.list_curated
+            && !self.list_presets
+            && !self.explain
+            && !self.explain_json
+            && !self.print_config
             && self.output_target.is_none()
             && self.output_directory.is_none()
+            && self.merge_gap.is_none()
+            && self.drop_empty_messages.is_none()
+            && self.keep_parentheticals.is_none()
+            && self.section_by_channel.is_none()
+            && self.dedupe.is_none()
+            && self.with_index.is_none()
+            && self.parallel_parse.is_none()
+            && self.dump_filtered.is_none()
+            && self.stats_json.is_none()
+            && self.ooc_line.is_empty()
+            && self.ooc_mode.is_none()
+            && self.say_punct.is_none()
+            && self.output_quotes.is_none()
+            && self.format.is_none()
+            && self.ellipsis.is_none()
+            && self.channel_style.is_empty()
+            && self.channel_pattern.is_none()
+            && !self.named_channels
+            && self.timestamp_format.is_empty()
+            && self.llm_timeout.is_none()
+            && self.connect_timeout.is_none()
+            && self.read_timeout.is_none()
+            && self.temperature.is_none()
+            && !self.no_reasoning
+            && self.llm_concurrency.is_none()
+            && self.llm_max_retries.is_none()
+            && self.llm_max_chunks.is_none()
+            && self.model_chain.is_none()
+            && self.auto_strategy.is_none()
+            && self.llm_min_retain_ratio.is_none()
+            && self.context_safety_margin.is_none()
+            && self.llm_retry_failed.is_none()
+            && self.max_lines.is_none()
+            && self.max_bytes.is_none()
+            && self.log_file.is_none()
+            && self.diff_against.is_none()
+            && !self.header
+            && self.protect.is_empty()
+            && self.glossary_file.is_none()
+            && !self.split_by_speaker
+            && !self.anonymize
+            && self.anon_map.is_none()
+            && self.debug_unparsed.is_none()
+            && self.limit.is_none()
+            && self.grep.is_none()
+            && !self.grep_ignore_case
+            && !self.annotate_source
+            && self.split_dir.is_none()
+            && !self.strict_parse
+            && !self.fail_on_empty
+            && self.today.is_none()
+            && self.header_template.is_none()
+            && self.from.is_none()
+            && self.to.is_none()
+            && !self.split_events
+            && !self.force
+            && !self.mkdir_output
+            && self.batch.is_none()
+            && !self.fail_fast
     }
 
     /// Convert CLI flags into runtime overrides plus any advisory warnings.
-    pub fn to_runtime_overrides(&self) -> Result<(RuntimeOverrides, Vec<String>), String> {
+    pub fn to_runtime_overrides(&self) -> Result<(RuntimeOverrides, Vec<ConfigWarning>), String> {
         let mut overrides = RuntimeOverrides::default();
         let warnings = Vec::new();
 
@@ -221,6 +798,10 @@ impl ProcessArgs {
             overrides.dry_run = Some(true);
         }
 
+        if self.llm_sample {
+            overrides.llm_sample = Some(true);
+        }
+
         if let Some(ref infile) = self.infile {
             overrides.infile = Some(infile.clone());
         }
@@ -284,13 +865,13 @@ impl ProcessArgs {
             preset_ids.insert(preset.clone());
         }
         if self.rsm7 {
-            preset_ids.insert(TUESDAY_7_PRESET_NAME.to_string());
+            preset_ids.insert(TUESDAY_7_PRESET_ID.to_string());
         }
         if self.rsm8 {
-            preset_ids.insert(TUESDAY_8_PRESET_NAME.to_string());
+            preset_ids.insert(TUESDAY_8_PRESET_ID.to_string());
         }
         if self.tp6 {
-            preset_ids.insert(FRIDAY_6_PRESET_NAME.to_string());
+            preset_ids.insert(FRIDAY_6_PRESET_ID.to_string());
         }
 
         if preset_ids.len() > 1 {
@@ -337,7 +918,7 @@ impl ProcessArgs {
             overrides.cleanup = Some(cleanup);
         }
 
-        if let Some(use_llm) = self.use_llm {
+        if let Some(use_llm) = self.use_llm.or(self.no_llm.then_some(false)) {
             overrides.use_llm = Some(use_llm);
             overrides.use_ai_corrections = Some(use_llm);
         }
@@ -347,11 +928,342 @@ impl ProcessArgs {
             overrides.keep_original_output = Some(true);
         }
 
+        if let Some(ref dir) = self.originals_dir {
+            overrides.kept_originals_dir = Some(parse_optional_field(dir));
+        }
+
+        if let Some(context) = self.diff_context {
+            overrides.diff_context = Some(Some(context));
+        }
+        if self.diff_summary {
+            overrides.diff_summary = Some(true);
+        }
+        overrides.final_newline = self.final_newline;
+
         if self.no_diff {
             overrides.no_diff = Some(true);
             overrides.show_diff = Some(false);
         }
 
+        if let Some(gap) = self.merge_gap {
+            overrides.merge_gap_seconds = Some(if gap == 0 { None } else { Some(gap) });
+        }
+        overrides.drop_empty_messages = self.drop_empty_messages;
+        overrides.keep_parentheticals = self.keep_parentheticals;
+        overrides.section_by_channel = self.section_by_channel;
+        overrides.dedupe_consecutive = self.dedupe;
+        overrides.with_index = self.with_index;
+        overrides.parallel_parse = self.parallel_parse;
+
+        if let Some(ref dump) = self.dump_filtered {
+            let path = dump.as_deref().map(str::trim).unwrap_or_default();
+            overrides.dump_filtered = Some(Some(path.to_string()));
+        }
+
+        if let Some(ref stats) = self.stats_json {
+            let path = stats.as_deref().map(str::trim).unwrap_or_default();
+            overrides.stats_json = Some(Some(path.to_string()));
+        }
+
+        if let Some(secs) = self.llm_timeout {
+            if secs == 0 {
+                return Err("--llm-timeout must be at least 1 second.".into());
+            }
+            overrides.llm_timeout_secs = Some(secs);
+        }
+
+        if let Some(secs) = self.connect_timeout {
+            if secs == 0 {
+                return Err("--connect-timeout must be at least 1 second.".into());
+            }
+            overrides.connect_timeout_secs = Some(secs);
+        }
+
+        if let Some(secs) = self.read_timeout {
+            if secs == 0 {
+                return Err("--read-timeout must be at least 1 second.".into());
+            }
+            overrides.read_timeout_secs = Some(secs);
+        }
+
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err("--temperature must be between 0.0 and 2.0.".into());
+            }
+            overrides.llm_temperature = Some(temperature);
+        }
+
+        if self.no_reasoning {
+            overrides.llm_disable_reasoning = Some(true);
+        }
+
+        if let Some(concurrency) = self.llm_concurrency {
+            if !(1..=MAX_LLM_CONCURRENCY).contains(&concurrency) {
+                return Err(format!(
+                    "--llm-concurrency must be between 1 and {}.",
+                    MAX_LLM_CONCURRENCY
+                ));
+            }
+            overrides.llm_concurrency = Some(concurrency);
+        }
+
+        if let Some(retries) = self.llm_max_retries {
+            if retries > MAX_LLM_MAX_RETRIES {
+                return Err(format!(
+                    "--llm-max-retries must be at most {}.",
+                    MAX_LLM_MAX_RETRIES
+                ));
+            }
+            overrides.llm_max_retries = Some(retries);
+        }
+        overrides.llm_max_chunks = self.llm_max_chunks;
+
+        if let Some(ref chain) = self.model_chain {
+            overrides.model_chain = Some(
+                chain
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|model| !model.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+
+        if let Some(ref strategy) = self.auto_strategy {
+            overrides.auto_strategy = Some(AutoStrategy::parse(strategy).ok_or_else(|| {
+                format!(
+                    "Invalid --auto-strategy '{}'; expected cheapest, best-quality or fastest.",
+                    strategy
+                )
+            })?);
+        }
+
+        if let Some(ratio) = self.llm_min_retain_ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err("--llm-min-retain-ratio must be between 0 and 1.".into());
+            }
+            overrides.llm_min_retain_ratio = Some(ratio);
+        }
+        if let Some(margin) = self.context_safety_margin {
+            if !(0.0..1.0).contains(&margin) {
+                return Err("--context-safety-margin must be at least 0 and below 1.".into());
+            }
+            overrides.context_safety_margin = Some(margin);
+        }
+        overrides.llm_retry_failed = self.llm_retry_failed;
+
+        if let Some(lines) = self.max_lines {
+            overrides.max_output_lines = Some(lines);
+        }
+
+        if let Some(bytes) = self.max_bytes {
+            overrides.max_output_bytes = Some(bytes);
+        }
+
+        if self.force {
+            overrides.force = Some(true);
+        }
+
+        if self.mkdir_output {
+            overrides.mkdir_output = Some(true);
+        }
+
+        if let Some(ref template) = self.header_template {
+            overrides.header_template = Some(Some(template.clone()));
+        } else if self.header {
+            overrides.header_template = Some(Some(DEFAULT_HEADER_TEMPLATE.to_string()));
+        }
+
+        if self.split_by_speaker {
+            overrides.split_by_speaker = Some(true);
+        }
+
+        if let Some(ref dir) = self.split_dir {
+            overrides.split_directory = Some(parse_optional_field(dir));
+        }
+
+        if self.anonymize {
+            overrides.anonymize = Some(true);
+        }
+
+        if let Some(ref path) = self.anon_map {
+            overrides.anon_map = Some(Some(path.clone()));
+        }
+
+        if let Some(ref path) = self.debug_unparsed {
+            overrides.debug_unparsed = Some(Some(path.clone()));
+        }
+
+        if let Some(limit) = self.limit {
+            if limit == 0 {
+                return Err("--limit must be at least 1.".into());
+            }
+            overrides.limit = Some(Some(limit));
+        }
+
+        if let Some(ref pattern) = self.grep {
+            overrides.grep = Some(Some(pattern.clone()));
+        }
+        if self.grep_ignore_case {
+            overrides.grep_ignore_case = Some(true);
+        }
+
+        if self.annotate_source {
+            overrides.annotate_source = Some(true);
+        }
+
+        if self.strict_parse {
+            overrides.strict_parse = Some(true);
+        }
+
+        if self.fail_on_empty {
+            overrides.fail_on_empty = Some(true);
+        }
+
+        overrides.today = self.today;
+
+        if !self.protect.is_empty() {
+            overrides.extra_protected_terms = Some(self.protect.clone());
+        }
+
+        if let Some(ref path) = self.glossary_file {
+            let raw = std::fs::read_to_string(path)
+                .map_err(|err| format!("Failed to read glossary file {}: {}", path, err))?;
+            let entries = rconv_core::config::parse_glossary_tsv(&raw)
+                .map_err(|err| format!("Invalid glossary file {}: {}", path, err))?;
+            overrides.extra_glossary = Some(entries);
+        }
+
+        if let Some(ref path) = self.log_file {
+            overrides.log_file = Some(parse_optional_field(path));
+        }
+
+        if let Some(ref path) = self.diff_against {
+            overrides.diff_against = Some(parse_optional_field(path));
+        }
+
+        if !self.ooc_line.is_empty() {
+            let patterns = self
+                .ooc_line
+                .iter()
+                .map(|value| OocLinePattern::parse(value))
+                .collect::<Result<Vec<_>, _>>()?;
+            overrides.extra_ooc_line_patterns = Some(patterns);
+        }
+
+        if let Some(ref mode) = self.ooc_mode {
+            let normalized = mode.trim().to_ascii_lowercase();
+            let mode = match normalized.as_str() {
+                "strip" => OocMode::Strip,
+                "keep-inline" | "keep" => OocMode::KeepInline,
+                "footnote" => OocMode::Footnote,
+                other => {
+                    return Err(format!(
+                        "Unknown OOC mode '{other}'. Expected 'strip', 'keep-inline' or 'footnote'."
+                    ));
+                }
+            };
+            overrides.ooc_mode = Some(mode);
+        }
+
+        if let Some(ref style) = self.say_punct {
+            let normalized = style.trim().to_ascii_lowercase();
+            let punctuation = match normalized.as_str() {
+                "comma" => SayPunctuation::Comma,
+                "colon" => SayPunctuation::Colon,
+                "none" => SayPunctuation::None,
+                other => {
+                    return Err(format!(
+                        "Unknown say punctuation '{other}'. Expected 'comma', 'colon' or 'none'."
+                    ));
+                }
+            };
+            overrides.say_punctuation = Some(punctuation);
+        }
+
+        if let Some(ref style) = self.output_quotes {
+            let normalized = style.trim().to_ascii_lowercase();
+            let quotes = match normalized.as_str() {
+                "straight" => OutputQuotes::Straight,
+                "curly" | "smart" => OutputQuotes::Curly,
+                other => {
+                    return Err(format!(
+                        "Unknown output quote style '{other}'. Expected 'straight' or 'curly'."
+                    ));
+                }
+            };
+            overrides.output_quotes = Some(quotes);
+        }
+
+        if let Some(ref format) = self.format {
+            let normalized = format.trim().to_ascii_lowercase();
+            let format = match normalized.as_str() {
+                "prose" => OutputFormat::Prose,
+                "tsv" => OutputFormat::Tsv,
+                other => {
+                    return Err(format!(
+                        "Unknown output format '{other}'. Expected 'prose' or 'tsv'."
+                    ));
+                }
+            };
+            overrides.output_format = Some(format);
+        }
+
+        if let Some(ref style) = self.ellipsis {
+            let normalized = style.trim().to_ascii_lowercase();
+            let ellipsis = match normalized.as_str() {
+                "three-dots" | "dots" => EllipsisStyle::ThreeDots,
+                "unicode" => EllipsisStyle::Unicode,
+                "as-is" => EllipsisStyle::AsIs,
+                other => {
+                    return Err(format!(
+                        "Unknown ellipsis style '{other}'. Expected 'three-dots', 'unicode' or 'as-is'."
+                    ));
+                }
+            };
+            overrides.ellipsis_style = Some(ellipsis);
+        }
+
+        if !self.channel_style.is_empty() {
+            let mut styles = BTreeMap::new();
+            for spec in &self.channel_style {
+                let Some((channel, style)) = spec.split_once('=') else {
+                    return Err(format!(
+                        "Invalid channel style '{spec}'. Expected CH=STYLE, e.g. 11=raw."
+                    ));
+                };
+                let channel = channel.trim();
+                if channel.is_empty() {
+                    return Err(format!("Channel style '{spec}' has no channel."));
+                }
+                let style = match style.trim().to_ascii_lowercase().as_str() {
+                    "say" => ChannelStyle::Say,
+                    "emote" => ChannelStyle::Emote,
+                    "raw" => ChannelStyle::Raw,
+                    other => {
+                        return Err(format!(
+                            "Unknown channel style '{other}'. Expected 'say', 'emote' or 'raw'."
+                        ));
+                    }
+                };
+                styles.insert(channel.to_string(), style);
+            }
+            overrides.channel_styles = Some(styles);
+        }
+
+        if self.named_channels {
+            overrides.channel_pattern = Some(NAMED_CHANNEL_PATTERN.to_string());
+        } else {
+            overrides.channel_pattern = self.channel_pattern.clone();
+        }
+
+        if !self.timestamp_format.is_empty() {
+            for spec in &self.timestamp_format {
+                validate_timestamp_format(spec)?;
+            }
+            overrides.timestamp_formats = Some(self.timestamp_format.clone());
+        }
+
         Ok((overrides, warnings))
     }
 }
@@ -373,6 +1285,12 @@ pub struct PresetCreateArgs {
     pub file_prefix: String,
     #[arg(long = "weeks-ago", default_value_t = 0)]
     pub default_weeks_ago: u32,
+    /// Output filename pattern, e.g. `conv-{yyyy}-{mm}-{dd}.txt`
+    #[arg(long = "filename-template", value_name = "TEMPLATE")]
+    pub filename_template: Option<String>,
+    /// Model used for this preset when the global model is `auto`, e.g. `openai/gpt-4o-mini`
+    #[arg(long = "llm-model", value_name = "SLUG")]
+    pub llm_model: Option<String>,
 }
 
 /// Arguments for updating an existing preset by name.
@@ -399,6 +1317,12 @@ pub struct PresetUpdateArgs {
     /// New default weeks ago (if changing)
     #[arg(long = "new-weeks-ago")]
     pub default_weeks_ago: Option<u32>,
+    /// New output filename pattern (if changing); `none` restores the default pattern
+    #[arg(long = "new-filename-template", value_name = "TEMPLATE")]
+    pub filename_template: Option<String>,
+    /// New pinned model (if changing); `none` follows the global model again
+    #[arg(long = "new-llm-model", value_name = "SLUG")]
+    pub llm_model: Option<String>,
 }
 
 /// Arguments for deleting a preset by name.
@@ -408,7 +1332,19 @@ pub struct PresetDeleteArgs {
     pub name: String,
 }
 
-fn parse_optional_field(value: &str) -> Option<String> {
+/// Arguments for validating a preset file.
+#[derive(Debug, Clone, Args)]
+pub struct PresetValidateArgs {
+    /// TOML file with `[[presets]]` tables, as in config.toml.
+    #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub file: String,
+
+    /// Print the result as JSON.
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+pub(crate) fn parse_optional_field(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
         None
@@ -475,6 +1411,259 @@ mod tests {
             "unexpected error message: {err}"
         );
     }
+
+    #[test]
+    fn merge_gap_zero_disables_merging() {
+        let args = ProcessArgs {
+            merge_gap: Some(0),
+            ..Default::default()
+        };
+        let (overrides, _) = args.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.merge_gap_seconds, Some(None));
+
+        let args = ProcessArgs {
+            merge_gap: Some(5),
+            ..Default::default()
+        };
+        let (overrides, _) = args.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.merge_gap_seconds, Some(Some(5)));
+    }
+
+    #[test]
+    fn no_llm_disables_corrections_enabled_in_config() {
+        assert!(Cli::try_parse_from(["rconv", "--llm", "--no-llm"]).is_err());
+
+        let cli = Cli::try_parse_from(["rconv", "--no-llm", "--keep-orig"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.use_llm, Some(false));
+        assert_eq!(overrides.use_ai_corrections, Some(false));
+
+        let mut config = rconv_core::ConvocationsConfig::default();
+        assert!(config.use_llm);
+        let mut warnings = Vec::new();
+        rconv_core::apply_runtime_overrides(
+            &mut config,
+            &overrides,
+            &rconv_core::FileConfig::default().presets,
+            &mut warnings,
+        );
+        assert!(!config.use_llm);
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.code == rconv_core::WarningCode::LlmOnlyOption)
+        );
+    }
+
+    #[test]
+    fn llm_sample_requires_dry_run() {
+        assert!(Cli::try_parse_from(["rconv", "--llm-sample"]).is_err());
+
+        let cli = Cli::try_parse_from(["rconv", "--dry-run", "--llm-sample"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.dry_run, Some(true));
+        assert_eq!(overrides.llm_sample, Some(true));
+    }
+
+    #[test]
+    fn explain_flags_are_exclusive_and_not_overrides() {
+        assert!(Cli::try_parse_from(["rconv", "--explain", "--explain-json"]).is_err());
+
+        let cli = Cli::try_parse_from(["rconv", "--explain-json", "--rsm7"]).expect("parse");
+        assert!(cli.process.explain_json);
+        assert!(!cli.process.is_empty());
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.dry_run, None);
+    }
+
+    #[test]
+    fn model_chain_splits_on_commas() {
+        let cli = Cli::try_parse_from([
+            "rconv",
+            "--model-chain",
+            "google/gemini-2.5-flash-lite, openai/gpt-4o-mini,",
+        ])
+        .expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(
+            overrides.model_chain,
+            Some(vec![
+                "google/gemini-2.5-flash-lite".to_string(),
+                "openai/gpt-4o-mini".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn ooc_mode_parses_known_modes() {
+        let cli = Cli::try_parse_from(["rconv", "--ooc-mode", "Footnote"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.ooc_mode, Some(OocMode::Footnote));
+
+        let cli = Cli::try_parse_from(["rconv", "--ooc-mode", "hide"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn format_parses_prose_and_tsv() {
+        let cli = Cli::try_parse_from(["rconv", "--format", "TSV"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.output_format, Some(OutputFormat::Tsv));
+
+        let cli = Cli::try_parse_from(["rconv", "--format", "csv"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn say_punct_parses_styles() {
+        let cli = Cli::try_parse_from(["rconv", "--say-punct", "colon"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.say_punctuation, Some(SayPunctuation::Colon));
+
+        let cli = Cli::try_parse_from(["rconv", "--say-punct", "dash"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn ellipsis_parses_styles() {
+        let cli = Cli::try_parse_from(["rconv", "--ellipsis", "Unicode"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.ellipsis_style, Some(EllipsisStyle::Unicode));
+
+        let cli = Cli::try_parse_from(["rconv", "--ellipsis", "dashes"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn output_quotes_parses_styles() {
+        let cli = Cli::try_parse_from(["rconv", "--output-quotes", "Curly"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.output_quotes, Some(OutputQuotes::Curly));
+
+        let cli = Cli::try_parse_from(["rconv", "--output-quotes", "angled"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn named_channels_sets_the_channel_pattern() {
+        let cli = Cli::try_parse_from(["rconv", "--named-channels"]).expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(
+            overrides.channel_pattern.as_deref(),
+            Some(NAMED_CHANNEL_PATTERN)
+        );
+
+        assert!(
+            Cli::try_parse_from(["rconv", "--named-channels", "--channel-pattern", "[a-z]+"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn channel_style_parses_channel_and_style() {
+        let cli = Cli::try_parse_from([
+            "rconv",
+            "--channel-style",
+            "11=Raw",
+            "--channel-style",
+            "6=say",
+        ])
+        .expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        let styles = overrides.channel_styles.expect("channel styles");
+        assert_eq!(styles.get("11"), Some(&ChannelStyle::Raw));
+        assert_eq!(styles.get("6"), Some(&ChannelStyle::Say));
+
+        for bad in ["11", "=raw", "11=shout"] {
+            let cli = Cli::try_parse_from(["rconv", "--channel-style", bad]).expect("parse");
+            assert!(cli.process.to_runtime_overrides().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn timestamp_formats_are_validated_in_order() {
+        let cli = Cli::try_parse_from([
+            "rconv",
+            "--timestamp-format",
+            "bracketed",
+            "--timestamp-format",
+            "eso",
+        ])
+        .expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(
+            overrides.timestamp_formats,
+            Some(vec!["bracketed".to_string(), "eso".to_string()])
+        );
+
+        let cli = Cli::try_parse_from(["rconv", "--timestamp-format", "(unclosed"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn temperature_and_reasoning_flags_become_overrides() {
+        let cli = Cli::try_parse_from(["rconv", "--temperature", "0.9", "--no-reasoning"])
+            .expect("parse");
+        let (overrides, _) = cli.process.to_runtime_overrides().expect("overrides");
+        assert_eq!(overrides.llm_temperature, Some(0.9));
+        assert_eq!(overrides.llm_disable_reasoning, Some(true));
+
+        let cli = Cli::try_parse_from(["rconv", "--temperature", "2.5"]).expect("parse");
+        assert!(cli.process.to_runtime_overrides().is_err());
+    }
+
+    #[test]
+    fn split_events_requires_a_range() {
+        assert!(Cli::try_parse_from(["rconv", "--split-events", "--from", "2025-09-01"]).is_err());
+        assert!(
+            Cli::try_parse_from(["rconv", "--from", "2025-09-01", "--to", "2025-10-01"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "rconv",
+                "--split-events",
+                "--from",
+                "2025-09-01",
+                "--to",
+                "2025-10-01",
+                "--start",
+                "2025-09-01T00:00",
+            ])
+            .is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "rconv",
+            "--split-events",
+            "--from",
+            "2025-09-01",
+            "--to",
+            "2025-10-01",
+            "--force",
+        ])
+        .expect("parse");
+        assert!(!cli.process.is_empty());
+        assert_eq!(
+            cli.process.from,
+            chrono::NaiveDate::from_ymd_opt(2025, 9, 1)
+        );
+        assert_eq!(cli.process.to, chrono::NaiveDate::from_ymd_opt(2025, 10, 1));
+        assert!(cli.process.force);
+    }
+
+    #[test]
+    fn batch_conflicts_with_single_file_flags() {
+        let cli = Cli::try_parse_from(["rconv", "--batch", "manifest.txt", "--fail-fast"])
+            .expect("parse");
+        assert!(!cli.process.is_empty());
+        assert_eq!(cli.process.batch.as_deref(), Some("manifest.txt"));
+        assert!(cli.process.fail_fast);
+
+        assert!(
+            Cli::try_parse_from(["rconv", "--batch", "manifest.txt", "--infile", "a.log"]).is_err()
+        );
+        assert!(Cli::try_parse_from(["rconv", "--fail-fast"]).is_err());
+    }
 }
 
 fn combine_duration(current: Option<f32>, next: f32) -> Result<Option<f32>, String> {
@@ -1,13 +1,25 @@
+pub mod batch;
 pub mod cli_args;
 
 use clap::Parser;
-use cli_args::{Cli, Command, PresetCommand, SecretCommand};
+use cli_args::{
+    Cli, Command, ConfigCommand, CuratedCommand, HistoryArgs, PresetCommand, ScanDatesArgs,
+    SecretCommand, ValidateArgs,
+};
+use rconv_core::history;
 use rconv_core::logging::{self, LoggingDestination};
+use rconv_core::timestamp::TimestampFormats;
 use rconv_core::{
-    apply_runtime_overrides,
-    config::{PresetDefinition, preset_id_from_name},
-    curator, load_config, run_cli, runtime_preferences_to_convocations, save_config,
-    save_presets_and_ui_only,
+    ConfigSource, RunPlan, SplitEventManifest, apply_runtime_overrides,
+    config::{
+        PresetDefinition, parse_preset_file, preset_diagnostics, preset_id_from_name,
+        preset_problems, serialize_config, unique_preset_id, validate_model_slug,
+    },
+    config_path, curator, effective_config_toml, explain_run, load_config, next_occurrence,
+    run_cli, run_split_events,
+    runtime::expand_path,
+    runtime_preferences_to_convocations, save_config, save_presets_and_ui_only,
+    scan_event_dates_in_file, validate_filename_template, validation,
 };
 use rpassword::prompt_password;
 
@@ -66,11 +78,52 @@ async fn dispatch(cli: Cli) -> Result<(), String> {
             }
             handle_secret_command(cmd)
         }
+        Some(Command::Config(cmd)) => {
+            if !cli.process.is_empty() {
+                return Err("Processing flags cannot be combined with config commands.".into());
+            }
+            handle_config_command(cmd)
+        }
+        Some(Command::Curated(cmd)) => {
+            if !cli.process.is_empty() {
+                return Err("Processing flags cannot be combined with curated commands.".into());
+            }
+            handle_curated_command(cmd)
+        }
+        Some(Command::ScanDates(args)) => {
+            if !cli.process.is_empty() {
+                return Err("Processing flags cannot be combined with scan-dates.".into());
+            }
+            handle_scan_dates(args)
+        }
+        Some(Command::Validate(args)) => handle_validate(args, cli.process),
+        Some(Command::History(args)) => {
+            if !cli.process.is_empty() {
+                return Err("Processing flags cannot be combined with history.".into());
+            }
+            handle_history(args)
+        }
         None => run_process(cli.process).await,
     }
 }
 
 async fn run_process(args: cli_args::ProcessArgs) -> Result<(), String> {
+    if args.list_presets {
+        let others = cli_args::ProcessArgs {
+            list_presets: false,
+            ..args
+        };
+        if !others.is_empty() {
+            return Err("--list-presets cannot be combined with processing flags.".into());
+        }
+        let load = load_config();
+        for warning in load.warnings {
+            eprintln!("Warning: {warning}");
+        }
+        print_preset_table(&load.config.presets);
+        return Ok(());
+    }
+
     if args.list_curated {
         let entries = curator::catalog_summaries().map_err(|err| err.to_string())?;
         if entries.is_empty() {
@@ -119,6 +172,13 @@ async fn run_process(args: cli_args::ProcessArgs) -> Result<(), String> {
         runtime_preferences_to_convocations(&load.config.runtime, &presets);
     warnings.append(&mut runtime_warnings);
 
+    let mut args = args;
+    if let Some(ref pattern) = args.infile_glob {
+        let infile = newest_glob_match(pattern)?;
+        eprintln!("Using {infile} (newest match for {pattern})");
+        args.infile = Some(infile);
+    }
+
     let (overrides, mut override_warnings) = args.to_runtime_overrides()?;
     warnings.append(&mut override_warnings);
 
@@ -134,10 +194,174 @@ async fn run_process(args: cli_args::ProcessArgs) -> Result<(), String> {
         eprintln!("Warning: {warning}");
     }
 
-    run_cli(runtime_config).await
+    if args.print_config {
+        let toml = effective_config_toml(&runtime_config).map_err(|err| err.to_string())?;
+        eprint!("{toml}");
+        if args.dry_run {
+            return Ok(());
+        }
+    }
+
+    if args.explain || args.explain_json {
+        let plan = explain_run(runtime_config)
+            .await
+            .map_err(|err| err.to_string())?;
+        if args.explain_json {
+            let json = serde_json::to_string_pretty(&plan)
+                .map_err(|err| format!("Failed to serialize plan: {err}"))?;
+            println!("{json}");
+        } else {
+            print_run_plan(&plan);
+        }
+        return Ok(());
+    }
+
+    if args.split_events {
+        let (Some(from), Some(to)) = (args.from, args.to) else {
+            return Err("--split-events needs both --from and --to.".to_string());
+        };
+        let manifest = run_split_events(runtime_config, from, to, args.force)
+            .await
+            .map_err(|err| err.to_string())?;
+        print_split_manifest(&manifest);
+        return Ok(());
+    }
+
+    if let Some(ref manifest) = args.batch {
+        let entries = batch::load_manifest(manifest)?;
+        let total = entries.len();
+        let results = batch::run_batch(&runtime_config, entries, args.fail_fast).await;
+        batch::print_batch_summary(&results, total);
+        let failed = results.iter().filter(|r| r.error.is_some()).count();
+        if failed > 0 {
+            return Err(format!("{} of {} batch entries failed.", failed, total));
+        }
+        return Ok(());
+    }
+
+    run_cli(runtime_config).await.map_err(|err| err.to_string())
+}
+
+/// The most recently modified file matching `pattern` (after `~` and variable expansion).
+fn newest_glob_match(pattern: &str) -> Result<String, String> {
+    let expanded = expand_path(pattern.trim())?;
+    let paths = glob::glob(&expanded)
+        .map_err(|err| format!("Invalid --infile-glob pattern '{pattern}': {err}"))?;
+    paths
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("No files match --infile-glob '{pattern}'."))
+}
+
+fn print_split_manifest(manifest: &SplitEventManifest) {
+    let written = manifest
+        .entries
+        .iter()
+        .filter(|entry| entry.skipped.is_none())
+        .count();
+    println!(
+        "Processed {} event(s) from {} to {}: {} written, {} skipped",
+        manifest.entries.len(),
+        manifest.from,
+        manifest.to,
+        written,
+        manifest.entries.len() - written
+    );
+    for entry in &manifest.entries {
+        match entry.skipped {
+            Some(ref reason) => println!("  {}  skipped ({})", entry.event_date, reason),
+            None => println!("  {}  {}", entry.event_date, entry.output),
+        }
+    }
+}
+
+fn print_run_plan(plan: &RunPlan) {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    println!("Mode            : {}", plan.mode);
+    println!("Input           : {}", plan.input);
+    if let Some(event_type) = plan.event_type {
+        match plan.preset {
+            Some(ref preset) => println!("Event type      : {} (preset {})", event_type, preset),
+            None => println!("Event type      : {}", event_type),
+        }
+    }
+    if let (Some(minutes), Some(source)) = (plan.duration_minutes, &plan.duration_source) {
+        println!("Duration        : {} minutes ({})", minutes, source);
+    }
+    if let Some(weeks_ago) = plan.weeks_ago {
+        println!("Weeks ago       : {}", weeks_ago);
+    }
+    if let (Some(start), Some(end)) = (&plan.start, &plan.end) {
+        println!("Window          : {} → {}", start, end);
+    }
+    if !plan.channels.is_empty() {
+        println!("Channels        : {}", plan.channels.join(", "));
+    }
+    println!("Format dialogue : {}", yes_no(plan.format_dialogue));
+    println!("Cleanup         : {}", yes_no(plan.cleanup));
+    if plan.output_overridden {
+        println!(
+            "Output          : {} (override; default would be {})",
+            plan.output, plan.default_output
+        );
+    } else {
+        println!("Output          : {}", plan.output);
+    }
+    match plan.llm_model {
+        Some(ref model) => println!("LLM model       : {}", model),
+        None => println!("LLM model       : disabled"),
+    }
+    if plan.llm_model.is_some() {
+        println!("Show diff       : {}", yes_no(plan.show_diff));
+        println!("Keep original   : {}", yes_no(plan.keep_original));
+    }
+}
+
+/// Print the `preset list` table, sorted by name.
+fn print_preset_table(presets: &[PresetDefinition]) {
+    let mut entries: Vec<&PresetDefinition> = presets.iter().collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    if entries.is_empty() {
+        println!("No presets defined.");
+        return;
+    }
+    println!(
+        "{:<28}  {:<28}  {:<26}  {:>5}m  {:<10}  {:>5}  {}",
+        "ID", "Name", "When", "Dur", "Prefix", "Weeks", "Source"
+    );
+    for preset in entries {
+        let when = format!(
+            "{} {} {}",
+            capitalize_ascii(&preset.weekday),
+            preset.start_time,
+            preset.timezone
+        );
+        let source = if preset.builtin { "builtin" } else { "custom" };
+        println!(
+            "{:<28}  {:<28}  {:<26}  {:>5}m  {:<10}  {:>5}  {}",
+            preset.id,
+            preset.name,
+            when,
+            preset.duration_minutes,
+            preset.file_prefix,
+            preset.default_weeks_ago,
+            source
+        );
+    }
 }
 
 fn handle_preset_command(command: PresetCommand) -> Result<(), String> {
+    // Validation reads only the given file, never the user's config.
+    if let PresetCommand::Validate(args) = command {
+        return validate_preset_file(&args);
+    }
+
     let load = load_config();
     for warning in load.warnings {
         eprintln!("Warning: {warning}");
@@ -146,36 +370,7 @@ fn handle_preset_command(command: PresetCommand) -> Result<(), String> {
 
     match command {
         PresetCommand::List => {
-            let mut entries: Vec<&PresetDefinition> = config.presets.iter().collect();
-            entries.sort_by(|a, b| a.name.cmp(&b.name));
-            if entries.is_empty() {
-                println!("No presets defined.");
-            } else {
-                println!(
-                    "{:<28}  {:<28}  {:<26}  {:>5}m  {:<10}  {:>5}  {}",
-                    "ID", "Name", "When", "Dur", "Prefix", "Weeks", "Source"
-                );
-                for preset in entries {
-                    let id = preset_id_from_name(&preset.name);
-                    let when = format!(
-                        "{} {} {}",
-                        capitalize_ascii(&preset.weekday),
-                        preset.start_time,
-                        preset.timezone
-                    );
-                    let source = if preset.builtin { "builtin" } else { "custom" };
-                    println!(
-                        "{:<28}  {:<28}  {:<26}  {:>5}m  {:<10}  {:>5}  {}",
-                        id,
-                        preset.name,
-                        when,
-                        preset.duration_minutes,
-                        preset.file_prefix,
-                        preset.default_weeks_ago,
-                        source
-                    );
-                }
-            }
+            print_preset_table(&config.presets);
             Ok(())
         }
         PresetCommand::Show(args) => {
@@ -183,11 +378,35 @@ fn handle_preset_command(command: PresetCommand) -> Result<(), String> {
             print_preset_details(preset);
             Ok(())
         }
+        PresetCommand::Next(args) => {
+            let now = chrono::Utc::now();
+            let describe = |preset: &PresetDefinition| {
+                next_occurrence(preset, now)
+                    .map(|start| start.format("%a %Y-%m-%d %H:%M %Z").to_string())
+            };
+            if args.id.is_none() && args.name.is_none() {
+                for preset in &config.presets {
+                    let next = describe(preset).unwrap_or_else(|err| err);
+                    println!("{:<24} {}", preset.name, next);
+                }
+                return Ok(());
+            }
+            let preset = resolve_preset(&config.presets, &args)?;
+            println!("{}: {}", preset.name, describe(preset)?);
+            Ok(())
+        }
         PresetCommand::Create(args) => {
             if config.presets.iter().any(|preset| preset.name == args.name) {
                 return Err(format!("Preset '{}' already exists.", args.name));
             }
+            if let Some(template) = &args.filename_template {
+                validate_filename_template(template)?;
+            }
+            if let Some(model) = &args.llm_model {
+                validate_model_slug(model)?;
+            }
             let preset = PresetDefinition {
+                id: unique_preset_id(&config.presets, &args.name),
                 name: args.name.clone(),
                 weekday: args.weekday.to_ascii_lowercase(),
                 timezone: args.timezone.clone(),
@@ -195,6 +414,11 @@ fn handle_preset_command(command: PresetCommand) -> Result<(), String> {
                 duration_minutes: args.duration_minutes,
                 file_prefix: args.file_prefix.clone(),
                 default_weeks_ago: args.default_weeks_ago,
+                filename_template: args.filename_template.clone(),
+                llm_model: args
+                    .llm_model
+                    .as_deref()
+                    .map(|model| model.trim().to_string()),
                 builtin: false,
             };
             config.presets.push(preset);
@@ -229,6 +453,20 @@ fn handle_preset_command(command: PresetCommand) -> Result<(), String> {
             if let Some(weeks_ago) = args.default_weeks_ago {
                 preset.default_weeks_ago = weeks_ago;
             }
+            if let Some(template) = args.filename_template.as_deref() {
+                let template = cli_args::parse_optional_field(template);
+                if let Some(template) = &template {
+                    validate_filename_template(template)?;
+                }
+                preset.filename_template = template;
+            }
+            if let Some(model) = args.llm_model.as_deref() {
+                let model = cli_args::parse_optional_field(model);
+                if let Some(model) = &model {
+                    validate_model_slug(model)?;
+                }
+                preset.llm_model = model;
+            }
 
             // Save only presets and UI preferences; runtime preferences are session-only
             save_presets_and_ui_only(&config.presets, &config.ui).map_err(|err| err.to_string())?;
@@ -256,9 +494,89 @@ fn handle_preset_command(command: PresetCommand) -> Result<(), String> {
             println!("Deleted preset '{}'", args.name);
             Ok(())
         }
+        PresetCommand::Doctor => {
+            let findings = preset_diagnostics(&config.presets);
+            if findings.is_empty() {
+                println!(
+                    "Checked {} presets: no problems found.",
+                    config.presets.len()
+                );
+            } else {
+                println!(
+                    "Checked {} presets: {} problem(s) found.",
+                    config.presets.len(),
+                    findings.len()
+                );
+                for finding in findings {
+                    println!("- {}", finding);
+                }
+            }
+            Ok(())
+        }
+        PresetCommand::Validate(_) => unreachable!("handled before loading the config"),
     }
 }
 
+fn validate_preset_file(args: &cli_args::PresetValidateArgs) -> Result<(), String> {
+    let raw = std::fs::read_to_string(&args.file)
+        .map_err(|err| format!("Failed to read {}: {err}", args.file))?;
+    let presets =
+        parse_preset_file(&raw).map_err(|err| format!("Failed to parse {}: {err}", args.file))?;
+    let results: Vec<(&PresetDefinition, Vec<String>)> = presets
+        .iter()
+        .map(|preset| (preset, preset_problems(preset)))
+        .collect();
+    let failed = results
+        .iter()
+        .filter(|(_, problems)| !problems.is_empty())
+        .count();
+
+    if args.json {
+        let report: Vec<_> = results
+            .iter()
+            .map(|(preset, problems)| {
+                serde_json::json!({
+                    "id": preset.id,
+                    "name": preset.name,
+                    "ok": problems.is_empty(),
+                    "problems": problems,
+                })
+            })
+            .collect();
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|err| format!("Failed to serialize report: {err}"))?;
+        println!("{json}");
+    } else {
+        for (preset, problems) in &results {
+            if problems.is_empty() {
+                println!("PASS  {}", preset.name);
+            } else {
+                println!("FAIL  {}", preset.name);
+                for problem in problems {
+                    println!("      - {}", problem);
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} preset(s) in {} failed validation.",
+            failed,
+            results.len(),
+            args.file
+        ));
+    }
+    if !args.json {
+        println!(
+            "All {} preset(s) in {} are valid.",
+            results.len(),
+            args.file
+        );
+    }
+    Ok(())
+}
+
 fn handle_secret_command(command: SecretCommand) -> Result<(), String> {
     let load = load_config();
     for warning in load.warnings {
@@ -293,6 +611,341 @@ fn handle_secret_command(command: SecretCommand) -> Result<(), String> {
     }
 }
 
+fn handle_config_command(command: ConfigCommand) -> Result<(), String> {
+    match command {
+        ConfigCommand::Sanitize(args) => {
+            let path = config_path();
+            let before = std::fs::read_to_string(&path)
+                .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+            let load = load_config();
+            for warning in &load.warnings {
+                println!("[{}] {}", warning.code, warning);
+            }
+            if load.source != ConfigSource::File {
+                return Err(format!(
+                    "{} could not be loaded; fix it by hand before sanitizing.",
+                    path.display()
+                ));
+            }
+            let after = serialize_config(&load.config).map_err(|err| err.to_string())?;
+            if before == after {
+                println!("{} is already clean.", path.display());
+                return Ok(());
+            }
+
+            if args.dry_run {
+                let mut diff = Vec::new();
+                termdiff::diff(&mut diff, &before, &after, &termdiff::SignsTheme::default())
+                    .map_err(|err| format!("Failed to render diff: {err}"))?;
+                print!("{}", String::from_utf8_lossy(&diff));
+                println!("Dry run: {} was not changed.", path.display());
+                return Ok(());
+            }
+
+            save_config(&load.config).map_err(|err| err.to_string())?;
+            println!(
+                "Wrote sanitized configuration to {} ({} warning(s) addressed; comments are not preserved).",
+                path.display(),
+                load.warnings.len()
+            );
+            Ok(())
+        }
+    }
+}
+
+fn handle_curated_command(command: CuratedCommand) -> Result<(), String> {
+    match command {
+        CuratedCommand::Explain(args) => {
+            let entries =
+                curator::explain_catalog(args.slug.as_deref()).map_err(|err| err.to_string())?;
+            if let Some(ref slug) = args.slug.filter(|_| entries.is_empty()) {
+                return Err(format!("'{slug}' is not in the curated catalog."));
+            }
+            if args.json {
+                let json = serde_json::to_string_pretty(&entries)
+                    .map_err(|err| format!("Failed to serialize provenance: {err}"))?;
+                println!("{json}");
+                return Ok(());
+            }
+            for entry in &entries {
+                print_curated_provenance(entry);
+            }
+            Ok(())
+        }
+        CuratedCommand::Diff(args) => {
+            let read = |path: &str| {
+                std::fs::read_to_string(path).map_err(|err| format!("Cannot read {path}: {err}"))
+            };
+            let diff = curator::diff_snapshots(&read(&args.old)?, &read(&args.new)?)
+                .map_err(|err| err.to_string())?;
+            if args.json {
+                let json = serde_json::to_string_pretty(&diff)
+                    .map_err(|err| format!("Failed to serialize diff: {err}"))?;
+                println!("{json}");
+            } else {
+                print_snapshot_diff(&diff);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_snapshot_diff(diff: &curator::SnapshotDiff) {
+    if diff.is_empty() {
+        println!("No changes.");
+        return;
+    }
+    let tier = |tier: curator::CuratedTier| match tier {
+        curator::CuratedTier::Free => "free",
+        curator::CuratedTier::Cheap => "cheap",
+    };
+    let price = |value: Option<f64>| {
+        value
+            .map(|v| format!("{v:.2}"))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    for entry in &diff.added {
+        println!("+ {} ({})", entry.slug, tier(entry.tier));
+    }
+    for entry in &diff.removed {
+        println!("- {} ({})", entry.slug, tier(entry.tier));
+    }
+    for change in &diff.tier_changes {
+        println!(
+            "~ {}: tier {} -> {}",
+            change.slug,
+            tier(change.from),
+            tier(change.to)
+        );
+    }
+    for change in &diff.price_changes {
+        println!(
+            "~ {}: price in {} -> {}, out {} -> {}",
+            change.slug,
+            price(change.old_in_per_million),
+            price(change.new_in_per_million),
+            price(change.old_out_per_million),
+            price(change.new_out_per_million)
+        );
+    }
+}
+
+fn print_curated_provenance(entry: &curator::CuratedProvenance) {
+    let summary = &entry.summary;
+    let tier = match summary.tier {
+        curator::CuratedTier::Free => "free",
+        curator::CuratedTier::Cheap => "cheap",
+    };
+    let origin = match entry.origin {
+        curator::EntryOrigin::Matched => "matched",
+        curator::EntryOrigin::SeriesFallback => "series fallback",
+        curator::EntryOrigin::ProviderHeuristic => "provider heuristic",
+        curator::EntryOrigin::ProviderFallback => "provider fallback",
+        curator::EntryOrigin::Unknown => "unknown",
+    };
+    let price_source = match summary.price_source {
+        curator::PriceSource::Aa => "aa",
+        curator::PriceSource::Openrouter => "openrouter",
+    };
+    println!("{} ({tier})", summary.slug);
+    println!(
+        "  Origin: {origin}{}",
+        if entry.promoted {
+            ", promoted below threshold"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "  Match strategy: {}",
+        summary.match_strategy.as_deref().unwrap_or("-")
+    );
+    println!("  Price source: {price_source}");
+    for check in &entry.thresholds {
+        let value = check
+            .value
+            .map(|value| format!("{value}"))
+            .unwrap_or_else(|| "unknown".to_string());
+        println!(
+            "  {} {}: {value} (limit {})",
+            if check.passed { "PASS" } else { "FAIL" },
+            check.name,
+            check.limit
+        );
+    }
+}
+
+/// `validate`: load the config, apply the processing flags and report every problem.
+fn handle_validate(args: ValidateArgs, process: cli_args::ProcessArgs) -> Result<(), String> {
+    let load = load_config();
+    let mut warnings = load.warnings;
+    let (mut runtime_config, mut runtime_warnings) =
+        runtime_preferences_to_convocations(&load.config.runtime, &load.config.presets);
+    warnings.append(&mut runtime_warnings);
+
+    let mut process = process;
+    let glob_error = match process.infile_glob {
+        Some(ref pattern) => newest_glob_match(pattern)
+            .map(|infile| process.infile = Some(infile))
+            .err(),
+        None => None,
+    };
+
+    let mut result = match process.to_runtime_overrides() {
+        Ok((overrides, mut override_warnings)) => {
+            warnings.append(&mut override_warnings);
+            let preset_catalog = runtime_config.presets.clone();
+            apply_runtime_overrides(
+                &mut runtime_config,
+                &overrides,
+                &preset_catalog,
+                &mut warnings,
+            );
+            validation::validate(&runtime_config, &warnings)
+        }
+        Err(err) => {
+            let mut result = validation::validate(&runtime_config, &warnings);
+            result.push_error(None, err);
+            result
+        }
+    };
+    if let Some(err) = glob_error {
+        result.push_error(Some("infile"), err);
+    }
+    if let Some(estimate) = validation::estimate_cost(&runtime_config) {
+        result.estimated_tokens = Some(estimate.estimated_tokens);
+        result.estimated_cost_usd = estimate.estimated_cost_usd;
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|err| format!("Failed to serialize validation result: {err}"))?;
+        println!("{json}");
+    } else {
+        for warning in &result.warnings {
+            println!("warning: {warning}");
+        }
+        for error in &result.errors {
+            println!("error: {error}");
+        }
+        if !result.field_errors.is_empty() {
+            println!("Settings with errors:");
+            for (field, error) in &result.field_errors {
+                println!("  {field}: {error}");
+            }
+        }
+        if let Some(ref outfile) = result.outfile {
+            println!("Output: {outfile}");
+        }
+        if let Some(tokens) = result.estimated_tokens {
+            match result.estimated_cost_usd {
+                Some(cost) => println!("Estimated LLM usage: ~{tokens} tokens, about ${cost:.4}"),
+                None => println!("Estimated LLM usage: ~{tokens} tokens"),
+            }
+        }
+        if result.valid {
+            println!("Configuration is valid.");
+        }
+    }
+
+    if result.valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Configuration is invalid ({} error(s)).",
+            result.errors.len()
+        ))
+    }
+}
+
+fn handle_history(args: HistoryArgs) -> Result<(), String> {
+    let records = history::read_history(args.last).map_err(|err| {
+        format!(
+            "Failed to read run history {}: {err}",
+            history::history_path().display()
+        )
+    })?;
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&records)
+            .map_err(|err| format!("Failed to serialize history: {err}"))?;
+        println!("{json}");
+    } else if records.is_empty() {
+        println!("No runs recorded yet.");
+    } else {
+        for record in records {
+            let window = match (&record.start, &record.end) {
+                (Some(start), Some(end)) => format!("{start} → {end}"),
+                _ => "whole file".to_string(),
+            };
+            println!(
+                "{}  {}  {}  {}",
+                record.timestamp,
+                if record.success { "ok    " } else { "FAILED" },
+                record.preset.as_deref().unwrap_or("-"),
+                window
+            );
+            if let Some(output) = &record.output {
+                let messages = record
+                    .messages
+                    .map(|count| format!(" ({count} messages)"))
+                    .unwrap_or_default();
+                println!("    output: {output}{messages}");
+            }
+            if let Some(model) = &record.model {
+                println!("    model: {model}");
+            }
+            if let Some(error) = &record.error {
+                println!("    error: {error}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_scan_dates(args: ScanDatesArgs) -> Result<(), String> {
+    let runtime = (args.infile.is_none() || args.timestamp_format.is_empty()).then(|| {
+        let load = load_config();
+        for warning in load.warnings {
+            eprintln!("Warning: {warning}");
+        }
+        load.config.runtime
+    });
+    let infile = match args.infile {
+        Some(path) => path,
+        None => runtime
+            .as_ref()
+            .map(|runtime| runtime.chat_log_path.clone())
+            .unwrap_or_default(),
+    };
+    let specs = match runtime {
+        Some(runtime) if args.timestamp_format.is_empty() => runtime.timestamp_formats,
+        _ => args.timestamp_format,
+    };
+    let timestamps = TimestampFormats::from_specs(&specs)?;
+
+    let channel = args.channel.map(|value| value.to_string());
+    let dates = scan_event_dates_in_file(&infile, &timestamps, args.weekday, channel.as_deref())
+        .map_err(|err| err.to_string())?;
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&dates)
+            .map_err(|err| format!("Failed to serialize dates: {err}"))?;
+        println!("{json}");
+    } else if dates.is_empty() {
+        println!("No matching messages found in {infile}.");
+    } else {
+        println!("{:<10}  {:<9}  {:>8}", "Date", "Weekday", "Messages");
+        for entry in dates {
+            println!(
+                "{:<10}  {:<9}  {:>8}",
+                entry.date, entry.weekday, entry.messages
+            );
+        }
+    }
+    Ok(())
+}
+
 fn resolve_preset<'a>(
     presets: &'a [PresetDefinition],
     args: &cli_args::PresetShowArgs,
@@ -305,7 +958,7 @@ fn resolve_preset<'a>(
         let slug = preset_id_from_name(trimmed);
         presets
             .iter()
-            .find(|preset| preset_id_from_name(&preset.name) == slug)
+            .find(|preset| preset.id == trimmed || preset.id == slug)
             .ok_or_else(|| format!("Preset with ID '{}' not found.", trimmed))
     } else if let Some(ref raw_name) = args.name {
         let trimmed = raw_name.trim();
@@ -322,7 +975,7 @@ fn resolve_preset<'a>(
 }
 
 fn print_preset_details(preset: &PresetDefinition) {
-    let id = preset_id_from_name(&preset.name);
+    let id = &preset.id;
     println!("Name        : {}", preset.name);
     println!("ID          : {}", id);
     println!(
@@ -338,6 +991,12 @@ fn print_preset_details(preset: &PresetDefinition) {
     println!("Duration    : {} minutes", preset.duration_minutes);
     println!("File prefix : {}", preset.file_prefix);
     println!("Weeks ago   : {}", preset.default_weeks_ago);
+    if let Some(template) = &preset.filename_template {
+        println!("Filename    : {}", template);
+    }
+    if let Some(model) = &preset.llm_model {
+        println!("LLM model   : {}", model);
+    }
 }
 
 fn capitalize_ascii(input: &str) -> String {
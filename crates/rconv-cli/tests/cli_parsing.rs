@@ -1,6 +1,7 @@
 use rconv_core::config::{
-    DurationOverride, FRIDAY_6_PRESET_NAME, RuntimeOverrides, SATURDAY_PRESET_NAME,
-    TUESDAY_7_PRESET_NAME, TUESDAY_8_PRESET_NAME,
+    DurationOverride, FRIDAY_6_PRESET_ID, FRIDAY_6_PRESET_NAME, RuntimeOverrides,
+    SATURDAY_PRESET_ID, TUESDAY_7_PRESET_ID, TUESDAY_7_PRESET_NAME, TUESDAY_8_PRESET_ID,
+    TUESDAY_8_PRESET_NAME,
 };
 
 // Integration tests for CLI runtime overrides and configuration logic.
@@ -29,7 +30,7 @@ fn test_runtime_overrides_last() {
 #[test]
 fn test_runtime_overrides_preset() {
     let mut overrides = RuntimeOverrides::default();
-    overrides.active_preset = Some(TUESDAY_7_PRESET_NAME.to_string());
+    overrides.active_preset = Some(TUESDAY_7_PRESET_ID.to_string());
     assert!(!overrides.is_empty());
 }
 
@@ -176,10 +177,10 @@ fn test_preset_shortcut_flags() {
     // These would normally be tested via CLI parsing, but we can verify the constants
     // Verify all preset IDs are unique
     let preset_ids = vec![
-        SATURDAY_PRESET_NAME,
-        TUESDAY_7_PRESET_NAME,
-        TUESDAY_8_PRESET_NAME,
-        FRIDAY_6_PRESET_NAME,
+        SATURDAY_PRESET_ID,
+        TUESDAY_7_PRESET_ID,
+        TUESDAY_8_PRESET_ID,
+        FRIDAY_6_PRESET_ID,
     ];
 
     let unique_count = preset_ids
@@ -0,0 +1,483 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+// End-to-end runs of the real CLI binary against the committed fixture log. Each run gets
+// its own config directory and working directory, a fixed `--today`, and a fixed time zone
+// so the preset's window lines up with the fixture's local timestamps.
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ChatLog.log");
+
+fn run_cli(home: &Path, args: &[&str]) -> Output {
+    run_cli_with_env(home, args, &[])
+}
+
+fn run_cli_with_env(home: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    let work = home.join("work");
+    fs::create_dir_all(&work).expect("create working dir");
+    Command::new(env!("CARGO_BIN_EXE_rconv-cli"))
+        .args(args)
+        .current_dir(&work)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("CONVOCATIONS_WORKING_DIR", &work)
+        .env("TZ", "America/New_York")
+        .envs(env.iter().copied())
+        .output()
+        .expect("run rconv-cli")
+}
+
+#[test]
+fn cli_formats_fixture_log_into_default_output() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let output = run_cli(
+        home.path(),
+        &[
+            "--infile",
+            FIXTURE,
+            "--today",
+            "2025-01-06",
+            "--llm",
+            "false",
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "rconv-cli failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let work = home.path().join("work");
+    let mut written: Vec<String> = fs::read_dir(&work)
+        .expect("read working dir")
+        .map(|entry| {
+            entry
+                .expect("dir entry")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    written.sort();
+    assert_eq!(written, vec!["conv-010425.txt".to_string()]);
+
+    let contents = fs::read_to_string(work.join("conv-010425.txt")).expect("read output");
+    assert_eq!(
+        contents,
+        "Character One says, \"Welcome, everyone.\"\n\
+Character Two gestures gracefully.\n\
+Character Two says, \"The first part of a long thought and the end of it.\"\n\
+Character One says, \"Indeed it is.\"\n"
+    );
+}
+
+#[test]
+fn cli_reports_empty_window_without_writing() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let args = [
+        "--infile",
+        FIXTURE,
+        "--today",
+        "2025-02-03",
+        "--llm",
+        "false",
+    ];
+    let output = run_cli(home.path(), &args);
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Warning: No log data found"),
+        "unexpected stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_cli(home.path(), &[&args[..], &["--fail-on-empty"]].concat());
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No log data found"),
+        "unexpected stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let written = fs::read_dir(home.path().join("work"))
+        .expect("read working dir")
+        .count();
+    assert_eq!(written, 0);
+}
+
+#[test]
+fn cli_batch_continues_past_failed_entries() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let manifest = home.path().join("manifest.txt");
+    fs::write(
+        &manifest,
+        format!("# two logs\n{FIXTURE}:first.txt\nmissing.log:second.txt\n{FIXTURE}:third.txt\n"),
+    )
+    .expect("write manifest");
+
+    let output = run_cli(
+        home.path(),
+        &[
+            "--batch",
+            manifest.to_str().unwrap(),
+            "--today",
+            "2025-01-06",
+            "--llm",
+            "false",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("2 succeeded, 1 failed, 0 not run"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("FAILED  missing.log"), "{stdout}");
+
+    let work = home.path().join("work");
+    assert!(work.join("first.txt").exists());
+    assert!(!work.join("second.txt").exists());
+    assert!(work.join("third.txt").exists());
+
+    let output = run_cli(
+        home.path(),
+        &[
+            "--batch",
+            manifest.to_str().unwrap(),
+            "--fail-fast",
+            "--today",
+            "2025-01-06",
+            "--llm",
+            "false",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("1 succeeded, 1 failed, 1 not run"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn cli_config_sanitize_previews_then_writes_fixes() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let config_dir = home.path().join("config").join("convocations");
+    fs::create_dir_all(&config_dir).expect("create config dir");
+    let config_file = config_dir.join("config.toml");
+    let original = "schema_version = 1\n\n\
+[runtime]\n\
+llm_concurrency = 99\n";
+    fs::write(&config_file, original).expect("write config");
+
+    let output = run_cli(home.path(), &["config", "sanitize", "--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("[invalid-llm-setting] LLM concurrency must be between 1 and 16"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("llm_concurrency = 16"), "{stdout}");
+    assert_eq!(fs::read_to_string(&config_file).unwrap(), original);
+
+    let output = run_cli(home.path(), &["config", "sanitize"]);
+    assert!(output.status.success());
+    let written = fs::read_to_string(&config_file).unwrap();
+    assert!(written.contains("llm_concurrency = 16"), "{written}");
+
+    let output = run_cli(home.path(), &["config", "sanitize"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is already clean"));
+}
+
+#[test]
+fn cli_list_presets_prints_table_and_rejects_processing_flags() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let output = run_cli(home.path(), &["--list-presets"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("ID "), "unexpected stdout: {stdout}");
+    assert!(stdout.contains("saturday-10pm-midnight"), "{stdout}");
+    assert!(stdout.contains("builtin"), "{stdout}");
+
+    let output = run_cli(home.path(), &["--list-presets", "--infile", FIXTURE]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--list-presets cannot be combined"),
+        "unexpected stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn cli_print_config_with_dry_run_prints_toml_and_exits() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let output = run_cli(
+        home.path(),
+        &[
+            "--print-config",
+            "--dry-run",
+            "--infile",
+            FIXTURE,
+            "--merge-gap",
+            "42",
+            "--llm",
+            "false",
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "rconv-cli failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("infile = \"{FIXTURE}\"")),
+        "{stderr}"
+    );
+    assert!(stderr.contains("merge_gap_seconds = 42"), "{stderr}");
+    assert!(stderr.contains("use_llm = false"), "{stderr}");
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        fs::read_dir(home.path().join("work"))
+            .expect("read working dir")
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn cli_preset_validate_reports_each_preset_without_touching_config() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let file = home.path().join("shared.toml");
+    fs::write(
+        &file,
+        "[[presets]]\n\
+name = \"Good\"\n\
+weekday = \"friday\"\n\
+timezone = \"UTC\"\n\
+start_time = \"18:00\"\n\
+duration_minutes = 60\n\
+file_prefix = \"good\"\n\
+\n\
+[[presets]]\n\
+name = \"Bad\"\n\
+weekday = \"someday\"\n\
+timezone = \"UTC\"\n\
+start_time = \"18:00\"\n\
+duration_minutes = 60\n\
+file_prefix = \"bad\"\n",
+    )
+    .expect("write preset file");
+    let path = file.to_string_lossy();
+
+    let output = run_cli(home.path(), &["preset", "validate", &path]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("PASS  Good"), "{stdout}");
+    assert!(stdout.contains("FAIL  Bad"), "{stdout}");
+    assert!(stdout.contains("unknown weekday 'someday'"), "{stdout}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 of 2 preset(s)"),
+        "unexpected stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_cli(home.path(), &["preset", "validate", "--json", &path]);
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON report");
+    assert_eq!(report[0]["ok"], true);
+    assert_eq!(report[1]["name"], "Bad");
+    assert_eq!(report[1]["ok"], false);
+
+    assert!(!home.path().join("config/convocations/config.toml").exists());
+}
+
+#[test]
+fn cli_preset_next_shows_the_upcoming_start_in_the_preset_timezone() {
+    let home = tempfile::tempdir().expect("tempdir");
+
+    let output = run_cli(
+        home.path(),
+        &["preset", "next", "--id", "saturday-10pm-midnight"],
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Saturday"), "{stdout}");
+    assert!(stdout.contains("Sat "), "{stdout}");
+    assert!(stdout.contains("22:00 E"), "{stdout}");
+
+    let output = run_cli(home.path(), &["preset", "next"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 4);
+}
+
+#[test]
+fn cli_legacy_settings_are_migrated_once_and_renamed() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let config_dir = home.path().join("config/convocations");
+    fs::create_dir_all(&config_dir).expect("create config dir");
+    let legacy = config_dir.join("settings.json");
+    let settings = rconv_core::ConvocationsConfig {
+        rsm7: true,
+        ..Default::default()
+    };
+    fs::write(&legacy, serde_json::to_string(&settings).unwrap()).expect("write legacy");
+
+    let output = run_cli_with_env(
+        home.path(),
+        &["preset", "list"],
+        &[("CONVOCATIONS_IGNORE_LEGACY", "1")],
+    );
+    assert!(output.status.success());
+    assert!(legacy.exists());
+    assert!(!config_dir.join("config.toml").exists());
+
+    let output = run_cli(home.path(), &["preset", "list"]);
+    assert!(output.status.success());
+    assert!(!legacy.exists(), "legacy settings should be renamed");
+    assert!(config_dir.join("settings.json.migrated").exists());
+    let migrated = fs::read_to_string(config_dir.join("config.toml")).expect("config.toml");
+    assert!(migrated.contains("tuesday-7pm"), "{migrated}");
+}
+
+#[test]
+fn cli_infile_glob_picks_the_newest_matching_log() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let logs = home.path().join("logs");
+    fs::create_dir_all(&logs).expect("create logs dir");
+    let older = logs.join("ChatLog-old.log");
+    fs::write(&older, "").expect("write older log");
+    fs::File::options()
+        .write(true)
+        .open(&older)
+        .and_then(|file| file.set_modified(std::time::SystemTime::UNIX_EPOCH))
+        .expect("backdate older log");
+    fs::copy(FIXTURE, logs.join("ChatLog-new.log")).expect("copy fixture");
+    let pattern = logs.join("ChatLog*.log");
+    let pattern = pattern.to_str().expect("utf-8 path");
+
+    let output = run_cli(
+        home.path(),
+        &[
+            "--infile-glob",
+            pattern,
+            "--today",
+            "2025-01-06",
+            "--llm",
+            "false",
+        ],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "rconv-cli failed: {stderr}");
+    assert!(stderr.contains("ChatLog-new.log"), "{stderr}");
+    assert!(home.path().join("work/conv-010425.txt").exists());
+
+    let missing = logs.join("Nothing*.log");
+    let output = run_cli(
+        home.path(),
+        &["--infile-glob", missing.to_str().expect("utf-8 path")],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No files match"));
+}
+
+#[test]
+fn cli_validate_reports_problems_as_json_and_fails() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let output = run_cli(
+        home.path(),
+        &[
+            "--infile",
+            FIXTURE,
+            "--today",
+            "2025-01-06",
+            "validate",
+            "--json",
+        ],
+    );
+    assert!(output.status.success());
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("validation JSON");
+    assert_eq!(result["valid"], true);
+    assert!(
+        result["outfile"]
+            .as_str()
+            .is_some_and(|outfile| outfile.ends_with("conv-010425.txt")),
+        "{result}"
+    );
+    assert!(
+        result["estimated_tokens"]
+            .as_u64()
+            .is_some_and(|tokens| tokens > 0),
+        "{result}"
+    );
+
+    let output = run_cli(
+        home.path(),
+        &[
+            "--infile",
+            FIXTURE,
+            "--output-directory",
+            FIXTURE,
+            "--rsm7",
+            "--start",
+            "2025-01-04T21:00",
+            "validate",
+            "--json",
+        ],
+    );
+    assert!(!output.status.success());
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("validation JSON");
+    assert_eq!(result["valid"], false);
+    assert_eq!(
+        result["errors"].as_array().map(Vec::len),
+        Some(2),
+        "{result}"
+    );
+    assert!(
+        result["field_errors"]["output_directory"]
+            .as_str()
+            .is_some_and(|error| error.contains("is a file")),
+        "{result}"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Configuration is invalid"));
+}
+
+#[test]
+fn cli_history_lists_completed_and_failed_runs() {
+    let home = tempfile::tempdir().expect("tempdir");
+    let output = run_cli(home.path(), &["history"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No runs recorded yet."));
+
+    for today in ["2025-01-06", "2025-02-03"] {
+        run_cli(
+            home.path(),
+            &["--infile", FIXTURE, "--today", today, "--llm", "false"],
+        );
+    }
+
+    let output = run_cli(home.path(), &["history", "--json"]);
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout).expect("history JSON");
+    let records = records.as_array().expect("array of records");
+    assert_eq!(records.len(), 2, "{records:?}");
+    assert_eq!(records[0]["success"], true);
+    assert_eq!(records[0]["messages"], 4);
+    assert_eq!(records[0]["start"], "2025-01-04T22:00");
+    assert!(
+        records[0]["output"]
+            .as_str()
+            .is_some_and(|output| output.ends_with("conv-010425.txt"))
+    );
+    assert_eq!(records[1]["success"], false);
+    assert!(
+        records[1]["error"]
+            .as_str()
+            .is_some_and(|error| error.contains("No log data found"))
+    );
+
+    let output = run_cli(home.path(), &["history", "--last", "1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("FAILED"), "{stdout}");
+    assert!(!stdout.contains("conv-010425.txt"), "{stdout}");
+}
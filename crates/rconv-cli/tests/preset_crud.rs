@@ -1,6 +1,6 @@
 use rconv_core::config::{
-    FRIDAY_6_PRESET_NAME, FileConfig, PresetDefinition, RuntimePreferences, SATURDAY_PRESET_NAME,
-    TUESDAY_7_PRESET_NAME, TUESDAY_8_PRESET_NAME, UiPreferences,
+    FRIDAY_6_PRESET_NAME, FileConfig, PresetDefinition, RuntimePreferences, SATURDAY_PRESET_ID,
+    SATURDAY_PRESET_NAME, TUESDAY_7_PRESET_NAME, TUESDAY_8_PRESET_NAME, UiPreferences,
 };
 
 #[test]
@@ -77,6 +77,7 @@ fn test_preset_create() {
 
     // Create a new custom preset
     let custom_preset = PresetDefinition {
+        id: "custom-event".to_string(),
         name: "Custom Event".to_string(),
         weekday: "wednesday".to_string(),
         timezone: "America/New_York".to_string(),
@@ -84,6 +85,8 @@ fn test_preset_create() {
         duration_minutes: 90,
         file_prefix: "custom".to_string(),
         default_weeks_ago: 0,
+        filename_template: None,
+        llm_model: None,
         builtin: false,
     };
 
@@ -149,6 +152,7 @@ fn test_preset_delete() {
 
     // Add a custom preset
     config.presets.push(PresetDefinition {
+        id: "deletable".to_string(),
         name: "Deletable".to_string(),
         weekday: "thursday".to_string(),
         timezone: "America/New_York".to_string(),
@@ -156,6 +160,8 @@ fn test_preset_delete() {
         duration_minutes: 60,
         file_prefix: "del".to_string(),
         default_weeks_ago: 0,
+        filename_template: None,
+        llm_model: None,
         builtin: false,
     });
 
@@ -187,7 +193,7 @@ fn test_runtime_defaults() {
         "Default chat log path should be correct"
     );
     assert_eq!(
-        runtime.active_preset, SATURDAY_PRESET_NAME,
+        runtime.active_preset, SATURDAY_PRESET_ID,
         "Default preset should be Saturday"
     );
     assert_eq!(runtime.weeks_ago, 0, "Default weeks_ago should be 0");
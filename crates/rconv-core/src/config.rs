@@ -1,15 +1,20 @@
-use crate::curator::AUTO_SENTINEL;
-use crate::runtime::ConvocationsConfig;
+use crate::curator::{AUTO_SENTINEL, AutoStrategy};
+use crate::runtime::{ConvocationsConfig, OutputFormat};
 use crate::secret_store::{self, SecretReference, SecretStoreError};
+use crate::timestamp::{DEFAULT_TIMESTAMP_FORMAT, validate_timestamp_format};
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
 use dirs::config_dir;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const CONFIG_DIR_NAME: &str = "convocations";
 const CONFIG_FILE_NAME: &str = "config.toml";
 const LEGACY_SETTINGS_FILE_NAME: &str = "settings.json";
+const MIGRATED_LEGACY_SETTINGS_FILE_NAME: &str = "settings.json.migrated";
+/// Set to `1` to ignore `settings.json` when `config.toml` is missing.
+const IGNORE_LEGACY_ENV: &str = "CONVOCATIONS_IGNORE_LEGACY";
 const CURRENT_SCHEMA_VERSION: u32 = 1;
 pub const SATURDAY_PRESET_NAME: &str = "Saturday 10pm-midnight";
 pub const TUESDAY_7_PRESET_NAME: &str = "Tuesday 7pm";
@@ -20,15 +25,158 @@ pub const TUESDAY_7_PRESET_ID: &str = "tuesday-7pm";
 pub const TUESDAY_8_PRESET_ID: &str = "tuesday-8pm";
 pub const FRIDAY_6_PRESET_ID: &str = "friday-6pm";
 pub const DEFAULT_OPENROUTER_MODEL: &str = "google/gemini-2.5-flash-lite";
+/// Minimum share of a chunk's lines and characters an LLM correction must keep.
+pub const DEFAULT_LLM_MIN_RETAIN_RATIO: f64 = 0.5;
+/// Share of a model's context window kept free when sizing LLM correction chunks.
+pub const DEFAULT_CONTEXT_SAFETY_MARGIN: f64 = 0.1;
+/// Line introducing the transcript chunk in each LLM correction prompt.
+pub const DEFAULT_PROMPT_INPUT_MARKER: &str = "Text to correct:";
+/// Line closing each LLM correction prompt, after which the model writes its reply.
+pub const DEFAULT_PROMPT_OUTPUT_MARKER: &str = "Corrected text:";
+/// Rendered transcript lines above which a run stops before the LLM stage unless forced.
+pub const DEFAULT_MAX_OUTPUT_LINES: u64 = 20_000;
+/// Rendered transcript size in bytes above which a run stops before the LLM stage unless forced.
+pub const DEFAULT_MAX_OUTPUT_BYTES: u64 = 2 * 1024 * 1024;
+/// Records kept in `history.jsonl` before it is rotated.
+pub const DEFAULT_HISTORY_MAX_RECORDS: usize = 500;
+/// Upper bound on protected terms injected into the LLM prompt.
+pub const MAX_PROTECTED_TERMS: usize = 50;
+/// Upper bound on glossary entries injected into the LLM prompt.
+pub const MAX_GLOSSARY_ENTRIES: usize = 50;
+/// Number of LLM chunk requests kept in flight at once.
+pub const DEFAULT_LLM_CONCURRENCY: usize = 2;
+/// Upper bound for `llm_concurrency`.
+pub const MAX_LLM_CONCURRENCY: usize = 16;
+/// Extra attempts for a failed LLM chunk request.
+pub const DEFAULT_LLM_MAX_RETRIES: u32 = 2;
+/// Upper bound for `llm_max_retries`.
+pub const MAX_LLM_MAX_RETRIES: u32 = 10;
+/// Upper bound for `llm_timeout_secs`.
+pub const MAX_LLM_TIMEOUT_SECS: u64 = 600;
+/// Chunk requests above which the LLM stage stops the run unless forced.
+pub const DEFAULT_LLM_MAX_CHUNKS: usize = 50;
 
 /// Result returned by [`load_config`], capturing the source and any non-fatal issues.
 #[derive(Debug, Clone)]
 pub struct ConfigLoadResult {
     pub config: FileConfig,
-    pub warnings: Vec<String>,
+    pub warnings: Vec<ConfigWarning>,
     pub source: ConfigSource,
 }
 
+/// Category of a [`ConfigWarning`]. The kebab-case names (see [`WarningCode::as_str`]) are
+/// stable, so callers can match on them and users can list them in
+/// `runtime.silenced_warnings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCode {
+    /// A configuration file could not be read or parsed; defaults were used instead.
+    UnreadableConfig,
+    /// Settings were imported from the legacy `settings.json`.
+    LegacyMigration,
+    UnknownSchemaVersion,
+    DuplicatePreset,
+    /// A preset referenced by id or name does not exist.
+    MissingPreset,
+    /// A preset field was invalid and was reset, or the preset was removed.
+    InvalidPreset,
+    /// Several presets share a schedule.
+    OverlappingPresets,
+    InvalidDuration,
+    /// An LLM timeout, concurrency, retry or retain-ratio setting was out of range.
+    InvalidLlmSetting,
+    InvalidTimestampFormat,
+    InvalidOocPattern,
+    /// `channel_styles` listed no usable channels, or `channel_pattern` was not a valid regex,
+    /// and was reset.
+    InvalidChannelStyle,
+    TooManyProtectedTerms,
+    TooManyGlossaryEntries,
+    /// The runtime preferences contradicted each other and were reset.
+    InvalidRuntimeSettings,
+    /// Moving the API key into or out of secure storage failed or happened.
+    SecretStorage,
+    /// The saved OpenRouter API key could not be read, so AI corrections will be skipped.
+    MissingApiKey,
+    /// An option that only applies to AI corrections was set while they are off.
+    LlmOnlyOption,
+}
+
+impl WarningCode {
+    pub const ALL: [WarningCode; 18] = [
+        WarningCode::UnreadableConfig,
+        WarningCode::LegacyMigration,
+        WarningCode::UnknownSchemaVersion,
+        WarningCode::DuplicatePreset,
+        WarningCode::MissingPreset,
+        WarningCode::InvalidPreset,
+        WarningCode::OverlappingPresets,
+        WarningCode::InvalidDuration,
+        WarningCode::InvalidLlmSetting,
+        WarningCode::InvalidTimestampFormat,
+        WarningCode::InvalidOocPattern,
+        WarningCode::InvalidChannelStyle,
+        WarningCode::TooManyProtectedTerms,
+        WarningCode::TooManyGlossaryEntries,
+        WarningCode::InvalidRuntimeSettings,
+        WarningCode::SecretStorage,
+        WarningCode::MissingApiKey,
+        WarningCode::LlmOnlyOption,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WarningCode::UnreadableConfig => "unreadable-config",
+            WarningCode::LegacyMigration => "legacy-migration",
+            WarningCode::UnknownSchemaVersion => "unknown-schema-version",
+            WarningCode::DuplicatePreset => "duplicate-preset",
+            WarningCode::MissingPreset => "missing-preset",
+            WarningCode::InvalidPreset => "invalid-preset",
+            WarningCode::OverlappingPresets => "overlapping-presets",
+            WarningCode::InvalidDuration => "invalid-duration",
+            WarningCode::InvalidLlmSetting => "invalid-llm-setting",
+            WarningCode::InvalidTimestampFormat => "invalid-timestamp-format",
+            WarningCode::InvalidOocPattern => "invalid-ooc-pattern",
+            WarningCode::InvalidChannelStyle => "invalid-channel-style",
+            WarningCode::TooManyProtectedTerms => "too-many-protected-terms",
+            WarningCode::TooManyGlossaryEntries => "too-many-glossary-entries",
+            WarningCode::InvalidRuntimeSettings => "invalid-runtime-settings",
+            WarningCode::SecretStorage => "secret-storage",
+            WarningCode::MissingApiKey => "missing-api-key",
+            WarningCode::LlmOnlyOption => "llm-only-option",
+        }
+    }
+}
+
+impl std::fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A non-fatal problem found while loading or converting configuration. Displays as the
+/// message alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigWarning {
+    pub code: WarningCode,
+    pub message: String,
+}
+
+impl ConfigWarning {
+    pub fn new(code: WarningCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 /// Indicates where the configuration was loaded from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfigSource {
@@ -44,14 +192,48 @@ pub enum ConfigSource {
 #[derive(Debug)]
 pub enum ConfigError {
     Io(std::io::Error),
+    /// The configuration directory or file could not be written because of permissions or a
+    /// read-only filesystem.
+    NotWritable {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     Ser(toml::ser::Error),
     Secret(SecretStoreError),
 }
 
+impl ConfigError {
+    /// Wrap a write failure on `path`, singling out permission and read-only errors.
+    fn write(path: &Path, err: std::io::Error) -> Self {
+        if is_not_writable(&err) {
+            Self::NotWritable {
+                path: path.to_path_buf(),
+                source: err,
+            }
+        } else {
+            Self::Io(err)
+        }
+    }
+}
+
+/// Whether `err` means the location cannot be written at all, as opposed to a transient failure.
+fn is_not_writable(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem
+    )
+}
+
 impl std::fmt::Display for ConfigError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ConfigError::Io(err) => write!(f, "IO error: {err}"),
+            ConfigError::NotWritable { path, source } => write!(
+                f,
+                "Cannot write {}: {source}. The configuration directory is read-only or you do \
+                 not have permission to write to it; check its permissions and free space.",
+                path.display()
+            ),
             ConfigError::Ser(err) => write!(f, "TOML serialization error: {err}"),
             ConfigError::Secret(err) => write!(f, "Secret storage error: {err}"),
         }
@@ -91,6 +273,161 @@ pub struct FileConfig {
     pub presets: Vec<PresetDefinition>,
 }
 
+/// Find a preset by stable id, falling back to an exact display-name match so that
+/// configurations written before ids existed keep resolving.
+pub fn find_preset<'a>(presets: &'a [PresetDefinition], key: &str) -> Option<&'a PresetDefinition> {
+    presets
+        .iter()
+        .find(|preset| preset.id == key)
+        .or_else(|| presets.iter().find(|preset| preset.name == key))
+}
+
+/// Warnings for presets that share a schedule (weekday, start time and timezone), one per
+/// group of colliding presets, in preset order.
+pub fn overlapping_preset_warnings(presets: &[PresetDefinition]) -> Vec<String> {
+    let mut groups: Vec<(String, Vec<&str>)> = Vec::new();
+    for preset in presets {
+        let schedule = format!(
+            "{} {} {}",
+            preset.weekday.trim().to_ascii_lowercase(),
+            preset.start_time.trim(),
+            preset.timezone.trim()
+        );
+        match groups
+            .iter_mut()
+            .find(|(existing, _)| *existing == schedule)
+        {
+            Some((_, names)) => names.push(&preset.name),
+            None => groups.push((schedule, vec![&preset.name])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(schedule, names)| {
+            format!(
+                "Presets {} share the same schedule ({}); only the active preset applies.",
+                names
+                    .iter()
+                    .map(|name| format!("'{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                schedule
+            )
+        })
+        .collect()
+}
+
+/// Read-only health check of a preset collection: overlapping schedules plus per-preset
+/// problems such as an unknown weekday or timezone, a malformed start time, a zero duration,
+/// an empty file prefix or an unusable filename template.
+pub fn preset_diagnostics(presets: &[PresetDefinition]) -> Vec<String> {
+    let mut findings = overlapping_preset_warnings(presets);
+    for preset in presets {
+        findings.extend(preset_problems(preset));
+    }
+    findings
+}
+
+/// Problems with a single preset's fields: an empty name, an unknown weekday or timezone, a
+/// malformed start time, a zero duration, an empty file prefix, or an unusable filename
+/// template or model slug.
+pub fn preset_problems(preset: &PresetDefinition) -> Vec<String> {
+    let mut findings = Vec::new();
+    if preset.name.trim().is_empty() {
+        findings.push("A preset has an empty name.".to_string());
+    }
+    if preset.weekday.trim().parse::<Weekday>().is_err() {
+        findings.push(format!(
+            "Preset '{}' has unknown weekday '{}'.",
+            preset.name, preset.weekday
+        ));
+    }
+    if preset.timezone.trim().parse::<chrono_tz::Tz>().is_err() {
+        findings.push(format!(
+            "Preset '{}' has invalid timezone '{}'.",
+            preset.name, preset.timezone
+        ));
+    }
+    if NaiveTime::parse_from_str(preset.start_time.trim(), "%H:%M").is_err() {
+        findings.push(format!(
+            "Preset '{}' has start_time '{}' that is not in HH:MM format.",
+            preset.name, preset.start_time
+        ));
+    }
+    if preset.duration_minutes == 0 {
+        findings.push(format!("Preset '{}' has a zero duration.", preset.name));
+    }
+    if preset.file_prefix.trim().is_empty() {
+        findings.push(format!(
+            "Preset '{}' has an empty file_prefix.",
+            preset.name
+        ));
+    }
+    if let Some(Err(err)) = preset
+        .filename_template
+        .as_deref()
+        .map(validate_filename_template)
+    {
+        findings.push(format!("Preset '{}': {}.", preset.name, err));
+    }
+    if let Some(Err(err)) = preset.llm_model.as_deref().map(validate_model_slug) {
+        findings.push(format!("Preset '{}': {}.", preset.name, err));
+    }
+    findings
+}
+
+/// A standalone preset file: `[[presets]]` tables in the same shape as `config.toml`.
+#[derive(Debug, Deserialize)]
+struct PresetFile {
+    #[serde(default)]
+    presets: Vec<PresetDefinition>,
+}
+
+/// Parse the `[[presets]]` tables of a shared preset file without validating them.
+pub fn parse_preset_file(raw: &str) -> Result<Vec<PresetDefinition>, String> {
+    toml::from_str::<PresetFile>(raw)
+        .map(|file| file.presets)
+        .map_err(|err| err.to_string())
+}
+
+/// Parse a glossary from tab-separated `term<TAB>definition` lines. Blank lines and lines
+/// starting with `#` are skipped.
+pub fn parse_glossary_tsv(raw: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for (index, line) in raw.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Some((term, definition)) = line.split_once('\t') else {
+            return Err(format!(
+                "line {}: expected a term and a definition separated by a tab",
+                index + 1
+            ));
+        };
+        let term = term.trim();
+        if term.is_empty() {
+            return Err(format!("line {}: the term is empty", index + 1));
+        }
+        entries.push((term.to_string(), definition.trim().to_string()));
+    }
+    Ok(entries)
+}
+
+/// Derive an id for a preset named `name` that does not collide with any of `presets`.
+pub fn unique_preset_id(presets: &[PresetDefinition], name: &str) -> String {
+    let base = preset_id_from_name(name);
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while presets.iter().any(|preset| preset.id == id) {
+        id = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    id
+}
+
 pub fn preset_id_from_name(name: &str) -> String {
     let mut id = String::with_capacity(name.len());
     let mut previous_dash = false;
@@ -172,8 +509,16 @@ pub struct RuntimePreferences {
     pub use_ai_corrections: bool,
     #[serde(default)]
     pub keep_original_output: bool,
+    #[serde(default)]
+    pub kept_originals_dir: Option<String>,
     #[serde(default = "RuntimePreferences::default_show_diff")]
     pub show_diff: bool,
+    #[serde(default)]
+    pub diff_context: Option<usize>,
+    #[serde(default)]
+    pub diff_summary: bool,
+    #[serde(default = "RuntimePreferences::default_final_newline")]
+    pub final_newline: bool,
     #[serde(default = "RuntimePreferences::default_cleanup_enabled")]
     pub cleanup_enabled: bool,
     #[serde(default = "RuntimePreferences::default_format_dialogue_enabled")]
@@ -192,6 +537,101 @@ pub struct RuntimePreferences {
     pub output_target: OutputTarget,
     #[serde(default)]
     pub output_directory_override: Option<String>,
+    #[serde(default)]
+    pub merge_gap_seconds: Option<u64>,
+    #[serde(default = "RuntimePreferences::default_drop_empty_messages")]
+    pub drop_empty_messages: bool,
+    #[serde(default = "RuntimePreferences::default_keep_parentheticals")]
+    pub keep_parentheticals: bool,
+    #[serde(default)]
+    pub section_by_channel: bool,
+    #[serde(default)]
+    pub dedupe_consecutive: bool,
+    #[serde(default)]
+    pub with_index: bool,
+    #[serde(default)]
+    pub parallel_parse: bool,
+    #[serde(default)]
+    pub section_headings: SectionHeadings,
+    #[serde(default = "default_timestamp_formats")]
+    pub timestamp_formats: Vec<String>,
+    #[serde(default = "default_ooc_line_patterns")]
+    pub ooc_line_patterns: Vec<OocLinePattern>,
+    #[serde(default)]
+    pub ooc_mode: OocMode,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    #[serde(default)]
+    pub say_punctuation: SayPunctuation,
+    #[serde(default)]
+    pub output_quotes: OutputQuotes,
+    #[serde(default)]
+    pub ellipsis_style: EllipsisStyle,
+    #[serde(default = "default_channel_styles")]
+    pub channel_styles: BTreeMap<String, ChannelStyle>,
+    #[serde(default = "default_channel_pattern")]
+    pub channel_pattern: String,
+    #[serde(default = "default_channel_names")]
+    pub channel_names: BTreeMap<String, String>,
+    #[serde(default = "RuntimePreferences::default_llm_timeout_secs")]
+    pub llm_timeout_secs: u64,
+    #[serde(default = "RuntimePreferences::default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    #[serde(default = "RuntimePreferences::default_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+    #[serde(default = "RuntimePreferences::default_llm_temperature")]
+    pub llm_temperature: f32,
+    #[serde(default)]
+    pub llm_disable_reasoning: bool,
+    #[serde(default = "RuntimePreferences::default_llm_concurrency")]
+    pub llm_concurrency: usize,
+    #[serde(default = "RuntimePreferences::default_llm_max_retries")]
+    pub llm_max_retries: u32,
+    #[serde(default = "RuntimePreferences::default_llm_max_chunks")]
+    pub llm_max_chunks: usize,
+    #[serde(default = "RuntimePreferences::default_llm_min_retain_ratio")]
+    pub llm_min_retain_ratio: f64,
+    #[serde(default = "RuntimePreferences::default_llm_retry_failed")]
+    pub llm_retry_failed: bool,
+    #[serde(default = "RuntimePreferences::default_context_safety_margin")]
+    pub context_safety_margin: f64,
+    #[serde(default = "RuntimePreferences::default_max_output_lines")]
+    pub max_output_lines: u64,
+    #[serde(default = "RuntimePreferences::default_max_output_bytes")]
+    pub max_output_bytes: u64,
+    #[serde(default = "RuntimePreferences::default_record_history")]
+    pub record_history: bool,
+    #[serde(default = "RuntimePreferences::default_history_max_records")]
+    pub history_max_records: usize,
+    #[serde(default = "RuntimePreferences::default_openrouter_referer")]
+    pub openrouter_referer: String,
+    #[serde(default = "RuntimePreferences::default_openrouter_title")]
+    pub openrouter_title: String,
+    #[serde(default)]
+    pub openrouter_extra_headers: BTreeMap<String, String>,
+    #[serde(default = "RuntimePreferences::default_prompt_input_marker")]
+    pub prompt_input_marker: String,
+    #[serde(default = "RuntimePreferences::default_prompt_output_marker")]
+    pub prompt_output_marker: String,
+    #[serde(default)]
+    pub prompt_input_prefix: String,
+    #[serde(default)]
+    pub prompt_input_suffix: String,
+    #[serde(default)]
+    pub header_template: Option<String>,
+    #[serde(default)]
+    pub protected_terms: Vec<String>,
+    /// In-world terms and their definitions or spellings, given to the model as a reference.
+    #[serde(default)]
+    pub glossary: Vec<(String, String)>,
+    #[serde(default)]
+    pub model_chain: Vec<String>,
+    #[serde(default)]
+    pub auto_strategy: Option<AutoStrategy>,
+    /// Warning codes (see [`WarningCode::as_str`]) that loading and conversion should not
+    /// report. Unknown names are ignored.
+    #[serde(default)]
+    pub silenced_warnings: Vec<String>,
 }
 
 impl Default for RuntimePreferences {
@@ -203,7 +643,11 @@ impl Default for RuntimePreferences {
             dry_run: false,
             use_ai_corrections: true,
             keep_original_output: false,
+            kept_originals_dir: None,
             show_diff: true,
+            diff_context: None,
+            diff_summary: false,
+            final_newline: Self::default_final_newline(),
             cleanup_enabled: true,
             format_dialogue_enabled: true,
             outfile_override: None,
@@ -213,6 +657,52 @@ impl Default for RuntimePreferences {
             free_models_only: false,
             output_target: OutputTarget::default(),
             output_directory_override: None,
+            merge_gap_seconds: None,
+            drop_empty_messages: Self::default_drop_empty_messages(),
+            keep_parentheticals: Self::default_keep_parentheticals(),
+            section_by_channel: false,
+            dedupe_consecutive: false,
+            with_index: false,
+            parallel_parse: false,
+            section_headings: SectionHeadings::default(),
+            header_template: None,
+            protected_terms: Vec::new(),
+            glossary: Vec::new(),
+            model_chain: Vec::new(),
+            auto_strategy: None,
+            silenced_warnings: Vec::new(),
+            timestamp_formats: default_timestamp_formats(),
+            ooc_line_patterns: default_ooc_line_patterns(),
+            ooc_mode: OocMode::default(),
+            output_format: OutputFormat::default(),
+            say_punctuation: SayPunctuation::default(),
+            output_quotes: OutputQuotes::default(),
+            ellipsis_style: EllipsisStyle::default(),
+            channel_styles: default_channel_styles(),
+            channel_pattern: default_channel_pattern(),
+            channel_names: default_channel_names(),
+            llm_timeout_secs: Self::default_llm_timeout_secs(),
+            connect_timeout_secs: Self::default_connect_timeout_secs(),
+            read_timeout_secs: Self::default_read_timeout_secs(),
+            llm_temperature: Self::default_llm_temperature(),
+            llm_disable_reasoning: false,
+            llm_concurrency: Self::default_llm_concurrency(),
+            llm_max_retries: Self::default_llm_max_retries(),
+            llm_max_chunks: Self::default_llm_max_chunks(),
+            llm_min_retain_ratio: Self::default_llm_min_retain_ratio(),
+            context_safety_margin: Self::default_context_safety_margin(),
+            llm_retry_failed: Self::default_llm_retry_failed(),
+            max_output_lines: Self::default_max_output_lines(),
+            max_output_bytes: Self::default_max_output_bytes(),
+            record_history: Self::default_record_history(),
+            history_max_records: Self::default_history_max_records(),
+            openrouter_referer: Self::default_openrouter_referer(),
+            openrouter_title: Self::default_openrouter_title(),
+            openrouter_extra_headers: BTreeMap::new(),
+            prompt_input_marker: Self::default_prompt_input_marker(),
+            prompt_output_marker: Self::default_prompt_output_marker(),
+            prompt_input_prefix: String::new(),
+            prompt_input_suffix: String::new(),
         }
     }
 }
@@ -223,7 +713,7 @@ impl RuntimePreferences {
     }
 
     fn default_active_preset() -> String {
-        SATURDAY_PRESET_NAME.to_string()
+        SATURDAY_PRESET_ID.to_string()
     }
 
     const fn default_use_ai_corrections() -> bool {
@@ -242,6 +732,90 @@ impl RuntimePreferences {
         true
     }
 
+    const fn default_llm_timeout_secs() -> u64 {
+        crate::openrouter::DEFAULT_COMPLETION_TIMEOUT_SECS
+    }
+
+    const fn default_connect_timeout_secs() -> u64 {
+        crate::openrouter::DEFAULT_CONNECT_TIMEOUT_SECS
+    }
+
+    const fn default_read_timeout_secs() -> u64 {
+        crate::openrouter::DEFAULT_READ_TIMEOUT_SECS
+    }
+
+    const fn default_llm_temperature() -> f32 {
+        crate::openrouter::DEFAULT_TEMPERATURE
+    }
+
+    const fn default_llm_concurrency() -> usize {
+        DEFAULT_LLM_CONCURRENCY
+    }
+
+    const fn default_llm_max_retries() -> u32 {
+        DEFAULT_LLM_MAX_RETRIES
+    }
+
+    const fn default_llm_max_chunks() -> usize {
+        DEFAULT_LLM_MAX_CHUNKS
+    }
+
+    const fn default_llm_min_retain_ratio() -> f64 {
+        DEFAULT_LLM_MIN_RETAIN_RATIO
+    }
+
+    const fn default_context_safety_margin() -> f64 {
+        DEFAULT_CONTEXT_SAFETY_MARGIN
+    }
+
+    const fn default_final_newline() -> bool {
+        true
+    }
+
+    const fn default_drop_empty_messages() -> bool {
+        true
+    }
+
+    const fn default_keep_parentheticals() -> bool {
+        true
+    }
+
+    const fn default_llm_retry_failed() -> bool {
+        true
+    }
+
+    const fn default_max_output_lines() -> u64 {
+        DEFAULT_MAX_OUTPUT_LINES
+    }
+
+    const fn default_max_output_bytes() -> u64 {
+        DEFAULT_MAX_OUTPUT_BYTES
+    }
+
+    const fn default_record_history() -> bool {
+        true
+    }
+
+    const fn default_history_max_records() -> usize {
+        DEFAULT_HISTORY_MAX_RECORDS
+    }
+
+    fn default_openrouter_referer() -> String {
+        crate::openrouter::DEFAULT_APP_REFERER.to_string()
+    }
+
+    fn default_openrouter_title() -> String {
+        crate::openrouter::DEFAULT_APP_TITLE.to_string()
+    }
+
+    fn default_prompt_input_marker() -> String {
+        DEFAULT_PROMPT_INPUT_MARKER.to_string()
+    }
+
+    fn default_prompt_output_marker() -> String {
+        DEFAULT_PROMPT_OUTPUT_MARKER.to_string()
+    }
+
     pub fn set_openrouter_api_key(&mut self, api_key: &str) -> Result<(), SecretStoreError> {
         let trimmed = api_key.trim();
         if trimmed.is_empty() {
@@ -295,6 +869,13 @@ impl RuntimePreferences {
         }
     }
 
+    /// True when `code` is listed in `silenced_warnings`.
+    pub fn silences(&self, code: WarningCode) -> bool {
+        self.silenced_warnings
+            .iter()
+            .any(|name| name.trim().eq_ignore_ascii_case(code.as_str()))
+    }
+
     pub fn has_openrouter_api_key(&self) -> bool {
         matches!(self.openrouter_api_key, Some(SecretValue::Reference(_)))
     }
@@ -313,6 +894,207 @@ impl Default for OutputTarget {
     }
 }
 
+/// How cleanup handles inline OOC segments such as `((brb))` inside a message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OocMode {
+    /// Delete the segments.
+    #[default]
+    Strip,
+    /// Leave the segments in the text unchanged.
+    KeepInline,
+    /// Replace each segment with a numbered marker and list its text after the message.
+    Footnote,
+}
+
+/// Punctuation between "says" and the quoted speech, e.g. `Name says, "..."`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SayPunctuation {
+    #[default]
+    Comma,
+    Colon,
+    None,
+}
+
+impl SayPunctuation {
+    /// The verb with its trailing punctuation: `says,`, `says:` or `says`.
+    pub fn says(self) -> &'static str {
+        match self {
+            SayPunctuation::Comma => "says,",
+            SayPunctuation::Colon => "says:",
+            SayPunctuation::None => "says",
+        }
+    }
+}
+
+/// How messages from a chat channel are rendered. Channels without a style are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChannelStyle {
+    /// `Name says, "text"`.
+    Say,
+    /// `Name text`, or speech when the text is quoted.
+    Emote,
+    /// The message text exactly as logged, without the speaker's name.
+    Raw,
+}
+
+/// Headings written above each section when `section_by_channel` groups messages by their
+/// channel style.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SectionHeadings {
+    pub say: String,
+    pub emote: String,
+    pub raw: String,
+}
+
+impl SectionHeadings {
+    /// Heading for messages rendered with `style`.
+    pub fn heading(&self, style: ChannelStyle) -> &str {
+        match style {
+            ChannelStyle::Say => &self.say,
+            ChannelStyle::Emote => &self.emote,
+            ChannelStyle::Raw => &self.raw,
+        }
+    }
+}
+
+impl Default for SectionHeadings {
+    fn default() -> Self {
+        Self {
+            say: "Dialogue".to_string(),
+            emote: "Actions".to_string(),
+            raw: "Other".to_string(),
+        }
+    }
+}
+
+/// Quote characters written around speech in the transcript. Input is always normalized to
+/// straight quotes first; this only affects output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputQuotes {
+    #[default]
+    Straight,
+    Curly,
+}
+
+impl OutputQuotes {
+    /// Opening speech quote.
+    pub fn open(self) -> char {
+        match self {
+            OutputQuotes::Straight => '"',
+            OutputQuotes::Curly => '\u{201C}',
+        }
+    }
+
+    /// Closing speech quote.
+    pub fn close(self) -> char {
+        match self {
+            OutputQuotes::Straight => '"',
+            OutputQuotes::Curly => '\u{201D}',
+        }
+    }
+}
+
+/// How runs of dots and the `…` character are written in the transcript.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EllipsisStyle {
+    /// Exactly three dots: `...`.
+    #[default]
+    ThreeDots,
+    /// The single ellipsis character `…`.
+    Unicode,
+    /// Leave dots as they came out of cleanup.
+    AsIs,
+}
+
+/// Marks a whole message as out-of-character. A message matches when it starts with `prefix`
+/// and, if `suffix` is set, also ends with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OocLinePattern {
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: Option<String>,
+}
+
+impl OocLinePattern {
+    pub fn new(prefix: &str, suffix: Option<&str>) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.map(str::to_string),
+        }
+    }
+
+    /// Parse `PREFIX` or `PREFIX SUFFIX` (e.g. `//` or `<< >>`).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut parts = value.split_whitespace();
+        let prefix = parts
+            .next()
+            .ok_or_else(|| "OOC line pattern cannot be empty.".to_string())?;
+        let suffix = parts.next();
+        if parts.next().is_some() {
+            return Err(format!(
+                "Invalid OOC line pattern '{}'. Expected 'PREFIX' or 'PREFIX SUFFIX'.",
+                value
+            ));
+        }
+        Ok(Self::new(prefix, suffix))
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        if self.prefix.is_empty() || !message.starts_with(&self.prefix) {
+            return false;
+        }
+        match self.suffix.as_deref() {
+            Some(suffix) => {
+                message.len() >= self.prefix.len() + suffix.len() && message.ends_with(suffix)
+            }
+            None => true,
+        }
+    }
+}
+
+fn default_timestamp_formats() -> Vec<String> {
+    vec![DEFAULT_TIMESTAMP_FORMAT.to_string()]
+}
+
+/// Channels kept by default: 0 rendered as speech and 6 as emotes.
+pub fn default_channel_styles() -> BTreeMap<String, ChannelStyle> {
+    BTreeMap::from([
+        ("0".to_string(), ChannelStyle::Say),
+        ("6".to_string(), ChannelStyle::Emote),
+    ])
+}
+
+/// Channel field of a chat log line: numeric codes only.
+pub const DEFAULT_CHANNEL_PATTERN: &str = r"\d+";
+
+/// Channel field for logs that name their channels (`say`, `emote`) as well as numbering them.
+pub const NAMED_CHANNEL_PATTERN: &str = r"\d+|[A-Za-z]+";
+
+pub fn default_channel_pattern() -> String {
+    DEFAULT_CHANNEL_PATTERN.to_string()
+}
+
+/// Channel names mapped to their numeric codes: `say` is 0 and `emote` is 6.
+pub fn default_channel_names() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("say".to_string(), "0".to_string()),
+        ("emote".to_string(), "6".to_string()),
+    ])
+}
+
+pub(crate) fn default_ooc_line_patterns() -> Vec<OocLinePattern> {
+    vec![
+        OocLinePattern::new("((", Some("))")),
+        OocLinePattern::new("[[", Some("]]")),
+    ]
+}
+
 /// Represents the optional duration override UI state.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DurationOverride {
@@ -389,6 +1171,10 @@ impl Default for ThemePreference {
 /// Preset definition shared between CLI and GUI.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresetDefinition {
+    /// Stable identifier referenced by `active_preset`; survives display-name changes.
+    /// Derived from the name via [`preset_id_from_name`] when missing on load.
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub weekday: String,
     pub timezone: String,
@@ -397,13 +1183,97 @@ pub struct PresetDefinition {
     pub file_prefix: String,
     #[serde(default)]
     pub default_weeks_ago: u32,
+    /// Optional output filename pattern; see [`render_filename_template`]. When unset the
+    /// default `{prefix}-{date}.txt` is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename_template: Option<String>,
+    /// Model for runs of this preset while the global model is `auto`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_model: Option<String>,
     #[serde(default)]
     pub builtin: bool,
 }
 
+/// Placeholders understood by [`render_filename_template`].
+pub const FILENAME_TEMPLATE_PLACEHOLDERS: [&str; 6] =
+    ["{prefix}", "{date}", "{yyyy}", "{mm}", "{dd}", "{preset}"];
+
+/// Expand a preset filename template for an event held on `event_date`.
+///
+/// `{date}` is the `MMDDYY` form used by the default pattern and `{preset}` is the preset id.
+/// The result must be a bare file name: unknown placeholders, path separators and empty
+/// names are rejected.
+pub fn render_filename_template(
+    template: &str,
+    prefix: &str,
+    preset_id: &str,
+    event_date: NaiveDate,
+) -> Result<String, String> {
+    let rendered = template
+        .trim()
+        .replace("{prefix}", prefix)
+        .replace("{date}", &event_date.format("%m%d%y").to_string())
+        .replace("{yyyy}", &format!("{:04}", event_date.year()))
+        .replace("{mm}", &format!("{:02}", event_date.month()))
+        .replace("{dd}", &format!("{:02}", event_date.day()))
+        .replace("{preset}", preset_id);
+
+    if let Some(start) = rendered.find('{') {
+        let end = rendered[start..]
+            .find('}')
+            .map_or(rendered.len(), |offset| start + offset + 1);
+        return Err(format!(
+            "Filename template '{}' has unknown placeholder '{}' (expected one of {})",
+            template,
+            &rendered[start..end],
+            FILENAME_TEMPLATE_PLACEHOLDERS.join(", ")
+        ));
+    }
+    if rendered.contains('/') || rendered.contains('\\') {
+        return Err(format!(
+            "Filename template '{}' must produce a file name without path separators (got '{}')",
+            template, rendered
+        ));
+    }
+    if rendered.is_empty() || rendered == "." || rendered == ".." {
+        return Err(format!(
+            "Filename template '{}' does not produce a usable file name",
+            template
+        ));
+    }
+    Ok(rendered)
+}
+
+/// Check that `template` renders to a valid file name for a sample event.
+pub fn validate_filename_template(template: &str) -> Result<(), String> {
+    let sample_date = NaiveDate::from_ymd_opt(2025, 1, 4).expect("valid sample date");
+    render_filename_template(template, "conv", "preset", sample_date).map(|_| ())
+}
+
+/// Loose check that `slug` looks like an OpenRouter model id (`provider/model`, optionally
+/// with a `:variant` suffix). Whether the model exists is only known at run time.
+pub fn validate_model_slug(slug: &str) -> Result<(), String> {
+    let slug = slug.trim();
+    let valid_chars = slug
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':' | '/'));
+    let well_formed = slug
+        .split_once('/')
+        .is_some_and(|(provider, model)| !provider.is_empty() && !model.is_empty());
+    if valid_chars && well_formed {
+        Ok(())
+    } else {
+        Err(format!(
+            "model '{}' is not an OpenRouter model id like 'provider/model'",
+            slug
+        ))
+    }
+}
+
 pub(crate) fn default_presets() -> Vec<PresetDefinition> {
     vec![
         PresetDefinition {
+            id: SATURDAY_PRESET_ID.to_string(),
             name: SATURDAY_PRESET_NAME.to_string(),
             weekday: "saturday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -411,9 +1281,12 @@ pub(crate) fn default_presets() -> Vec<PresetDefinition> {
             duration_minutes: 145,
             file_prefix: "conv".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: true,
         },
         PresetDefinition {
+            id: TUESDAY_7_PRESET_ID.to_string(),
             name: TUESDAY_7_PRESET_NAME.to_string(),
             weekday: "tuesday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -421,9 +1294,12 @@ pub(crate) fn default_presets() -> Vec<PresetDefinition> {
             duration_minutes: 60,
             file_prefix: "rsm7".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: true,
         },
         PresetDefinition {
+            id: TUESDAY_8_PRESET_ID.to_string(),
             name: TUESDAY_8_PRESET_NAME.to_string(),
             weekday: "tuesday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -431,9 +1307,12 @@ pub(crate) fn default_presets() -> Vec<PresetDefinition> {
             duration_minutes: 60,
             file_prefix: "rsm8".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: true,
         },
         PresetDefinition {
+            id: FRIDAY_6_PRESET_ID.to_string(),
             name: FRIDAY_6_PRESET_NAME.to_string(),
             weekday: "friday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -441,6 +1320,8 @@ pub(crate) fn default_presets() -> Vec<PresetDefinition> {
             duration_minutes: 60,
             file_prefix: "tp6".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: true,
         },
     ]
@@ -465,10 +1346,79 @@ pub struct RuntimeOverrides {
     pub outfile: Option<Option<String>>,
     pub use_ai_corrections: Option<bool>,
     pub keep_original_output: Option<bool>,
+    pub kept_originals_dir: Option<Option<String>>,
     pub show_diff: Option<bool>,
+    pub diff_context: Option<Option<usize>>,
+    pub diff_summary: Option<bool>,
+    pub final_newline: Option<bool>,
     pub output_directory: Option<Option<String>>,
     pub output_target: Option<OutputTarget>,
     pub openrouter_model: Option<String>,
+    pub merge_gap_seconds: Option<Option<u64>>,
+    pub drop_empty_messages: Option<bool>,
+    pub keep_parentheticals: Option<bool>,
+    pub section_by_channel: Option<bool>,
+    pub dedupe_consecutive: Option<bool>,
+    pub with_index: Option<bool>,
+    pub parallel_parse: Option<bool>,
+    pub section_headings: Option<SectionHeadings>,
+    pub dump_filtered: Option<Option<String>>,
+    pub stats_json: Option<Option<String>>,
+    /// Replaces the configured timestamp formats.
+    pub timestamp_formats: Option<Vec<String>>,
+    /// Additional whole-line OOC patterns appended to the configured set.
+    pub extra_ooc_line_patterns: Option<Vec<OocLinePattern>>,
+    pub ooc_mode: Option<OocMode>,
+    pub output_format: Option<OutputFormat>,
+    pub say_punctuation: Option<SayPunctuation>,
+    pub output_quotes: Option<OutputQuotes>,
+    pub ellipsis_style: Option<EllipsisStyle>,
+    /// Channel styles merged over the configured map.
+    pub channel_styles: Option<BTreeMap<String, ChannelStyle>>,
+    pub channel_pattern: Option<String>,
+    pub channel_names: Option<BTreeMap<String, String>>,
+    pub llm_timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    pub llm_temperature: Option<f32>,
+    pub llm_disable_reasoning: Option<bool>,
+    pub llm_concurrency: Option<usize>,
+    pub llm_max_retries: Option<u32>,
+    pub llm_max_chunks: Option<usize>,
+    pub llm_min_retain_ratio: Option<f64>,
+    pub context_safety_margin: Option<f64>,
+    pub llm_retry_failed: Option<bool>,
+    pub max_output_lines: Option<u64>,
+    pub max_output_bytes: Option<u64>,
+    pub record_history: Option<bool>,
+    pub history_max_records: Option<usize>,
+    /// Go past safety limits such as the output size guard.
+    pub force: Option<bool>,
+    /// Create the output directory when it does not exist.
+    pub mkdir_output: Option<bool>,
+    pub header_template: Option<Option<String>>,
+    /// Additional protected terms appended to the configured list.
+    pub extra_protected_terms: Option<Vec<String>>,
+    /// Additional glossary entries; a term already in the glossary takes the new definition.
+    pub extra_glossary: Option<Vec<(String, String)>>,
+    /// Replaces the configured fallback model chain.
+    pub model_chain: Option<Vec<String>>,
+    pub auto_strategy: Option<AutoStrategy>,
+    pub llm_sample: Option<bool>,
+    pub split_by_speaker: Option<bool>,
+    pub split_directory: Option<Option<String>>,
+    pub anonymize: Option<bool>,
+    pub anon_map: Option<Option<String>>,
+    pub debug_unparsed: Option<Option<String>>,
+    pub limit: Option<Option<usize>>,
+    pub grep: Option<Option<String>>,
+    pub grep_ignore_case: Option<bool>,
+    pub annotate_source: Option<bool>,
+    pub strict_parse: Option<bool>,
+    pub fail_on_empty: Option<bool>,
+    pub today: Option<NaiveDate>,
+    pub log_file: Option<Option<String>>,
+    pub diff_against: Option<Option<String>>,
 }
 
 impl RuntimeOverrides {
@@ -489,10 +1439,71 @@ impl RuntimeOverrides {
             && self.outfile.is_none()
             && self.use_ai_corrections.is_none()
             && self.keep_original_output.is_none()
+            && self.kept_originals_dir.is_none()
             && self.show_diff.is_none()
+            && self.diff_context.is_none()
+            && self.diff_summary.is_none()
+            && self.final_newline.is_none()
             && self.output_directory.is_none()
             && self.output_target.is_none()
             && self.openrouter_model.is_none()
+            && self.merge_gap_seconds.is_none()
+            && self.drop_empty_messages.is_none()
+            && self.keep_parentheticals.is_none()
+            && self.section_by_channel.is_none()
+            && self.dedupe_consecutive.is_none()
+            && self.with_index.is_none()
+            && self.parallel_parse.is_none()
+            && self.section_headings.is_none()
+            && self.dump_filtered.is_none()
+            && self.stats_json.is_none()
+            && self.timestamp_formats.is_none()
+            && self.extra_ooc_line_patterns.is_none()
+            && self.ooc_mode.is_none()
+            && self.output_format.is_none()
+            && self.say_punctuation.is_none()
+            && self.output_quotes.is_none()
+            && self.ellipsis_style.is_none()
+            && self.channel_styles.is_none()
+            && self.channel_pattern.is_none()
+            && self.channel_names.is_none()
+            && self.llm_timeout_secs.is_none()
+            && self.connect_timeout_secs.is_none()
+            && self.read_timeout_secs.is_none()
+            && self.llm_temperature.is_none()
+            && self.llm_disable_reasoning.is_none()
+            && self.llm_concurrency.is_none()
+            && self.llm_max_retries.is_none()
+            && self.llm_max_chunks.is_none()
+            && self.llm_min_retain_ratio.is_none()
+            && self.context_safety_margin.is_none()
+            && self.llm_retry_failed.is_none()
+            && self.max_output_lines.is_none()
+            && self.max_output_bytes.is_none()
+            && self.record_history.is_none()
+            && self.history_max_records.is_none()
+            && self.force.is_none()
+            && self.mkdir_output.is_none()
+            && self.header_template.is_none()
+            && self.extra_protected_terms.is_none()
+            && self.extra_glossary.is_none()
+            && self.model_chain.is_none()
+            && self.auto_strategy.is_none()
+            && self.llm_sample.is_none()
+            && self.split_by_speaker.is_none()
+            && self.split_directory.is_none()
+            && self.anonymize.is_none()
+            && self.anon_map.is_none()
+            && self.debug_unparsed.is_none()
+            && self.limit.is_none()
+            && self.grep.is_none()
+            && self.grep_ignore_case.is_none()
+            && self.annotate_source.is_none()
+            && self.strict_parse.is_none()
+            && self.fail_on_empty.is_none()
+            && self.today.is_none()
+            && self.log_file.is_none()
+            && self.diff_against.is_none()
     }
 }
 
@@ -512,6 +1523,10 @@ fn legacy_settings_path() -> PathBuf {
     config_directory().join(LEGACY_SETTINGS_FILE_NAME)
 }
 
+fn legacy_migration_disabled() -> bool {
+    std::env::var(IGNORE_LEGACY_ENV).is_ok_and(|value| value.trim() == "1")
+}
+
 /// Load the configuration, falling back to defaults or the legacy JSON representation.
 pub fn load_config() -> ConfigLoadResult {
     let mut warnings = Vec::new();
@@ -525,10 +1540,13 @@ pub fn load_config() -> ConfigLoadResult {
                     warnings.append(&mut sanitize_warnings);
                     if secrets_migrated {
                         if let Err(err) = save_config(&cfg) {
-                            warnings
-                                .push(format!("Failed to persist secure secret updates: {}", err));
+                            warnings.push(ConfigWarning::new(
+                                WarningCode::SecretStorage,
+                                format!("Failed to persist secure secret updates: {}", err),
+                            ));
                         }
                     }
+                    warnings.retain(|warning| !cfg.runtime.silences(warning.code));
                     return ConfigLoadResult {
                         config: cfg,
                         warnings,
@@ -536,56 +1554,93 @@ pub fn load_config() -> ConfigLoadResult {
                     };
                 }
                 Err(err) => {
-                    warnings.push(format!(
-                        "Failed to parse {} as TOML: {}. Falling back to defaults.",
-                        CONFIG_FILE_NAME, err
+                    warnings.push(ConfigWarning::new(
+                        WarningCode::UnreadableConfig,
+                        format!(
+                            "Failed to parse {} as TOML: {}. Falling back to defaults.",
+                            CONFIG_FILE_NAME, err
+                        ),
                     ));
                 }
             },
             Err(err) => {
-                warnings.push(format!(
-                    "Failed to read {}: {}. Falling back to defaults.",
-                    CONFIG_FILE_NAME, err
+                warnings.push(ConfigWarning::new(
+                    WarningCode::UnreadableConfig,
+                    format!(
+                        "Failed to read {}: {}. Falling back to defaults.",
+                        CONFIG_FILE_NAME, err
+                    ),
                 ));
             }
         }
     } else {
-        // Attempt to migrate the legacy JSON settings.
+        // Attempt to migrate the legacy JSON settings. Once migrated the file is renamed so a
+        // deliberately deleted config.toml doesn't bring the old settings back.
         let legacy_path = legacy_settings_path();
-        if legacy_path.exists() {
+        if legacy_path.exists() && !legacy_migration_disabled() {
             match fs::read_to_string(&legacy_path) {
                 Ok(raw) => match serde_json::from_str::<ConvocationsConfig>(&raw) {
                     Ok(legacy) => {
                         let cfg = migrate_legacy_config(legacy);
                         let (cfg, mut sanitize_warnings, secrets_migrated) = sanitize_config(cfg);
-                        warnings.push(format!(
-                            "Loaded configuration from legacy {}. A new {} will be written.",
-                            LEGACY_SETTINGS_FILE_NAME, CONFIG_FILE_NAME
+                        warnings.push(ConfigWarning::new(
+                            WarningCode::LegacyMigration,
+                            format!(
+                                "Loaded configuration from legacy {}. A new {} will be written and the old file renamed to {}.",
+                                LEGACY_SETTINGS_FILE_NAME,
+                                CONFIG_FILE_NAME,
+                                MIGRATED_LEGACY_SETTINGS_FILE_NAME
+                            ),
                         ));
                         warnings.append(&mut sanitize_warnings);
                         if let Err(err) = save_config(&cfg) {
-                            warnings
-                                .push(format!("Failed to persist migrated configuration: {}", err));
-                        } else if secrets_migrated {
-                            warnings.push(
-                                "Migrated secrets were stored securely during legacy import."
-                                    .to_string(),
-                            );
+                            warnings.push(ConfigWarning::new(
+                                WarningCode::LegacyMigration,
+                                format!("Failed to persist migrated configuration: {}", err),
+                            ));
+                        } else {
+                            let migrated_path =
+                                config_directory().join(MIGRATED_LEGACY_SETTINGS_FILE_NAME);
+                            if let Err(err) = fs::rename(&legacy_path, &migrated_path) {
+                                warnings.push(ConfigWarning::new(
+                                    WarningCode::LegacyMigration,
+                                    format!(
+                                        "Failed to rename {} to {}: {}",
+                                        LEGACY_SETTINGS_FILE_NAME,
+                                        MIGRATED_LEGACY_SETTINGS_FILE_NAME,
+                                        err
+                                    ),
+                                ));
+                            }
+                            if secrets_migrated {
+                                warnings.push(ConfigWarning::new(
+                                    WarningCode::LegacyMigration,
+                                    "Migrated secrets were stored securely during legacy import."
+                                        .to_string(),
+                                ));
+                            }
                         }
+                        warnings.retain(|warning| !cfg.runtime.silences(warning.code));
                         return ConfigLoadResult {
                             config: cfg,
                             warnings,
                             source: ConfigSource::LegacyJson,
                         };
                     }
-                    Err(err) => warnings.push(format!(
-                        "Failed to parse {}: {}. Ignoring legacy settings.",
-                        LEGACY_SETTINGS_FILE_NAME, err
+                    Err(err) => warnings.push(ConfigWarning::new(
+                        WarningCode::UnreadableConfig,
+                        format!(
+                            "Failed to parse {}: {}. Ignoring legacy settings.",
+                            LEGACY_SETTINGS_FILE_NAME, err
+                        ),
                     )),
                 },
-                Err(err) => warnings.push(format!(
-                    "Failed to read {}: {}. Ignoring legacy settings.",
-                    LEGACY_SETTINGS_FILE_NAME, err
+                Err(err) => warnings.push(ConfigWarning::new(
+                    WarningCode::UnreadableConfig,
+                    format!(
+                        "Failed to read {}: {}. Ignoring legacy settings.",
+                        LEGACY_SETTINGS_FILE_NAME, err
+                    ),
                 )),
             }
         }
@@ -599,21 +1654,26 @@ pub fn load_config() -> ConfigLoadResult {
     }
 }
 
-/// Persist the configuration to disk.
+/// Persist the configuration to disk. Any plaintext API key is moved into secure storage first so
+/// the file never contains it.
 pub fn save_config(config: &FileConfig) -> Result<(), ConfigError> {
     let path = config_path();
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
+        fs::create_dir_all(parent).map_err(|err| ConfigError::write(parent, err))?;
     }
     let mut config_to_write = config.clone();
-    if config_to_write.runtime.migrate_openrouter_secret()? {
-        // ensure the on-disk representation never regresses to plaintext
-    }
-    let serialized = toml::to_string_pretty(&config_to_write)?;
-    fs::write(path, serialized)?;
+    config_to_write.runtime.migrate_openrouter_secret()?;
+    let serialized = serialize_config(&config_to_write)?;
+    fs::write(&path, serialized).map_err(|err| ConfigError::write(&path, err))?;
     Ok(())
 }
 
+/// `config` as TOML, without touching disk or secure storage. [`save_config`] additionally moves a
+/// plaintext API key into secure storage before writing.
+pub fn serialize_config(config: &FileConfig) -> Result<String, ConfigError> {
+    Ok(toml::to_string_pretty(config)?)
+}
+
 /// Persist only presets and UI preferences, preserving existing runtime preferences from disk.
 /// This is used by GUI save operations and preset CRUD to keep runtime preferences ephemeral.
 pub fn save_presets_and_ui_only(
@@ -630,19 +1690,35 @@ pub fn save_presets_and_ui_only(
     save_config(&config)
 }
 
-fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<String>, bool) {
+fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<ConfigWarning>, bool) {
     let mut warnings = Vec::new();
     let mut secrets_migrated = false;
 
     if config.schema_version != CURRENT_SCHEMA_VERSION {
-        warnings.push(format!(
-            "Unknown config schema version {}. Resetting to {}.",
-            config.schema_version, CURRENT_SCHEMA_VERSION
+        warnings.push(ConfigWarning::new(
+            WarningCode::UnknownSchemaVersion,
+            format!(
+                "Unknown config schema version {}. Resetting to {}.",
+                config.schema_version, CURRENT_SCHEMA_VERSION
+            ),
         ));
         config = FileConfig::default();
         return (config, warnings, secrets_migrated);
     }
 
+    // Populate missing or colliding preset ids from display names.
+    let mut assigned: Vec<PresetDefinition> = Vec::with_capacity(config.presets.len());
+    for mut preset in std::mem::take(&mut config.presets) {
+        let id = preset.id.trim();
+        if id.is_empty() || assigned.iter().any(|other| other.id == id) {
+            preset.id = unique_preset_id(&assigned, &preset.name);
+        } else {
+            preset.id = id.to_string();
+        }
+        assigned.push(preset);
+    }
+    config.presets = assigned;
+
     let mut preset_names = HashSet::new();
     let mut duplicates = HashSet::new();
 
@@ -656,9 +1732,12 @@ fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<String>, bool) {
     });
 
     if !duplicates.is_empty() {
-        warnings.push(format!(
-            "Removed duplicate preset names: {}",
-            duplicates.into_iter().collect::<Vec<_>>().join(", ")
+        warnings.push(ConfigWarning::new(
+            WarningCode::DuplicatePreset,
+            format!(
+                "Removed duplicate preset names: {}",
+                duplicates.into_iter().collect::<Vec<_>>().join(", ")
+            ),
         ));
     }
 
@@ -666,73 +1745,345 @@ fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<String>, bool) {
     let mut existing: HashMap<String, PresetDefinition> = config
         .presets
         .iter()
-        .map(|p| (p.name.clone(), p.clone()))
+        .map(|p| (p.id.clone(), p.clone()))
         .collect();
     for builtin in default_presets() {
-        existing.entry(builtin.name.clone()).or_insert(builtin);
+        existing.entry(builtin.id.clone()).or_insert(builtin);
     }
     config.presets = existing.into_values().collect();
     config.presets.sort_by(|a, b| a.name.cmp(&b.name));
 
-    if !config
-        .presets
-        .iter()
-        .any(|preset| preset.name == config.runtime.active_preset)
-    {
-        warnings.push(format!(
-            "Active preset '{}' not found. Resetting to default preset '{}'.",
-            config.runtime.active_preset, SATURDAY_PRESET_NAME
-        ));
-        config.runtime.active_preset = SATURDAY_PRESET_NAME.to_string();
+    // Key the active preset on its id, migrating configs that stored the display name.
+    match find_preset(&config.presets, &config.runtime.active_preset) {
+        Some(preset) => config.runtime.active_preset = preset.id.clone(),
+        None => {
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidPreset,
+                format!(
+                    "Active preset '{}' not found. Resetting to default preset '{}'.",
+                    config.runtime.active_preset, SATURDAY_PRESET_NAME
+                ),
+            ));
+            config.runtime.active_preset = SATURDAY_PRESET_ID.to_string();
+        }
     }
 
     // Validate preset definitions
     for preset in &mut config.presets {
         if preset.duration_minutes == 0 {
-            warnings.push(format!(
-                "Preset '{}' has invalid duration_minutes (0). Resetting to 60.",
-                preset.name
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidPreset,
+                format!(
+                    "Preset '{}' has invalid duration_minutes (0). Resetting to 60.",
+                    preset.name
+                ),
             ));
             preset.duration_minutes = 60;
         }
         if preset.file_prefix.trim().is_empty() {
-            warnings.push(format!(
-                "Preset '{}' has empty file_prefix. This preset will be removed.",
-                preset.name
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidPreset,
+                format!(
+                    "Preset '{}' has empty file_prefix. This preset will be removed.",
+                    preset.name
+                ),
             ));
         }
-    }
+        preset.llm_model = preset
+            .llm_model
+            .take()
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty());
+        if let Some(Err(err)) = preset.llm_model.as_deref().map(validate_model_slug) {
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidPreset,
+                format!(
+                    "Preset '{}': {}. Using the global model instead.",
+                    preset.name, err
+                ),
+            ));
+            preset.llm_model = None;
+        }
+        if let Some(template) = preset.filename_template.take() {
+            if template.trim().is_empty() {
+                continue;
+            }
+            match validate_filename_template(&template) {
+                Ok(()) => preset.filename_template = Some(template),
+                Err(err) => warnings.push(ConfigWarning::new(
+                    WarningCode::InvalidPreset,
+                    format!(
+                        "Preset '{}': {}. Falling back to the default filename pattern.",
+                        preset.name, err
+                    ),
+                )),
+            }
+        }
+    }
 
     // Remove presets with empty file_prefix (now a hard requirement)
     let initial_count = config.presets.len();
     config.presets.retain(|p| !p.file_prefix.trim().is_empty());
     if config.presets.len() < initial_count {
-        warnings.push("Removed presets with empty file_prefix (required field)".to_string());
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidPreset,
+            "Removed presets with empty file_prefix (required field)".to_string(),
+        ));
     }
 
+    warnings.extend(
+        overlapping_preset_warnings(&config.presets)
+            .into_iter()
+            .map(|message| ConfigWarning::new(WarningCode::OverlappingPresets, message)),
+    );
+
     let duration_hours = config.runtime.duration_override.hours;
     if !duration_hours.is_finite() {
-        warnings.push(
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidDuration,
             "Duration override hours must be a finite number. Disabling override and resetting to 1.0."
                 .to_string(),
-        );
+        ));
         config.runtime.duration_override.enabled = false;
         config.runtime.duration_override.hours = DurationOverride::default_hours();
     } else if duration_hours < 1.0 {
-        warnings.push(
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidDuration,
             "Duration override hours must be at least 1.0. Disabling override and resetting to 1.0."
                 .to_string(),
-        );
+        ));
         config.runtime.duration_override.enabled = false;
         config.runtime.duration_override.hours = DurationOverride::default_hours();
     }
 
+    if config.runtime.llm_timeout_secs == 0 {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "LLM timeout must be at least 1 second. Resetting to {}.",
+                RuntimePreferences::default_llm_timeout_secs()
+            ),
+        ));
+        config.runtime.llm_timeout_secs = RuntimePreferences::default_llm_timeout_secs();
+    } else if config.runtime.llm_timeout_secs > MAX_LLM_TIMEOUT_SECS {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "LLM timeout must be at most {} seconds. Clamping {} to {}.",
+                MAX_LLM_TIMEOUT_SECS, config.runtime.llm_timeout_secs, MAX_LLM_TIMEOUT_SECS
+            ),
+        ));
+        config.runtime.llm_timeout_secs = MAX_LLM_TIMEOUT_SECS;
+    }
+
+    if config.runtime.connect_timeout_secs == 0 {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "Connect timeout must be at least 1 second. Resetting to {}.",
+                RuntimePreferences::default_connect_timeout_secs()
+            ),
+        ));
+        config.runtime.connect_timeout_secs = RuntimePreferences::default_connect_timeout_secs();
+    }
+
+    if config.runtime.read_timeout_secs == 0 {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "Read timeout must be at least 1 second. Resetting to {}.",
+                RuntimePreferences::default_read_timeout_secs()
+            ),
+        ));
+        config.runtime.read_timeout_secs = RuntimePreferences::default_read_timeout_secs();
+    }
+
+    if !(0.0..=2.0).contains(&config.runtime.llm_temperature) {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "LLM temperature must be between 0.0 and 2.0. Resetting to {}.",
+                RuntimePreferences::default_llm_temperature()
+            ),
+        ));
+        config.runtime.llm_temperature = RuntimePreferences::default_llm_temperature();
+    }
+
+    let concurrency = config.runtime.llm_concurrency;
+    if !(1..=MAX_LLM_CONCURRENCY).contains(&concurrency) {
+        let clamped = concurrency.clamp(1, MAX_LLM_CONCURRENCY);
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "LLM concurrency must be between 1 and {}. Clamping {} to {}.",
+                MAX_LLM_CONCURRENCY, concurrency, clamped
+            ),
+        ));
+        config.runtime.llm_concurrency = clamped;
+    }
+
+    if config.runtime.llm_max_retries > MAX_LLM_MAX_RETRIES {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "LLM max retries must be at most {}. Clamping {} to {}.",
+                MAX_LLM_MAX_RETRIES, config.runtime.llm_max_retries, MAX_LLM_MAX_RETRIES
+            ),
+        ));
+        config.runtime.llm_max_retries = MAX_LLM_MAX_RETRIES;
+    }
+
+    let retain_ratio = config.runtime.llm_min_retain_ratio;
+    if !retain_ratio.is_finite() || !(0.0..=1.0).contains(&retain_ratio) {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "LLM minimum retain ratio must be between 0 and 1. Resetting to {}.",
+                DEFAULT_LLM_MIN_RETAIN_RATIO
+            ),
+        ));
+        config.runtime.llm_min_retain_ratio = DEFAULT_LLM_MIN_RETAIN_RATIO;
+    }
+
+    let margin = config.runtime.context_safety_margin;
+    if !margin.is_finite() || !(0.0..1.0).contains(&margin) {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidLlmSetting,
+            format!(
+                "Context safety margin must be at least 0 and below 1. Resetting to {}.",
+                DEFAULT_CONTEXT_SAFETY_MARGIN
+            ),
+        ));
+        config.runtime.context_safety_margin = DEFAULT_CONTEXT_SAFETY_MARGIN;
+    }
+
+    let mut timestamp_formats = Vec::new();
+    for spec in &config.runtime.timestamp_formats {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            continue;
+        }
+        match validate_timestamp_format(spec) {
+            Ok(()) => timestamp_formats.push(spec.to_string()),
+            Err(err) => warnings.push(ConfigWarning::new(
+                WarningCode::InvalidTimestampFormat,
+                format!("Ignoring timestamp format: {}", err),
+            )),
+        }
+    }
+    if timestamp_formats.is_empty() {
+        timestamp_formats = default_timestamp_formats();
+    }
+    config.runtime.timestamp_formats = timestamp_formats;
+
+    let pattern_count = config.runtime.ooc_line_patterns.len();
+    config
+        .runtime
+        .ooc_line_patterns
+        .retain(|pattern| !pattern.prefix.trim().is_empty());
+    if config.runtime.ooc_line_patterns.len() < pattern_count {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidOocPattern,
+            "Removed OOC line patterns with an empty prefix.".to_string(),
+        ));
+    }
+
+    config.runtime.channel_styles = std::mem::take(&mut config.runtime.channel_styles)
+        .into_iter()
+        .map(|(channel, style)| (channel.trim().to_string(), style))
+        .filter(|(channel, _)| !channel.is_empty())
+        .collect();
+    if config.runtime.channel_styles.is_empty() {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidChannelStyle,
+            "No chat channels are configured in channel_styles; restoring the defaults (0 = say, 6 = emote).",
+        ));
+        config.runtime.channel_styles = default_channel_styles();
+    }
+
+    if let Err(err) = regex::Regex::new(&config.runtime.channel_pattern) {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidChannelStyle,
+            format!(
+                "Invalid channel_pattern ({}); restoring the default ({}).",
+                err, DEFAULT_CHANNEL_PATTERN
+            ),
+        ));
+        config.runtime.channel_pattern = default_channel_pattern();
+    }
+    config.runtime.channel_names = std::mem::take(&mut config.runtime.channel_names)
+        .into_iter()
+        .map(|(name, code)| (name.trim().to_ascii_lowercase(), code.trim().to_string()))
+        .filter(|(name, code)| !name.is_empty() && !code.is_empty())
+        .collect();
+
+    let mut seen_terms = HashSet::new();
+    config.runtime.protected_terms = config
+        .runtime
+        .protected_terms
+        .iter()
+        .map(|term| term.trim().to_string())
+        .filter(|term| !term.is_empty() && seen_terms.insert(term.clone()))
+        .collect();
+    if config.runtime.protected_terms.len() > MAX_PROTECTED_TERMS {
+        warnings.push(ConfigWarning::new(
+            WarningCode::TooManyProtectedTerms,
+            format!(
+                "{} protected terms configured; only the first {} are sent to the model.",
+                config.runtime.protected_terms.len(),
+                MAX_PROTECTED_TERMS
+            ),
+        ));
+    }
+
+    let mut seen_glossary_terms = HashSet::new();
+    config.runtime.glossary = config
+        .runtime
+        .glossary
+        .iter()
+        .map(|(term, definition)| (term.trim().to_string(), definition.trim().to_string()))
+        .filter(|(term, _)| !term.is_empty() && seen_glossary_terms.insert(term.clone()))
+        .collect();
+    if config.runtime.glossary.len() > MAX_GLOSSARY_ENTRIES {
+        warnings.push(ConfigWarning::new(
+            WarningCode::TooManyGlossaryEntries,
+            format!(
+                "{} glossary entries configured; only the first {} are sent to the model.",
+                config.runtime.glossary.len(),
+                MAX_GLOSSARY_ENTRIES
+            ),
+        ));
+    }
+
+    config
+        .runtime
+        .model_chain
+        .retain(|model| !model.trim().is_empty());
+
     if let Some(ref mut outfile) = config.runtime.outfile_override {
         if outfile.trim().is_empty() {
             *outfile = String::new();
         }
     }
 
+    let directory_conflict = config
+        .runtime
+        .output_directory_override
+        .as_deref()
+        .map(str::trim)
+        .filter(|directory| !directory.is_empty())
+        .and_then(crate::runtime::output_directory_conflict);
+    if let Some(path) = directory_conflict {
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidRuntimeSettings,
+            format!(
+                "runtime.output_directory_override: '{}' is a file, not a directory. Clearing it.",
+                path.display()
+            ),
+        ));
+        config.runtime.output_directory_override = None;
+    }
+
     // Convert to ConvocationsConfig and validate
     let (convocations_config, mut conv_warnings) =
         runtime_preferences_to_convocations(&config.runtime, &config.presets);
@@ -740,23 +2091,44 @@ fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<String>, bool) {
 
     // Use the runtime validation function to check for contradictory settings
     if let Err(validation_error) = crate::runtime::validate_config(&convocations_config) {
-        warnings.push(format!(
-            "Configuration validation failed: {}. Resetting runtime preferences to defaults.",
-            validation_error
+        warnings.push(ConfigWarning::new(
+            WarningCode::InvalidRuntimeSettings,
+            format!(
+                "Configuration validation failed: {}. Resetting runtime preferences to defaults.",
+                validation_error
+            ),
         ));
         config.runtime = RuntimePreferences::default();
     }
 
     match config.runtime.migrate_openrouter_secret() {
         Ok(true) => {
-            warnings.push("Migrated stored OpenRouter API key into secure storage.".to_string());
+            warnings.push(ConfigWarning::new(
+                WarningCode::SecretStorage,
+                "Migrated stored OpenRouter API key into secure storage.".to_string(),
+            ));
             secrets_migrated = true;
         }
         Ok(false) => {}
+        Err(SecretStoreError::Io(err)) if is_not_writable(&err) => {
+            // Best effort: keep the plaintext key usable for this session and retry next load.
+            warnings.push(ConfigWarning::new(
+                WarningCode::SecretStorage,
+                format!(
+                    "Could not move the OpenRouter API key into secure storage because {} is not \
+                     writable ({}). The key stays in config.toml for now.",
+                    config_directory().display(),
+                    err
+                ),
+            ));
+        }
         Err(err) => {
-            warnings.push(format!(
-                "Failed to secure OpenRouter API key: {}. Clearing the saved key.",
-                err
+            warnings.push(ConfigWarning::new(
+                WarningCode::SecretStorage,
+                format!(
+                    "Failed to secure OpenRouter API key: {}. Clearing the saved key.",
+                    err
+                ),
             ));
             let _ = config.runtime.clear_openrouter_api_key();
             secrets_migrated = true;
@@ -767,17 +2139,21 @@ fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<String>, bool) {
         match config.runtime.resolve_openrouter_api_key() {
             Ok(Some(_)) => { /* Secret accessible; nothing to do */ }
             Ok(None) => {
-                warnings.push(
+                warnings.push(ConfigWarning::new(
+                    WarningCode::MissingApiKey,
                     "Stored OpenRouter API key was not found in secure storage; clearing the saved key."
                         .to_string(),
-                );
+                ));
                 let _ = config.runtime.clear_openrouter_api_key();
                 secrets_migrated = true;
             }
             Err(err) => {
-                warnings.push(format!(
-                    "Failed to retrieve OpenRouter API key: {}. Clearing the saved key.",
-                    err
+                warnings.push(ConfigWarning::new(
+                    WarningCode::MissingApiKey,
+                    format!(
+                        "Failed to retrieve OpenRouter API key: {}. Clearing the saved key.",
+                        err
+                    ),
                 ));
                 let _ = config.runtime.clear_openrouter_api_key();
                 secrets_migrated = true;
@@ -791,7 +2167,7 @@ fn sanitize_config(mut config: FileConfig) -> (FileConfig, Vec<String>, bool) {
 fn migrate_legacy_config(legacy: ConvocationsConfig) -> FileConfig {
     let mut runtime = RuntimePreferences::default();
     runtime.chat_log_path = legacy.infile;
-    runtime.weeks_ago = legacy.last;
+    runtime.weeks_ago = legacy.last.unwrap_or(0);
     runtime.dry_run = legacy.dry_run;
     runtime.use_ai_corrections = legacy.use_llm;
     runtime.keep_original_output = legacy.keep_orig;
@@ -814,13 +2190,13 @@ fn migrate_legacy_config(legacy: ConvocationsConfig) -> FileConfig {
     };
 
     runtime.active_preset = if legacy.rsm7 {
-        TUESDAY_7_PRESET_NAME.to_string()
+        TUESDAY_7_PRESET_ID.to_string()
     } else if legacy.rsm8 {
-        TUESDAY_8_PRESET_NAME.to_string()
+        TUESDAY_8_PRESET_ID.to_string()
     } else if legacy.tp6 {
-        FRIDAY_6_PRESET_NAME.to_string()
+        FRIDAY_6_PRESET_ID.to_string()
     } else {
-        SATURDAY_PRESET_NAME.to_string()
+        SATURDAY_PRESET_ID.to_string()
     };
 
     FileConfig {
@@ -835,20 +2211,73 @@ fn migrate_legacy_config(legacy: ConvocationsConfig) -> FileConfig {
 pub fn runtime_preferences_to_convocations(
     runtime: &RuntimePreferences,
     presets: &[PresetDefinition],
-) -> (ConvocationsConfig, Vec<String>) {
+) -> (ConvocationsConfig, Vec<ConfigWarning>) {
     let mut config = ConvocationsConfig::default();
     let mut warnings = Vec::new();
 
     config.presets = presets.to_vec();
     config.infile = runtime.chat_log_path.clone();
-    config.last = runtime.weeks_ago;
+    // The settings file cannot tell "0" from "unset", so 0 defers to the preset default.
+    config.last = (runtime.weeks_ago != 0).then_some(runtime.weeks_ago);
     config.dry_run = runtime.dry_run;
     config.use_llm = runtime.use_ai_corrections;
     config.keep_orig = runtime.keep_original_output;
+    config.kept_originals_dir = runtime.kept_originals_dir.clone();
     config.no_diff = !runtime.show_diff;
+    config.diff_context = runtime.diff_context;
+    config.diff_summary = runtime.diff_summary;
+    config.final_newline = runtime.final_newline;
+    if config.keep_orig && !config.use_llm {
+        warnings.push(keep_orig_without_llm_warning());
+    }
     config.cleanup = runtime.cleanup_enabled;
     config.format_dialogue = runtime.format_dialogue_enabled;
     config.free_models_only = runtime.free_models_only;
+    config.merge_gap_seconds = runtime.merge_gap_seconds;
+    config.drop_empty_messages = runtime.drop_empty_messages;
+    config.keep_parentheticals = runtime.keep_parentheticals;
+    config.section_by_channel = runtime.section_by_channel;
+    config.dedupe_consecutive = runtime.dedupe_consecutive;
+    config.with_index = runtime.with_index;
+    config.parallel_parse = runtime.parallel_parse;
+    config.section_headings = runtime.section_headings.clone();
+    config.timestamp_formats = runtime.timestamp_formats.clone();
+    config.ooc_line_patterns = runtime.ooc_line_patterns.clone();
+    config.ooc_mode = runtime.ooc_mode;
+    config.output_format = runtime.output_format;
+    config.say_punctuation = runtime.say_punctuation;
+    config.output_quotes = runtime.output_quotes;
+    config.ellipsis_style = runtime.ellipsis_style;
+    config.channel_styles = runtime.channel_styles.clone();
+    config.channel_pattern = runtime.channel_pattern.clone();
+    config.channel_names = runtime.channel_names.clone();
+    config.llm_timeout_secs = runtime.llm_timeout_secs;
+    config.connect_timeout_secs = runtime.connect_timeout_secs;
+    config.read_timeout_secs = runtime.read_timeout_secs;
+    config.llm_temperature = runtime.llm_temperature;
+    config.llm_disable_reasoning = runtime.llm_disable_reasoning;
+    config.llm_concurrency = runtime.llm_concurrency;
+    config.llm_max_retries = runtime.llm_max_retries;
+    config.llm_max_chunks = runtime.llm_max_chunks;
+    config.llm_min_retain_ratio = runtime.llm_min_retain_ratio;
+    config.context_safety_margin = runtime.context_safety_margin;
+    config.llm_retry_failed = runtime.llm_retry_failed;
+    config.max_output_lines = runtime.max_output_lines;
+    config.max_output_bytes = runtime.max_output_bytes;
+    config.record_history = runtime.record_history;
+    config.history_max_records = runtime.history_max_records;
+    config.openrouter_referer = runtime.openrouter_referer.clone();
+    config.openrouter_title = runtime.openrouter_title.clone();
+    config.openrouter_extra_headers = runtime.openrouter_extra_headers.clone();
+    config.prompt_input_marker = runtime.prompt_input_marker.clone();
+    config.prompt_output_marker = runtime.prompt_output_marker.clone();
+    config.prompt_input_prefix = runtime.prompt_input_prefix.clone();
+    config.prompt_input_suffix = runtime.prompt_input_suffix.clone();
+    config.header_template = runtime.header_template.clone();
+    config.protected_terms = runtime.protected_terms.clone();
+    config.glossary = runtime.glossary.clone();
+    config.model_chain = runtime.model_chain.clone();
+    config.auto_strategy = runtime.auto_strategy;
 
     let trimmed_outfile = runtime.outfile_override.as_ref().and_then(|value| {
         let trimmed = value.trim();
@@ -896,15 +2325,17 @@ pub fn runtime_preferences_to_convocations(
     config.duration_override = runtime.duration_override.clone();
     if config.duration_override.enabled {
         if !config.duration_override.hours.is_finite() {
-            warnings.push(
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidDuration,
                 "Duration override hours must be a finite number. Disabling override.".to_string(),
-            );
+            ));
             config.duration_override.enabled = false;
             config.duration_override.hours = DurationOverride::default_hours();
         } else if config.duration_override.hours < 1.0 {
-            warnings.push(
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidDuration,
                 "Duration override hours must be at least 1.0. Disabling override.".to_string(),
-            );
+            ));
             config.duration_override.enabled = false;
             config.duration_override.hours = DurationOverride::default_hours();
         }
@@ -941,54 +2372,73 @@ pub fn runtime_preferences_to_convocations(
         }
         Err(err) => {
             config.openrouter_api_key = None;
-            warnings.push(format!(
-                "Failed to retrieve OpenRouter API key: {}. AI corrections will be skipped.",
-                err
+            warnings.push(ConfigWarning::new(
+                WarningCode::MissingApiKey,
+                format!(
+                    "Failed to retrieve OpenRouter API key: {}. AI corrections will be skipped.",
+                    err
+                ),
             ));
         }
     }
 
+    warnings.retain(|warning| !runtime.silences(warning.code));
     (config, warnings)
 }
 
 fn set_event_flags_for_preset(
     config: &mut ConvocationsConfig,
-    preset_name: &str,
+    preset_id: &str,
     presets: &[PresetDefinition],
-    warnings: &mut Vec<String>,
+    warnings: &mut Vec<ConfigWarning>,
 ) {
     config.rsm7 = false;
     config.rsm8 = false;
     config.tp6 = false;
 
-    let preset = presets.iter().find(|preset| preset.name == preset_name);
+    let preset = find_preset(presets, preset_id);
     match preset {
         Some(preset) => match preset.file_prefix.as_str() {
             "rsm7" => config.rsm7 = true,
             "rsm8" => config.rsm8 = true,
             "tp6" => config.tp6 = true,
             "conv" => { /* default Saturday */ }
-            other => warnings.push(format!(
-                "Preset '{}' uses unrecognised file prefix '{}'; falling back to Saturday configuration.",
-                preset_name, other
+            other => warnings.push(ConfigWarning::new(
+                WarningCode::InvalidPreset,
+                format!(
+                    "Preset '{}' uses unrecognised file prefix '{}'; falling back to Saturday configuration.",
+                    preset.name, other
+                ),
             )),
         },
-        None => warnings.push(format!(
-            "Preset '{}' not found. Falling back to Saturday configuration.",
-            preset_name
+        None => warnings.push(ConfigWarning::new(
+            WarningCode::MissingPreset,
+            format!(
+                "Preset '{}' not found. Falling back to Saturday configuration.",
+                preset_id
+            ),
         )),
     }
 }
 
 /// Merge runtime overrides into an existing configuration.
+fn keep_orig_without_llm_warning() -> ConfigWarning {
+    ConfigWarning::new(
+        WarningCode::LlmOnlyOption,
+        "Keeping the original output only applies when AI corrections are on; no unedited copy \
+         will be written."
+            .to_string(),
+    )
+}
+
 pub fn apply_runtime_overrides(
     config: &mut ConvocationsConfig,
     overrides: &RuntimeOverrides,
     presets: &[PresetDefinition],
-    warnings: &mut Vec<String>,
+    warnings: &mut Vec<ConfigWarning>,
 ) {
     if let Some(value) = overrides.last {
-        config.last = value;
+        config.last = Some(value);
     }
     if let Some(value) = overrides.dry_run {
         config.dry_run = value;
@@ -1003,20 +2453,24 @@ pub fn apply_runtime_overrides(
         config.end = value.clone();
     }
     if let Some(ref preset_id) = overrides.active_preset {
-        config.active_preset = preset_id.clone();
+        config.active_preset = find_preset(presets, preset_id)
+            .map(|preset| preset.id.clone())
+            .unwrap_or_else(|| preset_id.clone());
         set_event_flags_for_preset(config, preset_id, presets, warnings);
     }
     if let Some(mut duration) = overrides.duration_override.clone() {
         if !duration.hours.is_finite() {
-            warnings.push(
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidDuration,
                 "Duration override hours must be a finite number. Ignoring override.".to_string(),
-            );
+            ));
             duration.enabled = false;
             duration.hours = DurationOverride::default_hours();
         } else if duration.hours < 1.0 {
-            warnings.push(
+            warnings.push(ConfigWarning::new(
+                WarningCode::InvalidDuration,
                 "Duration override hours must be at least 1.0. Ignoring override.".to_string(),
-            );
+            ));
             duration.enabled = false;
             duration.hours = DurationOverride::default_hours();
         }
@@ -1073,9 +2527,230 @@ pub fn apply_runtime_overrides(
     if let Some(value) = overrides.keep_original_output {
         config.keep_orig = value;
     }
+    if let Some(ref value) = overrides.kept_originals_dir {
+        config.kept_originals_dir = value.clone();
+    }
     if let Some(value) = overrides.show_diff {
         config.no_diff = !value;
     }
+    if let Some(value) = overrides.diff_context {
+        config.diff_context = value;
+    }
+    if let Some(value) = overrides.diff_summary {
+        config.diff_summary = value;
+    }
+    if let Some(value) = overrides.final_newline {
+        config.final_newline = value;
+    }
+    if let Some(value) = overrides.merge_gap_seconds {
+        config.merge_gap_seconds = value;
+    }
+    if let Some(value) = overrides.drop_empty_messages {
+        config.drop_empty_messages = value;
+    }
+    if let Some(value) = overrides.keep_parentheticals {
+        config.keep_parentheticals = value;
+    }
+    if let Some(value) = overrides.section_by_channel {
+        config.section_by_channel = value;
+    }
+    if let Some(value) = overrides.dedupe_consecutive {
+        config.dedupe_consecutive = value;
+    }
+    if let Some(value) = overrides.with_index {
+        config.with_index = value;
+    }
+    if let Some(value) = overrides.parallel_parse {
+        config.parallel_parse = value;
+    }
+    if let Some(ref headings) = overrides.section_headings {
+        config.section_headings = headings.clone();
+    }
+    if let Some(ref value) = overrides.dump_filtered {
+        config.dump_filtered = value.clone();
+    }
+    if let Some(ref value) = overrides.stats_json {
+        config.stats_json = value.clone();
+    }
+    if let Some(value) = overrides.llm_timeout_secs {
+        config.llm_timeout_secs = value;
+    }
+    if let Some(value) = overrides.connect_timeout_secs {
+        config.connect_timeout_secs = value;
+    }
+    if let Some(value) = overrides.read_timeout_secs {
+        config.read_timeout_secs = value;
+    }
+    if let Some(value) = overrides.llm_temperature {
+        config.llm_temperature = value;
+    }
+    if let Some(value) = overrides.llm_disable_reasoning {
+        config.llm_disable_reasoning = value;
+    }
+    if let Some(value) = overrides.llm_concurrency {
+        config.llm_concurrency = value;
+    }
+    if let Some(value) = overrides.llm_max_retries {
+        config.llm_max_retries = value;
+    }
+    if let Some(value) = overrides.llm_max_chunks {
+        config.llm_max_chunks = value;
+    }
+    if let Some(value) = overrides.llm_min_retain_ratio {
+        config.llm_min_retain_ratio = value;
+    }
+    if let Some(value) = overrides.context_safety_margin {
+        config.context_safety_margin = value;
+    }
+    if let Some(value) = overrides.llm_retry_failed {
+        config.llm_retry_failed = value;
+    }
+    if let Some(value) = overrides.max_output_lines {
+        config.max_output_lines = value;
+    }
+    if let Some(value) = overrides.max_output_bytes {
+        config.max_output_bytes = value;
+    }
+    if let Some(value) = overrides.record_history {
+        config.record_history = value;
+    }
+    if let Some(value) = overrides.history_max_records {
+        config.history_max_records = value;
+    }
+    if let Some(value) = overrides.force {
+        config.force = value;
+    }
+    if let Some(value) = overrides.mkdir_output {
+        config.mkdir_output = value;
+    }
+    if let Some(ref value) = overrides.header_template {
+        config.header_template = value.clone();
+    }
+    if let Some(value) = overrides.llm_sample {
+        config.llm_sample = value;
+    }
+    if let Some(value) = overrides.split_by_speaker {
+        config.split_by_speaker = value;
+    }
+    if let Some(ref value) = overrides.split_directory {
+        config.split_directory = value.clone();
+    }
+    if let Some(value) = overrides.anonymize {
+        config.anonymize = value;
+    }
+    if let Some(ref value) = overrides.anon_map {
+        config.anon_map = value.clone();
+    }
+    if let Some(ref value) = overrides.debug_unparsed {
+        config.debug_unparsed = value.clone();
+    }
+    if let Some(value) = overrides.limit {
+        config.limit = value;
+    }
+    if let Some(ref value) = overrides.grep {
+        config.grep = value.clone();
+    }
+    if let Some(value) = overrides.grep_ignore_case {
+        config.grep_ignore_case = value;
+    }
+    if let Some(value) = overrides.annotate_source {
+        config.annotate_source = value;
+    }
+    if let Some(value) = overrides.strict_parse {
+        config.strict_parse = value;
+    }
+    if let Some(value) = overrides.fail_on_empty {
+        config.fail_on_empty = value;
+    }
+    if let Some(date) = overrides.today {
+        config.today = Some(date);
+    }
+    if let Some(ref value) = overrides.log_file {
+        config.log_file = value.clone();
+    }
+    if let Some(ref value) = overrides.diff_against {
+        config.diff_against = value.clone();
+    }
+    if let Some(ref terms) = overrides.extra_protected_terms {
+        for term in terms {
+            let term = term.trim();
+            if !term.is_empty() && !config.protected_terms.iter().any(|t| t == term) {
+                config.protected_terms.push(term.to_string());
+            }
+        }
+    }
+    if let Some(ref entries) = overrides.extra_glossary {
+        for (term, definition) in entries {
+            let term = term.trim();
+            if term.is_empty() {
+                continue;
+            }
+            let definition = definition.trim().to_string();
+            match config
+                .glossary
+                .iter_mut()
+                .find(|(existing, _)| existing == term)
+            {
+                Some(entry) => entry.1 = definition,
+                None => config.glossary.push((term.to_string(), definition)),
+            }
+        }
+    }
+    if let Some(ref chain) = overrides.model_chain {
+        config.model_chain = chain.clone();
+    }
+    if let Some(strategy) = overrides.auto_strategy {
+        config.auto_strategy = Some(strategy);
+    }
+    if let Some(ref formats) = overrides.timestamp_formats {
+        config.timestamp_formats = formats.clone();
+    }
+    if let Some(ref patterns) = overrides.extra_ooc_line_patterns {
+        for pattern in patterns {
+            if !config.ooc_line_patterns.contains(pattern) {
+                config.ooc_line_patterns.push(pattern.clone());
+            }
+        }
+    }
+    if let Some(mode) = overrides.ooc_mode {
+        config.ooc_mode = mode;
+    }
+    if let Some(format) = overrides.output_format {
+        config.output_format = format;
+    }
+    if let Some(punctuation) = overrides.say_punctuation {
+        config.say_punctuation = punctuation;
+    }
+    if let Some(quotes) = overrides.output_quotes {
+        config.output_quotes = quotes;
+    }
+    if let Some(style) = overrides.ellipsis_style {
+        config.ellipsis_style = style;
+    }
+    if let Some(ref styles) = overrides.channel_styles {
+        for (channel, style) in styles {
+            config.channel_styles.insert(channel.clone(), *style);
+        }
+    }
+    if let Some(ref pattern) = overrides.channel_pattern {
+        config.channel_pattern = pattern.clone();
+    }
+    if let Some(ref names) = overrides.channel_names {
+        config.channel_names = names.clone();
+    }
+
+    let touches_llm_options = overrides.keep_orig.is_some()
+        || overrides.keep_original_output.is_some()
+        || overrides.use_llm.is_some();
+    if touches_llm_options
+        && config.keep_orig
+        && !config.use_llm
+        && !warnings
+            .iter()
+            .any(|w| w.code == WarningCode::LlmOnlyOption)
+    {
+        warnings.push(keep_orig_without_llm_warning());
+    }
 }
 
 /// Produce overrides by diffing a configuration against defaults.
@@ -1084,7 +2759,7 @@ pub fn runtime_overrides_from_convocations(config: &ConvocationsConfig) -> Runti
     let mut overrides = RuntimeOverrides::default();
 
     if config.last != defaults.last {
-        overrides.last = Some(config.last);
+        overrides.last = config.last;
     }
     if config.dry_run != defaults.dry_run {
         overrides.dry_run = Some(config.dry_run);
@@ -1101,11 +2776,11 @@ pub fn runtime_overrides_from_convocations(config: &ConvocationsConfig) -> Runti
     if config.active_preset != defaults.active_preset {
         overrides.active_preset = Some(config.active_preset.clone());
     } else if config.rsm7 && config.rsm7 != defaults.rsm7 {
-        overrides.active_preset = Some(TUESDAY_7_PRESET_NAME.to_string());
+        overrides.active_preset = Some(TUESDAY_7_PRESET_ID.to_string());
     } else if config.rsm8 && config.rsm8 != defaults.rsm8 {
-        overrides.active_preset = Some(TUESDAY_8_PRESET_NAME.to_string());
+        overrides.active_preset = Some(TUESDAY_8_PRESET_ID.to_string());
     } else if config.tp6 && config.tp6 != defaults.tp6 {
-        overrides.active_preset = Some(FRIDAY_6_PRESET_NAME.to_string());
+        overrides.active_preset = Some(FRIDAY_6_PRESET_ID.to_string());
     }
     if config.duration_override != defaults.duration_override {
         overrides.duration_override = Some(config.duration_override.clone());
@@ -1138,10 +2813,22 @@ pub fn runtime_overrides_from_convocations(config: &ConvocationsConfig) -> Runti
         overrides.keep_orig = Some(config.keep_orig);
         overrides.keep_original_output = Some(config.keep_orig);
     }
+    if config.kept_originals_dir != defaults.kept_originals_dir {
+        overrides.kept_originals_dir = Some(config.kept_originals_dir.clone());
+    }
     if config.no_diff != defaults.no_diff {
         overrides.no_diff = Some(config.no_diff);
         overrides.show_diff = Some(!config.no_diff);
     }
+    if config.diff_context != defaults.diff_context {
+        overrides.diff_context = Some(config.diff_context);
+    }
+    if config.diff_summary != defaults.diff_summary {
+        overrides.diff_summary = Some(config.diff_summary);
+    }
+    if config.final_newline != defaults.final_newline {
+        overrides.final_newline = Some(config.final_newline);
+    }
     if config.output_directory != defaults.output_directory {
         overrides.output_directory = Some(config.output_directory.clone());
         overrides.output_target = Some(OutputTarget::Directory);
@@ -1155,6 +2842,185 @@ pub fn runtime_overrides_from_convocations(config: &ConvocationsConfig) -> Runti
     if config.openrouter_model != defaults.openrouter_model {
         overrides.openrouter_model = Some(config.openrouter_model.clone());
     }
+    if config.merge_gap_seconds != defaults.merge_gap_seconds {
+        overrides.merge_gap_seconds = Some(config.merge_gap_seconds);
+    }
+    if config.drop_empty_messages != defaults.drop_empty_messages {
+        overrides.drop_empty_messages = Some(config.drop_empty_messages);
+    }
+    if config.keep_parentheticals != defaults.keep_parentheticals {
+        overrides.keep_parentheticals = Some(config.keep_parentheticals);
+    }
+    if config.section_by_channel != defaults.section_by_channel {
+        overrides.section_by_channel = Some(config.section_by_channel);
+    }
+    if config.dedupe_consecutive != defaults.dedupe_consecutive {
+        overrides.dedupe_consecutive = Some(config.dedupe_consecutive);
+    }
+    if config.with_index != defaults.with_index {
+        overrides.with_index = Some(config.with_index);
+    }
+    if config.parallel_parse != defaults.parallel_parse {
+        overrides.parallel_parse = Some(config.parallel_parse);
+    }
+    if config.section_headings != defaults.section_headings {
+        overrides.section_headings = Some(config.section_headings.clone());
+    }
+    if config.dump_filtered != defaults.dump_filtered {
+        overrides.dump_filtered = Some(config.dump_filtered.clone());
+    }
+    if config.stats_json != defaults.stats_json {
+        overrides.stats_json = Some(config.stats_json.clone());
+    }
+    if config.llm_timeout_secs != defaults.llm_timeout_secs {
+        overrides.llm_timeout_secs = Some(config.llm_timeout_secs);
+    }
+    if config.connect_timeout_secs != defaults.connect_timeout_secs {
+        overrides.connect_timeout_secs = Some(config.connect_timeout_secs);
+    }
+    if config.read_timeout_secs != defaults.read_timeout_secs {
+        overrides.read_timeout_secs = Some(config.read_timeout_secs);
+    }
+    if config.llm_temperature != defaults.llm_temperature {
+        overrides.llm_temperature = Some(config.llm_temperature);
+    }
+    if config.llm_disable_reasoning != defaults.llm_disable_reasoning {
+        overrides.llm_disable_reasoning = Some(config.llm_disable_reasoning);
+    }
+    if config.llm_concurrency != defaults.llm_concurrency {
+        overrides.llm_concurrency = Some(config.llm_concurrency);
+    }
+    if config.llm_max_retries != defaults.llm_max_retries {
+        overrides.llm_max_retries = Some(config.llm_max_retries);
+    }
+    if config.llm_max_chunks != defaults.llm_max_chunks {
+        overrides.llm_max_chunks = Some(config.llm_max_chunks);
+    }
+    if config.llm_min_retain_ratio != defaults.llm_min_retain_ratio {
+        overrides.llm_min_retain_ratio = Some(config.llm_min_retain_ratio);
+    }
+    if config.context_safety_margin != defaults.context_safety_margin {
+        overrides.context_safety_margin = Some(config.context_safety_margin);
+    }
+    if config.llm_retry_failed != defaults.llm_retry_failed {
+        overrides.llm_retry_failed = Some(config.llm_retry_failed);
+    }
+    if config.max_output_lines != defaults.max_output_lines {
+        overrides.max_output_lines = Some(config.max_output_lines);
+    }
+    if config.max_output_bytes != defaults.max_output_bytes {
+        overrides.max_output_bytes = Some(config.max_output_bytes);
+    }
+    if config.record_history != defaults.record_history {
+        overrides.record_history = Some(config.record_history);
+    }
+    if config.history_max_records != defaults.history_max_records {
+        overrides.history_max_records = Some(config.history_max_records);
+    }
+    if config.force != defaults.force {
+        overrides.force = Some(config.force);
+    }
+    if config.mkdir_output != defaults.mkdir_output {
+        overrides.mkdir_output = Some(config.mkdir_output);
+    }
+    if config.header_template != defaults.header_template {
+        overrides.header_template = Some(config.header_template.clone());
+    }
+    if config.llm_sample != defaults.llm_sample {
+        overrides.llm_sample = Some(config.llm_sample);
+    }
+    if config.split_by_speaker != defaults.split_by_speaker {
+        overrides.split_by_speaker = Some(config.split_by_speaker);
+    }
+    if config.split_directory != defaults.split_directory {
+        overrides.split_directory = Some(config.split_directory.clone());
+    }
+    if config.anonymize != defaults.anonymize {
+        overrides.anonymize = Some(config.anonymize);
+    }
+    if config.anon_map != defaults.anon_map {
+        overrides.anon_map = Some(config.anon_map.clone());
+    }
+    if config.debug_unparsed != defaults.debug_unparsed {
+        overrides.debug_unparsed = Some(config.debug_unparsed.clone());
+    }
+    if config.limit != defaults.limit {
+        overrides.limit = Some(config.limit);
+    }
+    if config.grep != defaults.grep {
+        overrides.grep = Some(config.grep.clone());
+    }
+    if config.grep_ignore_case != defaults.grep_ignore_case {
+        overrides.grep_ignore_case = Some(config.grep_ignore_case);
+    }
+    if config.annotate_source != defaults.annotate_source {
+        overrides.annotate_source = Some(config.annotate_source);
+    }
+    if config.strict_parse != defaults.strict_parse {
+        overrides.strict_parse = Some(config.strict_parse);
+    }
+    if config.fail_on_empty != defaults.fail_on_empty {
+        overrides.fail_on_empty = Some(config.fail_on_empty);
+    }
+    if config.today.is_some() {
+        overrides.today = config.today;
+    }
+    if config.log_file != defaults.log_file {
+        overrides.log_file = Some(config.log_file.clone());
+    }
+    if config.diff_against != defaults.diff_against {
+        overrides.diff_against = Some(config.diff_against.clone());
+    }
+    if !config.protected_terms.is_empty() {
+        overrides.extra_protected_terms = Some(config.protected_terms.clone());
+    }
+    if !config.glossary.is_empty() {
+        overrides.extra_glossary = Some(config.glossary.clone());
+    }
+    if config.model_chain != defaults.model_chain {
+        overrides.model_chain = Some(config.model_chain.clone());
+    }
+    if config.auto_strategy != defaults.auto_strategy {
+        overrides.auto_strategy = config.auto_strategy;
+    }
+    if config.timestamp_formats != defaults.timestamp_formats {
+        overrides.timestamp_formats = Some(config.timestamp_formats.clone());
+    }
+    if config.ooc_line_patterns != defaults.ooc_line_patterns {
+        let extra: Vec<OocLinePattern> = config
+            .ooc_line_patterns
+            .iter()
+            .filter(|pattern| !defaults.ooc_line_patterns.contains(pattern))
+            .cloned()
+            .collect();
+        if !extra.is_empty() {
+            overrides.extra_ooc_line_patterns = Some(extra);
+        }
+    }
+    if config.ooc_mode != defaults.ooc_mode {
+        overrides.ooc_mode = Some(config.ooc_mode);
+    }
+    if config.output_format != defaults.output_format {
+        overrides.output_format = Some(config.output_format);
+    }
+    if config.say_punctuation != defaults.say_punctuation {
+        overrides.say_punctuation = Some(config.say_punctuation);
+    }
+    if config.output_quotes != defaults.output_quotes {
+        overrides.output_quotes = Some(config.output_quotes);
+    }
+    if config.ellipsis_style != defaults.ellipsis_style {
+        overrides.ellipsis_style = Some(config.ellipsis_style);
+    }
+    if config.channel_styles != defaults.channel_styles {
+        overrides.channel_styles = Some(config.channel_styles.clone());
+    }
+    if config.channel_pattern != defaults.channel_pattern {
+        overrides.channel_pattern = Some(config.channel_pattern.clone());
+    }
+    if config.channel_names != defaults.channel_names {
+        overrides.channel_names = Some(config.channel_names.clone());
+    }
 
     overrides
 }
@@ -1163,11 +3029,200 @@ pub fn runtime_overrides_from_convocations(config: &ConvocationsConfig) -> Runti
 mod tests {
     use super::*;
 
+    #[test]
+    fn serialize_config_does_not_migrate_plaintext_keys() {
+        let mut config = FileConfig::default();
+        config.runtime.openrouter_api_key = Some(SecretValue::Plain("sk-or-test".to_string()));
+
+        let serialized = serialize_config(&config).expect("serialize");
+
+        assert!(serialized.contains("sk-or-test"), "{serialized}");
+    }
+
+    #[test]
+    fn sanitize_warns_about_overlapping_presets_without_removing_them() {
+        let mut config = FileConfig::default();
+        let mut twin = config.presets[0].clone();
+        twin.id = "twin".to_string();
+        twin.name = "Twin".to_string();
+        twin.weekday = twin.weekday.to_ascii_uppercase();
+        twin.builtin = false;
+        let original_name = config.presets[0].name.clone();
+        config.presets.push(twin);
+        let count = config.presets.len();
+
+        let (config, warnings, _) = sanitize_config(config);
+
+        assert_eq!(config.presets.len(), count);
+        let overlap: Vec<_> = warnings
+            .iter()
+            .filter(|warning| warning.code == WarningCode::OverlappingPresets)
+            .collect();
+        assert_eq!(overlap.len(), 1, "warnings: {warnings:?}");
+        assert!(overlap[0].message.contains(&format!("'{}'", original_name)));
+        assert!(overlap[0].message.contains("'Twin'"));
+    }
+
+    #[test]
+    fn preset_diagnostics_reports_invalid_fields() {
+        let mut presets = default_presets();
+        assert!(preset_diagnostics(&presets).is_empty());
+
+        presets[0].timezone = "Mars/Olympus_Mons".to_string();
+        presets[1].weekday = "someday".to_string();
+        presets[2].start_time = "7pm".to_string();
+        presets[3].file_prefix = " ".to_string();
+        presets[3].duration_minutes = 0;
+
+        let findings = preset_diagnostics(&presets);
+        assert_eq!(findings.len(), 5, "findings: {findings:?}");
+        assert!(findings[0].contains("invalid timezone 'Mars/Olympus_Mons'"));
+        assert!(findings[1].contains("unknown weekday 'someday'"));
+        assert!(findings[2].contains("HH:MM"));
+    }
+
+    #[test]
+    fn parse_preset_file_reads_presets_for_validation() {
+        let raw = r#"
+[[presets]]
+name = "Thursday Social"
+weekday = "thursday"
+timezone = "America/New_York"
+start_time = "20:00"
+duration_minutes = 90
+file_prefix = "social"
+
+[[presets]]
+name = ""
+weekday = "thursday"
+timezone = "Nowhere/Special"
+start_time = "20:00"
+duration_minutes = 90
+file_prefix = "broken"
+"#;
+        let presets = parse_preset_file(raw).expect("parse");
+        assert_eq!(presets.len(), 2);
+        assert!(preset_problems(&presets[0]).is_empty());
+        let problems = preset_problems(&presets[1]);
+        assert_eq!(problems.len(), 2, "problems: {problems:?}");
+        assert!(problems[0].contains("empty name"));
+        assert!(problems[1].contains("invalid timezone 'Nowhere/Special'"));
+
+        assert!(parse_preset_file("[[presets]]\nname = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn test_glossary_file_entries_extend_and_override_the_config() {
+        let raw = "# term\tdefinition\nDwemer\tthe vanished deep elves\r\n\n  Kwama \t egg-laying mine creatures\n";
+        let entries = parse_glossary_tsv(raw).expect("parse");
+        assert_eq!(
+            entries,
+            vec![
+                ("Dwemer".to_string(), "the vanished deep elves".to_string()),
+                ("Kwama".to_string(), "egg-laying mine creatures".to_string()),
+            ]
+        );
+        let err = parse_glossary_tsv("Dwemer\n").unwrap_err();
+        assert!(err.starts_with("line 1:"), "{err}");
+
+        let mut config = ConvocationsConfig {
+            glossary: vec![("Dwemer".to_string(), "dwarves".to_string())],
+            ..ConvocationsConfig::default()
+        };
+        let overrides = RuntimeOverrides {
+            extra_glossary: Some(entries),
+            ..RuntimeOverrides::default()
+        };
+        apply_runtime_overrides(&mut config, &overrides, &default_presets(), &mut Vec::new());
+        assert_eq!(config.glossary.len(), 2);
+        assert_eq!(config.glossary[0].1, "the vanished deep elves");
+    }
+
+    #[test]
+    fn filename_template_expands_placeholders() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 8).unwrap();
+        assert_eq!(
+            render_filename_template("conv-{yyyy}-{mm}-{dd}.txt", "conv", "sat", date).unwrap(),
+            "conv-2025-03-08.txt"
+        );
+        assert_eq!(
+            render_filename_template("{preset}_{prefix}_{date}.log", "rsm7", "tue-7", date)
+                .unwrap(),
+            "tue-7_rsm7_030825.log"
+        );
+    }
+
+    #[test]
+    fn filename_template_rejects_bad_output() {
+        for template in [
+            "logs/{date}.txt",
+            "..\\{date}.txt",
+            "{weekday}.txt",
+            "   ",
+            "..",
+        ] {
+            assert!(
+                validate_filename_template(template).is_err(),
+                "expected '{template}' to be rejected"
+            );
+        }
+        assert!(validate_filename_template("{prefix}-{date}.txt").is_ok());
+    }
+
+    #[test]
+    fn sanitize_clears_output_directory_that_is_a_file() {
+        let file = tempfile::NamedTempFile::new().expect("temp file");
+        let mut config = FileConfig::default();
+        config.runtime.output_target = OutputTarget::Directory;
+        config.runtime.output_directory_override = Some(file.path().to_string_lossy().into_owned());
+        config.runtime.llm_concurrency = 3;
+
+        let (config, warnings, _) = sanitize_config(config);
+
+        assert!(warnings.iter().any(|warning| {
+            warning.code == WarningCode::InvalidRuntimeSettings
+                && warning.message.contains("output_directory_override")
+                && warning.message.contains("is a file")
+        }));
+        assert_eq!(config.runtime.output_directory_override, None);
+        assert_eq!(config.runtime.llm_concurrency, 3);
+    }
+
+    #[test]
+    fn sanitize_drops_invalid_filename_template() {
+        let mut config = FileConfig::default();
+        config.presets[0].filename_template = Some("out/{date}.txt".to_string());
+        config.presets[1].filename_template = Some("{prefix}-{yyyy}.txt".to_string());
+        let kept_id = config.presets[1].id.clone();
+
+        let (config, warnings, _) = sanitize_config(config);
+
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.message.contains("path separators"))
+        );
+        let kept = find_preset(&config.presets, &kept_id).unwrap();
+        assert_eq!(
+            kept.filename_template.as_deref(),
+            Some("{prefix}-{yyyy}.txt")
+        );
+        assert_eq!(
+            config
+                .presets
+                .iter()
+                .filter(|preset| preset.filename_template.is_some())
+                .count(),
+            1
+        );
+    }
+
     #[test]
     fn test_sanitize_bad_toml_duplicate_presets() {
         let mut config = FileConfig::default();
         // Add a duplicate preset
         config.presets.push(PresetDefinition {
+            id: String::new(),
             name: SATURDAY_PRESET_NAME.to_string(),
             weekday: "saturday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -1175,6 +3230,8 @@ mod tests {
             duration_minutes: 145,
             file_prefix: "conv".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
@@ -1193,7 +3250,9 @@ mod tests {
 
         // Should have a warning about duplicates
         assert!(
-            warnings.iter().any(|w| w.contains("duplicate")),
+            warnings
+                .iter()
+                .any(|w| w.code == WarningCode::DuplicatePreset),
             "Should warn about duplicate preset names"
         );
     }
@@ -1203,6 +3262,7 @@ mod tests {
         let mut config = FileConfig::default();
         // Create a preset with zero duration
         config.presets.push(PresetDefinition {
+            id: "bad-preset".to_string(),
             name: "Bad Preset".to_string(),
             weekday: "monday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -1210,6 +3270,8 @@ mod tests {
             duration_minutes: 0,
             file_prefix: "bad".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
@@ -1228,16 +3290,43 @@ mod tests {
         assert!(
             warnings
                 .iter()
-                .any(|w| w.contains("Bad Preset") && w.contains("duration_minutes")),
+                .any(|w| w.message.contains("Bad Preset") && w.message.contains("duration_minutes")),
             "Should warn about zero duration"
         );
     }
 
+    #[test]
+    fn test_sanitize_drops_malformed_preset_model() {
+        let mut config = FileConfig::default();
+        config.presets[0].llm_model = Some(" openai/gpt-4o-mini ".to_string());
+        config.presets[1].llm_model = Some("gpt 4".to_string());
+        let kept = config.presets[0].id.clone();
+        let dropped = config.presets[1].id.clone();
+
+        let (sanitized, warnings, _) = sanitize_config(config);
+        let model = |id: &str| {
+            find_preset(&sanitized.presets, id).and_then(|preset| preset.llm_model.clone())
+        };
+        assert_eq!(model(&kept).as_deref(), Some("openai/gpt-4o-mini"));
+        assert_eq!(model(&dropped), None);
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.code == WarningCode::InvalidPreset && w.message.contains("gpt 4")),
+            "warnings: {warnings:?}"
+        );
+
+        assert!(validate_model_slug("google/gemma-3-27b-it:free").is_ok());
+        assert!(validate_model_slug("auto").is_err());
+        assert!(validate_model_slug("/model").is_err());
+    }
+
     #[test]
     fn test_sanitize_preset_empty_prefix() {
         let mut config = FileConfig::default();
         // Create a preset with empty prefix
         config.presets.push(PresetDefinition {
+            id: "no-prefix".to_string(),
             name: "No Prefix".to_string(),
             weekday: "tuesday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -1245,6 +3334,8 @@ mod tests {
             duration_minutes: 60,
             file_prefix: "  ".to_string(), // whitespace only
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
@@ -1261,11 +3352,44 @@ mod tests {
         assert!(
             warnings
                 .iter()
-                .any(|w| w.contains("No Prefix") && w.contains("file_prefix")),
+                .any(|w| w.message.contains("No Prefix") && w.message.contains("file_prefix")),
             "Should warn about empty prefix"
         );
     }
 
+    #[test]
+    fn test_unwritable_config_dir_reports_path_and_cause() {
+        use std::io::{Error, ErrorKind};
+
+        let path = Path::new("/readonly/convocations/config.toml");
+        for kind in [ErrorKind::PermissionDenied, ErrorKind::ReadOnlyFilesystem] {
+            let err = ConfigError::write(path, Error::from(kind));
+            assert!(matches!(err, ConfigError::NotWritable { .. }), "{kind:?}");
+            let message = err.to_string();
+            assert!(message.contains("/readonly/convocations/config.toml"));
+            assert!(message.contains("read-only"), "{message}");
+        }
+
+        let err = ConfigError::write(path, Error::other("disk on fire"));
+        assert!(matches!(err, ConfigError::Io(_)));
+    }
+
+    #[test]
+    fn test_sanitize_empty_channel_styles_restores_defaults() {
+        let mut config = FileConfig::default();
+        config.runtime.channel_styles = BTreeMap::from([(" ".to_string(), ChannelStyle::Raw)]);
+
+        let (sanitized, warnings, _) = sanitize_config(config);
+
+        assert_eq!(sanitized.runtime.channel_styles, default_channel_styles());
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.code == WarningCode::InvalidChannelStyle),
+            "Should warn about the unusable channel map"
+        );
+    }
+
     #[test]
     fn test_sanitize_invalid_duration_override_infinite() {
         let mut config = FileConfig::default();
@@ -1282,7 +3406,7 @@ mod tests {
 
         // Should have a warning
         assert!(
-            warnings.iter().any(|w| w.contains("finite")),
+            warnings.iter().any(|w| w.message.contains("finite")),
             "Should warn about non-finite hours"
         );
     }
@@ -1303,11 +3427,26 @@ mod tests {
 
         // Should have a warning
         assert!(
-            warnings.iter().any(|w| w.contains("at least 1.0")),
+            warnings.iter().any(|w| w.message.contains("at least 1.0")),
             "Should warn about hours < 1.0"
         );
     }
 
+    #[test]
+    fn test_ooc_line_pattern_parse_and_match() {
+        let slashes = OocLinePattern::parse("//").expect("prefix-only pattern");
+        assert_eq!(slashes, OocLinePattern::new("//", None));
+        assert!(slashes.matches("// brb, phone"));
+        assert!(!slashes.matches("Hello // there"));
+
+        let angled = OocLinePattern::parse("<< >>").expect("prefix/suffix pattern");
+        assert!(angled.matches("<<lag, sorry>>"));
+        assert!(!angled.matches("<<lag, sorry"));
+
+        assert!(OocLinePattern::parse("  ").is_err());
+        assert!(OocLinePattern::parse("a b c").is_err());
+    }
+
     #[test]
     fn test_sanitize_invalid_active_preset() {
         let mut config = FileConfig::default();
@@ -1316,13 +3455,13 @@ mod tests {
         let (sanitized, warnings, _) = sanitize_config(config);
 
         // Should have reset to default
-        assert_eq!(sanitized.runtime.active_preset, SATURDAY_PRESET_NAME);
+        assert_eq!(sanitized.runtime.active_preset, SATURDAY_PRESET_ID);
 
         // Should have a warning
         assert!(
-            warnings
-                .iter()
-                .any(|w| w.contains("nonexistent-preset") && w.contains("not found")),
+            warnings.iter().any(
+                |w| w.message.contains("nonexistent-preset") && w.message.contains("not found")
+            ),
             "Should warn about missing preset"
         );
     }
@@ -1340,7 +3479,9 @@ mod tests {
 
         // Should have a warning
         assert!(
-            warnings.iter().any(|w| w.contains("schema version")),
+            warnings
+                .iter()
+                .any(|w| w.message.contains("schema version")),
             "Should warn about unknown schema version"
         );
     }
@@ -1373,6 +3514,81 @@ mod tests {
         assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
     }
 
+    #[test]
+    fn test_diff_and_keep_original_map_independently() {
+        let presets = default_presets();
+        for (keep, show) in [(false, false), (true, false), (false, true), (true, true)] {
+            let runtime = RuntimePreferences {
+                keep_original_output: keep,
+                show_diff: show,
+                ..RuntimePreferences::default()
+            };
+            let (config, warnings) = runtime_preferences_to_convocations(&runtime, &presets);
+            assert_eq!(config.keep_orig, keep);
+            assert_eq!(config.no_diff, !show);
+            assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+        }
+
+        let runtime = RuntimePreferences {
+            use_ai_corrections: false,
+            keep_original_output: true,
+            ..RuntimePreferences::default()
+        };
+        let (_, warnings) = runtime_preferences_to_convocations(&runtime, &presets);
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.code == WarningCode::LlmOnlyOption)
+        );
+
+        let mut config = ConvocationsConfig::default();
+        let mut warnings = Vec::new();
+        let overrides = RuntimeOverrides {
+            use_llm: Some(false),
+            keep_orig: Some(true),
+            ..RuntimeOverrides::default()
+        };
+        apply_runtime_overrides(&mut config, &overrides, &presets, &mut warnings);
+        assert_eq!(
+            warnings
+                .iter()
+                .filter(|w| w.code == WarningCode::LlmOnlyOption)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_warning_codes_are_reported_and_can_be_silenced() {
+        for code in WarningCode::ALL {
+            assert_eq!(
+                serde_json::to_value(code).unwrap(),
+                serde_json::Value::String(code.as_str().to_string())
+            );
+        }
+
+        let mut runtime = RuntimePreferences {
+            active_preset: "gone".to_string(),
+            ..RuntimePreferences::default()
+        };
+        let (_, warnings) = runtime_preferences_to_convocations(&runtime, &default_presets());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::MissingPreset);
+        assert_eq!(
+            warnings[0].to_string(),
+            "Preset 'gone' not found. Falling back to Saturday configuration."
+        );
+        assert_eq!(
+            serde_json::to_value(&warnings[0]).unwrap()["code"],
+            "missing-preset"
+        );
+
+        runtime.silenced_warnings =
+            vec![" Missing-Preset ".to_string(), "no-such-code".to_string()];
+        let (_, warnings) = runtime_preferences_to_convocations(&runtime, &default_presets());
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+    }
+
     #[test]
     fn test_preset_crud_add_user_preset() {
         let mut config = FileConfig::default();
@@ -1380,6 +3596,7 @@ mod tests {
 
         // Add a custom user preset
         config.presets.push(PresetDefinition {
+            id: "wednesday-event".to_string(),
             name: "Wednesday Event".to_string(),
             weekday: "wednesday".to_string(),
             timezone: "America/Los_Angeles".to_string(),
@@ -1387,6 +3604,8 @@ mod tests {
             duration_minutes: 90,
             file_prefix: "wed".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
@@ -1413,6 +3632,7 @@ mod tests {
 
         // Add a custom preset
         config.presets.push(PresetDefinition {
+            id: "temporary".to_string(),
             name: "Temporary".to_string(),
             weekday: "thursday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -1420,6 +3640,8 @@ mod tests {
             duration_minutes: 60,
             file_prefix: "temp".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
@@ -1449,6 +3671,7 @@ mod tests {
 
         // Add a custom preset
         config.presets.push(PresetDefinition {
+            id: "original-name".to_string(),
             name: "Original Name".to_string(),
             weekday: "monday".to_string(),
             timezone: "America/New_York".to_string(),
@@ -1456,6 +3679,8 @@ mod tests {
             duration_minutes: 60,
             file_prefix: "orig".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
@@ -1570,6 +3795,117 @@ mod tests {
         assert!(warnings.is_empty());
     }
 
+    #[test]
+    fn test_sanitize_clamps_llm_request_settings() {
+        let mut config = FileConfig::default();
+        config.runtime.llm_concurrency = 0;
+        config.runtime.llm_max_retries = 50;
+        config.runtime.llm_timeout_secs = 3600;
+
+        let (sanitized, warnings, _) = sanitize_config(config);
+        assert_eq!(sanitized.runtime.llm_concurrency, 1);
+        assert_eq!(sanitized.runtime.llm_max_retries, MAX_LLM_MAX_RETRIES);
+        assert_eq!(sanitized.runtime.llm_timeout_secs, MAX_LLM_TIMEOUT_SECS);
+        assert_eq!(warnings.len(), 3, "unexpected warnings: {:?}", warnings);
+        assert!(
+            warnings
+                .iter()
+                .all(|warning| warning.code == WarningCode::InvalidLlmSetting)
+        );
+
+        let mut config = FileConfig::default();
+        config.runtime.llm_concurrency = 64;
+        let (sanitized, _, _) = sanitize_config(config);
+        assert_eq!(sanitized.runtime.llm_concurrency, MAX_LLM_CONCURRENCY);
+    }
+
+    #[test]
+    fn test_renaming_active_custom_preset_preserves_selection() {
+        let mut config = FileConfig::default();
+        config.presets.push(PresetDefinition {
+            id: "thursday-social".to_string(),
+            name: "Thursday Social".to_string(),
+            weekday: "thursday".to_string(),
+            timezone: "America/New_York".to_string(),
+            start_time: "20:00".to_string(),
+            duration_minutes: 90,
+            file_prefix: "social".to_string(),
+            default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
+            builtin: false,
+        });
+        config.runtime.active_preset = "thursday-social".to_string();
+
+        if let Some(preset) = config
+            .presets
+            .iter_mut()
+            .find(|p| p.id == "thursday-social")
+        {
+            preset.name = "Thursday Mixer".to_string();
+        }
+
+        let (sanitized, warnings, _) = sanitize_config(config);
+        assert!(
+            !warnings.iter().any(|w| w.message.contains("not found")),
+            "active preset should resolve: {:?}",
+            warnings
+        );
+        assert_eq!(sanitized.runtime.active_preset, "thursday-social");
+        let active = find_preset(&sanitized.presets, &sanitized.runtime.active_preset)
+            .expect("renamed preset still resolves");
+        assert_eq!(active.name, "Thursday Mixer");
+        assert_eq!(active.file_prefix, "social");
+    }
+
+    #[test]
+    fn test_sanitize_migrates_name_keyed_active_preset_and_missing_ids() {
+        let mut config = FileConfig::default();
+        for preset in &mut config.presets {
+            preset.id.clear();
+        }
+        config.presets.push(PresetDefinition {
+            id: String::new(),
+            name: "Late Show".to_string(),
+            weekday: "friday".to_string(),
+            timezone: "America/New_York".to_string(),
+            start_time: "23:00".to_string(),
+            duration_minutes: 60,
+            file_prefix: "late".to_string(),
+            default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
+            builtin: false,
+        });
+        config.runtime.active_preset = "Late Show".to_string();
+
+        let (sanitized, warnings, _) = sanitize_config(config);
+        assert!(
+            !warnings.iter().any(|w| w.message.contains("not found")),
+            "active preset should resolve: {:?}",
+            warnings
+        );
+        assert_eq!(sanitized.runtime.active_preset, "late-show");
+        assert!(
+            sanitized
+                .presets
+                .iter()
+                .any(|p| p.id == TUESDAY_7_PRESET_ID && p.name == TUESDAY_7_PRESET_NAME)
+        );
+        let mut ids: Vec<&str> = sanitized.presets.iter().map(|p| p.id.as_str()).collect();
+        let total = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), total, "preset ids must be unique");
+    }
+
+    #[test]
+    fn test_unique_preset_id_suffixes_collisions() {
+        let presets = default_presets();
+        assert_eq!(unique_preset_id(&presets, "Tuesday 7pm"), "tuesday-7pm-2");
+        assert_eq!(unique_preset_id(&presets, "Sunday Brunch"), "sunday-brunch");
+    }
+
     #[test]
     fn test_apply_runtime_overrides_preset_change() {
         let mut config = ConvocationsConfig::default();
@@ -1577,14 +3913,14 @@ mod tests {
         let mut warnings = Vec::new();
 
         // Default should be Saturday
-        assert_eq!(config.active_preset, SATURDAY_PRESET_NAME);
+        assert_eq!(config.active_preset, SATURDAY_PRESET_ID);
 
         let mut overrides = RuntimeOverrides::default();
-        overrides.active_preset = Some(TUESDAY_7_PRESET_NAME.to_string());
+        overrides.active_preset = Some(TUESDAY_7_PRESET_ID.to_string());
 
         apply_runtime_overrides(&mut config, &overrides, &presets, &mut warnings);
 
-        assert_eq!(config.active_preset, TUESDAY_7_PRESET_NAME);
+        assert_eq!(config.active_preset, TUESDAY_7_PRESET_ID);
         assert!(config.rsm7, "RSM7 flag should be set");
         assert!(!config.rsm8, "RSM8 flag should not be set");
         assert!(!config.tp6, "TP6 flag should not be set");
@@ -1614,6 +3950,7 @@ mod tests {
 
         let mut new_presets = default_presets();
         new_presets.push(PresetDefinition {
+            id: "new-preset".to_string(),
             name: "New Preset".to_string(),
             weekday: "sunday".to_string(),
             timezone: "America/Chicago".to_string(),
@@ -1621,6 +3958,8 @@ mod tests {
             duration_minutes: 90,
             file_prefix: "new".to_string(),
             default_weeks_ago: 0,
+            filename_template: None,
+            llm_model: None,
             builtin: false,
         });
 
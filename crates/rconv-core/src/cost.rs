@@ -0,0 +1,137 @@
+//! Rough pre-run estimates of LLM token usage and cost, and the cost of the tokens a run
+//! actually used.
+//!
+//! The numbers produced here are intentionally coarse: they are derived from a line count
+//! rather than a real tokenizer so that callers can show a cost badge before a run without
+//! parsing the whole chat log.
+
+use std::fs;
+
+use serde::Serialize;
+
+use crate::curator::{CuratedEntry, CuratedTier};
+use crate::openrouter::TokenUsage;
+use crate::runtime::expand_path;
+
+/// Average number of tokens a single chat log line contributes to a prompt.
+const TOKENS_PER_LINE: u64 = 30;
+
+/// Estimated token usage and cost for an LLM correction pass.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CostEstimate {
+    /// Prompt plus completion tokens.
+    pub estimated_tokens: u64,
+    /// `None` when pricing for the model is unknown.
+    pub estimated_cost_usd: Option<f64>,
+}
+
+/// Estimate token usage for `line_count` log lines corrected by `model`.
+///
+/// The completion is assumed to be roughly the same size as the prompt, since the model
+/// returns the corrected transcript.
+pub fn estimate_for_lines(line_count: usize, model: Option<&CuratedEntry>) -> CostEstimate {
+    let prompt_tokens = line_count as u64 * TOKENS_PER_LINE;
+    let completion_tokens = prompt_tokens;
+
+    CostEstimate {
+        estimated_tokens: prompt_tokens + completion_tokens,
+        estimated_cost_usd: price_tokens(prompt_tokens, completion_tokens, model),
+    }
+}
+
+/// Cost in USD of the tokens in `usage` at `model`'s curated pricing; `None` when the
+/// pricing is unknown.
+pub fn cost_for_usage(usage: &TokenUsage, model: Option<&CuratedEntry>) -> Option<f64> {
+    price_tokens(usage.prompt_tokens, usage.completion_tokens, model)
+}
+
+fn price_tokens(
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    model: Option<&CuratedEntry>,
+) -> Option<f64> {
+    let entry = model?;
+    if entry.tier == CuratedTier::Free {
+        return Some(0.0);
+    }
+    let price_in = entry.price_in_per_million?;
+    let price_out = entry.price_out_per_million?;
+    Some(
+        prompt_tokens as f64 / 1_000_000.0 * price_in
+            + completion_tokens as f64 / 1_000_000.0 * price_out,
+    )
+}
+
+/// Estimate token usage for the non-empty lines of `path`.
+pub fn estimate_for_file(
+    path: &str,
+    model: Option<&CuratedEntry>,
+) -> Result<CostEstimate, std::io::Error> {
+    let expanded = expand_path(path).map_err(std::io::Error::other)?;
+    let data = fs::read_to_string(expanded)?;
+    let line_count = data.lines().filter(|line| !line.trim().is_empty()).count();
+    Ok(estimate_for_lines(line_count, model))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curator::PriceSource;
+
+    fn entry(tier: CuratedTier, price_in: Option<f64>, price_out: Option<f64>) -> CuratedEntry {
+        CuratedEntry {
+            slug: "provider/model".to_string(),
+            display_name: "Model".to_string(),
+            provider: "provider".to_string(),
+            aaii: 50.0,
+            price_in_per_million: price_in,
+            price_out_per_million: price_out,
+            price_source: PriceSource::Openrouter,
+            context_length: Some(128_000),
+            modalities: Vec::new(),
+            match_strategy: None,
+            aa_last_updated: None,
+            tier,
+            openrouter_created_at: None,
+            cheapest_endpoint: None,
+        }
+    }
+
+    #[test]
+    fn estimate_uses_model_pricing() {
+        let model = entry(CuratedTier::Cheap, Some(1.0), Some(2.0));
+        let estimate = estimate_for_lines(1_000, Some(&model));
+        assert_eq!(estimate.estimated_tokens, 60_000);
+        let cost = estimate.estimated_cost_usd.expect("priced model");
+        assert!((cost - 0.09).abs() < 1e-9, "unexpected cost {cost}");
+    }
+
+    #[test]
+    fn usage_is_priced_per_million_tokens() {
+        let usage = TokenUsage {
+            prompt_tokens: 500_000,
+            completion_tokens: 250_000,
+            total_tokens: 750_000,
+        };
+        let model = entry(CuratedTier::Cheap, Some(1.0), Some(4.0));
+        let cost = cost_for_usage(&usage, Some(&model)).expect("priced model");
+        assert!((cost - 1.5).abs() < 1e-9, "unexpected cost {cost}");
+        assert_eq!(cost_for_usage(&usage, None), None);
+    }
+
+    #[test]
+    fn estimate_without_pricing_has_no_cost() {
+        let model = entry(CuratedTier::Cheap, None, Some(2.0));
+        assert_eq!(
+            estimate_for_lines(10, Some(&model)).estimated_cost_usd,
+            None
+        );
+        assert_eq!(estimate_for_lines(10, None).estimated_cost_usd, None);
+
+        let free = entry(CuratedTier::Free, None, None);
+        assert_eq!(
+            estimate_for_lines(10, Some(&free)).estimated_cost_usd,
+            Some(0.0)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
+use curator_snapshot::curate::has_term;
 use serde::Deserialize;
 use serde::Serialize;
 use thiserror::Error;
@@ -129,6 +130,33 @@ impl ModelPreference {
 
 pub const AUTO_SENTINEL: &str = "auto";
 
+/// How `auto` picks among the curated models of the preferred tier. Without a strategy the
+/// curated order (the provider picks made by the snapshot curator) decides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoStrategy {
+    /// Lowest combined input and output price per million tokens.
+    Cheapest,
+    /// Highest AAII score.
+    BestQuality,
+    /// Models whose name marks them as a low-latency variant ("flash", "mini", "lite", ...).
+    Fastest,
+}
+
+impl AutoStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "cheapest" => Some(Self::Cheapest),
+            "best-quality" => Some(Self::BestQuality),
+            "fastest" => Some(Self::Fastest),
+            _ => None,
+        }
+    }
+}
+
+/// Name fragments of model variants tuned for latency.
+const LOW_LATENCY_TERMS: [&str; 6] = ["flash", "mini", "lite", "haiku", "fast", "turbo"];
+
 #[derive(Debug, Error)]
 pub enum CuratorError {
     #[error("failed to read snapshot: {0}")]
@@ -238,6 +266,17 @@ impl CuratedCatalog {
         summaries
     }
 
+    /// How each entry got into the catalog and which snapshot thresholds it meets, limited to
+    /// `slug` when given.
+    pub fn provenance(&self, slug: Option<&str>) -> Vec<CuratedProvenance> {
+        self.free
+            .iter()
+            .chain(self.cheap.iter())
+            .filter(|entry| slug.is_none_or(|slug| entry.slug.eq_ignore_ascii_case(slug)))
+            .map(|entry| entry.provenance(&self.metadata.thresholds))
+            .collect()
+    }
+
     fn find(&self, slug: &str) -> Option<CuratedEntry> {
         self.free
             .iter()
@@ -264,6 +303,127 @@ impl CuratedEntry {
             cheapest_endpoint: self.cheapest_endpoint.clone(),
         }
     }
+
+    fn provenance(&self, thresholds: &ThresholdMetadata) -> CuratedProvenance {
+        let origin = EntryOrigin::from_match_strategy(self.match_strategy.as_deref());
+        // Round away f32 noise so 14.2 is reported as 14.2, not 14.199999809265137
+        let aaii = (f64::from(self.aaii) * 100.0).round() / 100.0;
+        let mut checks = Vec::new();
+        match self.tier {
+            CuratedTier::Free => checks.push(ThresholdCheck::at_least(
+                "min_free_aaii",
+                Some(aaii),
+                f64::from(thresholds.min_free_aaii),
+            )),
+            CuratedTier::Cheap => {
+                checks.push(ThresholdCheck::at_least(
+                    "min_paid_aaii",
+                    Some(aaii),
+                    f64::from(thresholds.min_paid_aaii),
+                ));
+                checks.push(ThresholdCheck::at_most(
+                    "cheap_in_max",
+                    self.price_in_per_million,
+                    thresholds.cheap_in_max,
+                ));
+                checks.push(ThresholdCheck::at_most(
+                    "cheap_out_max",
+                    self.price_out_per_million,
+                    thresholds.cheap_out_max,
+                ));
+            }
+        }
+        checks.push(ThresholdCheck::at_least(
+            "min_context_length",
+            self.context_length.map(f64::from),
+            f64::from(thresholds.min_context_length),
+        ));
+
+        CuratedProvenance {
+            promoted: origin == EntryOrigin::Matched && checks.iter().any(|check| !check.passed),
+            fallback: matches!(
+                origin,
+                EntryOrigin::SeriesFallback | EntryOrigin::ProviderFallback
+            ),
+            origin,
+            thresholds: checks,
+            summary: self.to_summary(),
+        }
+    }
+}
+
+/// Why a curated entry is in the catalog, see [`CuratedCatalog::provenance`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CuratedProvenance {
+    #[serde(flatten)]
+    pub summary: CuratedModelSummary,
+    pub origin: EntryOrigin,
+    /// Matched to Artificial Analysis data but below a threshold; added to fill the tier.
+    pub promoted: bool,
+    /// Added by a series or provider fallback rather than by matching.
+    pub fallback: bool,
+    pub thresholds: Vec<ThresholdCheck>,
+}
+
+/// How the snapshot generator picked an entry, read from its `match_strategy` label.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryOrigin {
+    /// Matched to Artificial Analysis data (by slug, alias, derivation or fuzzy name).
+    Matched,
+    /// Newest free model of a pinned series.
+    SeriesFallback,
+    /// Chosen by a per-provider heuristic.
+    ProviderHeuristic,
+    /// The provider's fallback pick when nothing else qualified.
+    ProviderFallback,
+    /// No recognizable `match_strategy`.
+    Unknown,
+}
+
+impl EntryOrigin {
+    fn from_match_strategy(strategy: Option<&str>) -> Self {
+        let Some(strategy) = strategy else {
+            return EntryOrigin::Unknown;
+        };
+        let kind = strategy.split(':').next().unwrap_or_default();
+        match kind {
+            "provided-slug" | "alias" | "derived" | "fuzzy" => EntryOrigin::Matched,
+            "manual-series" => EntryOrigin::SeriesFallback,
+            "provider-heuristic" => EntryOrigin::ProviderHeuristic,
+            "provider-fallback" => EntryOrigin::ProviderFallback,
+            _ => EntryOrigin::Unknown,
+        }
+    }
+}
+
+/// One snapshot threshold compared against an entry; unknown values never pass.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ThresholdCheck {
+    pub name: &'static str,
+    pub value: Option<f64>,
+    pub limit: f64,
+    pub passed: bool,
+}
+
+impl ThresholdCheck {
+    fn at_least(name: &'static str, value: Option<f64>, limit: f64) -> Self {
+        Self {
+            name,
+            value,
+            limit,
+            passed: value.is_some_and(|value| value >= limit),
+        }
+    }
+
+    fn at_most(name: &'static str, value: Option<f64>, limit: f64) -> Self {
+        Self {
+            name,
+            value,
+            limit,
+            passed: value.is_some_and(|value| value <= limit),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -310,6 +470,9 @@ pub enum ResolutionSource {
     FallbackNoSnapshot,
     FallbackEmpty,
     FallbackMissingEntry,
+    /// Auto selection was limited to free models and none is available; `model_slug` is
+    /// empty and no correction model should be used.
+    NoFreeModel,
 }
 
 impl CuratedResolution {
@@ -321,6 +484,16 @@ impl CuratedResolution {
             message: details.into(),
         }
     }
+
+    /// The paid fallback model must not stand in when only free models are allowed.
+    fn no_free_model(details: impl Into<String>) -> Self {
+        CuratedResolution {
+            model_slug: String::new(),
+            entry: None,
+            source: ResolutionSource::NoFreeModel,
+            message: details.into(),
+        }
+    }
 }
 
 pub fn load_catalog() -> Result<CuratedCatalog, CuratorError> {
@@ -479,10 +652,15 @@ async fn fetch_live_models() -> Result<Vec<ModelInfo>, openrouter::OpenRouterErr
 pub async fn resolve_preference(
     preference: &ModelPreference,
     free_only: bool,
+    strategy: Option<AutoStrategy>,
     _openrouter_api_key: Option<&str>,
 ) -> CuratedResolution {
+    let free_auto = free_only && matches!(preference, ModelPreference::Auto);
     let catalog = match load_catalog() {
         Ok(catalog) => catalog,
+        Err(err) if free_auto => {
+            return CuratedResolution::no_free_model(format!("no snapshot available ({err})"));
+        }
         Err(err) => {
             return CuratedResolution::fallback(
                 ResolutionSource::FallbackNoSnapshot,
@@ -507,7 +685,7 @@ pub async fn resolve_preference(
 
     let selected = match preference {
         ModelPreference::Explicit(slug) => reconciled.find(slug),
-        ModelPreference::Auto => select_auto(&reconciled, free_only),
+        ModelPreference::Auto => select_auto(&reconciled, free_only, strategy),
     };
 
     match selected {
@@ -525,6 +703,9 @@ pub async fn resolve_preference(
         }
         None => {
             match preference {
+                ModelPreference::Auto if free_only => {
+                    CuratedResolution::no_free_model("the curated catalog has no free models")
+                }
                 ModelPreference::Auto => {
                     // Auto mode with no curated models: use fallback
                     CuratedResolution::fallback(
@@ -547,22 +728,69 @@ pub async fn resolve_preference(
     }
 }
 
-fn select_auto(catalog: &CuratedCatalog, free_only: bool) -> Option<CuratedEntry> {
+/// The entry [`resolve_preference`] would pick from the curated snapshot alone, without
+/// reconciling against live OpenRouter models. `None` when there is no snapshot or no match.
+pub(crate) fn select_from_snapshot(
+    preference: &ModelPreference,
+    free_only: bool,
+    strategy: Option<AutoStrategy>,
+) -> Option<CuratedEntry> {
+    let catalog = load_catalog().ok()?;
+    match preference {
+        ModelPreference::Explicit(slug) => catalog.find(slug),
+        ModelPreference::Auto => select_auto(&catalog, free_only, strategy),
+    }
+}
+
+/// Pick from the cheap tier, falling back to the free tier, or only from the free tier when
+/// `free_only` is set.
+fn select_auto(
+    catalog: &CuratedCatalog,
+    free_only: bool,
+    strategy: Option<AutoStrategy>,
+) -> Option<CuratedEntry> {
     if free_only {
-        catalog
-            .free
-            .first()
-            .cloned()
-            .or_else(|| catalog.cheap.first().cloned())
-    } else {
-        catalog
-            .cheap
-            .first()
-            .cloned()
-            .or_else(|| catalog.free.first().cloned())
+        return select_by_strategy(&catalog.free, strategy).cloned();
+    }
+    select_by_strategy(&catalog.cheap, strategy)
+        .or_else(|| select_by_strategy(&catalog.free, strategy))
+        .cloned()
+}
+
+/// Pick from `entries` (in curated order) by `strategy`; ties keep the curated order.
+fn select_by_strategy(
+    entries: &[CuratedEntry],
+    strategy: Option<AutoStrategy>,
+) -> Option<&CuratedEntry> {
+    let first_best = |key: &dyn Fn(&CuratedEntry) -> f64| {
+        entries
+            .iter()
+            .fold(None, |best: Option<&CuratedEntry>, entry| match best {
+                Some(best) if key(best) >= key(entry) => Some(best),
+                _ => Some(entry),
+            })
+    };
+    match strategy {
+        None => entries.first(),
+        Some(AutoStrategy::Cheapest) => first_best(&|entry| {
+            let price = entry.price_in_per_million.unwrap_or(f64::INFINITY)
+                + entry.price_out_per_million.unwrap_or(f64::INFINITY);
+            -price
+        }),
+        Some(AutoStrategy::BestQuality) => first_best(&|entry| f64::from(entry.aaii)),
+        Some(AutoStrategy::Fastest) => entries
+            .iter()
+            .find(|entry| is_low_latency(entry))
+            .or_else(|| entries.first()),
     }
 }
 
+fn is_low_latency(entry: &CuratedEntry) -> bool {
+    LOW_LATENCY_TERMS
+        .iter()
+        .any(|term| has_term(&entry.slug, term) || has_term(&entry.display_name, term))
+}
+
 fn parse_snapshot(raw: &str) -> Result<SnapshotFile, CuratorError> {
     let snapshot: SnapshotFile = serde_json::from_str(raw)?;
     if snapshot.schema_version != SNAPSHOT_SCHEMA_VERSION {
@@ -760,6 +988,93 @@ pub fn catalog_summaries() -> Result<Vec<CuratedModelSummary>, CuratorError> {
     Ok(catalog.summaries())
 }
 
+/// Provenance of every curated entry, or just `slug`'s, see [`CuratedCatalog::provenance`].
+pub fn explain_catalog(slug: Option<&str>) -> Result<Vec<CuratedProvenance>, CuratorError> {
+    let catalog = load_catalog()?;
+    Ok(catalog.provenance(slug))
+}
+
+/// What changed between two curated snapshots, each list sorted by slug.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnapshotDiff {
+    pub added: Vec<CuratedModelSummary>,
+    pub removed: Vec<CuratedModelSummary>,
+    pub tier_changes: Vec<TierChange>,
+    pub price_changes: Vec<PriceChange>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.tier_changes.is_empty()
+            && self.price_changes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TierChange {
+    pub slug: String,
+    pub from: CuratedTier,
+    pub to: CuratedTier,
+}
+
+/// Per-million-token prices of a model present in both snapshots.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PriceChange {
+    pub slug: String,
+    pub old_in_per_million: Option<f64>,
+    pub old_out_per_million: Option<f64>,
+    pub new_in_per_million: Option<f64>,
+    pub new_out_per_million: Option<f64>,
+}
+
+/// Compare two snapshot files (as raw JSON) by slug.
+pub fn diff_snapshots(old_raw: &str, new_raw: &str) -> Result<SnapshotDiff, CuratorError> {
+    let index = |raw: &str| -> Result<BTreeMap<String, CuratedModelSummary>, CuratorError> {
+        let catalog = convert_snapshot(parse_snapshot(raw)?, CatalogSource::Embedded)?;
+        let mut entries = BTreeMap::new();
+        for summary in catalog.summaries() {
+            entries.entry(summary.slug.clone()).or_insert(summary);
+        }
+        Ok(entries)
+    };
+    let old = index(old_raw)?;
+    let new = index(new_raw)?;
+
+    let mut diff = SnapshotDiff::default();
+    for (slug, before) in &old {
+        let Some(after) = new.get(slug) else {
+            diff.removed.push(before.clone());
+            continue;
+        };
+        if before.tier != after.tier {
+            diff.tier_changes.push(TierChange {
+                slug: slug.clone(),
+                from: before.tier,
+                to: after.tier,
+            });
+        }
+        if before.price_in_per_million != after.price_in_per_million
+            || before.price_out_per_million != after.price_out_per_million
+        {
+            diff.price_changes.push(PriceChange {
+                slug: slug.clone(),
+                old_in_per_million: before.price_in_per_million,
+                old_out_per_million: before.price_out_per_million,
+                new_in_per_million: after.price_in_per_million,
+                new_out_per_million: after.price_out_per_million,
+            });
+        }
+    }
+    diff.added = new
+        .into_iter()
+        .filter(|(slug, _)| !old.contains_key(slug))
+        .map(|(_, summary)| summary)
+        .collect();
+    Ok(diff)
+}
+
 pub fn catalog_for_testing(raw: &str) -> Result<CuratedCatalog, CuratorError> {
     let snapshot = parse_snapshot(raw)?;
     convert_snapshot(snapshot, CatalogSource::Embedded)
@@ -841,6 +1156,86 @@ mod tests {
       ]
     }"#;
 
+    #[test]
+    fn provenance_reports_origin_promotion_and_thresholds() {
+        let mut catalog = catalog_for_testing(SAMPLE_SNAPSHOT).expect("catalog");
+        catalog.cheap[0].aaii = 61.0;
+        catalog.free[0].match_strategy = Some("manual-series:gemma".to_string());
+
+        let all = catalog.provenance(None);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].origin, EntryOrigin::SeriesFallback);
+        assert!(all[0].fallback);
+        assert!(!all[0].promoted);
+        assert!(all[0].thresholds.iter().all(|check| check.passed));
+
+        let cheap = catalog.provenance(Some("PROVIDER/pro-cheap"));
+        assert_eq!(cheap.len(), 1);
+        assert_eq!(cheap[0].origin, EntryOrigin::Matched);
+        assert_eq!(
+            cheap[0].summary.match_strategy.as_deref(),
+            Some("alias:cheap")
+        );
+        assert_eq!(cheap[0].summary.price_source, PriceSource::Openrouter);
+        assert!(cheap[0].promoted);
+        let failed: Vec<&str> = cheap[0]
+            .thresholds
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name)
+            .collect();
+        assert_eq!(failed, vec!["min_paid_aaii"]);
+
+        assert!(catalog.provenance(Some("missing/model")).is_empty());
+    }
+
+    #[test]
+    fn diff_snapshots_reports_added_removed_tier_and_price_changes() {
+        let unchanged = diff_snapshots(SAMPLE_SNAPSHOT, SAMPLE_SNAPSHOT).expect("diff");
+        assert!(unchanged.is_empty());
+
+        let new = SAMPLE_SNAPSHOT
+            .replace("provider/pro-free", "provider/next-free")
+            .replace(
+                "\"price_in_per_million\": 0.75",
+                "\"price_in_per_million\": 0.5",
+            );
+        let diff = diff_snapshots(SAMPLE_SNAPSHOT, &new).expect("diff");
+        let slugs = |entries: &[CuratedModelSummary]| {
+            entries
+                .iter()
+                .map(|entry| entry.slug.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(slugs(&diff.added), vec!["provider/next-free"]);
+        assert_eq!(slugs(&diff.removed), vec!["provider/pro-free"]);
+        assert!(diff.tier_changes.is_empty());
+        assert_eq!(
+            diff.price_changes,
+            vec![PriceChange {
+                slug: "provider/pro-cheap".to_string(),
+                old_in_per_million: Some(0.75),
+                old_out_per_million: Some(3.0),
+                new_in_per_million: Some(0.5),
+                new_out_per_million: Some(3.0),
+            }]
+        );
+
+        let moved = SAMPLE_SNAPSHOT.replace("provider/pro-free", "provider/pro-cheap");
+        let diff = diff_snapshots(&moved, SAMPLE_SNAPSHOT).expect("diff");
+        assert_eq!(
+            diff.tier_changes,
+            vec![TierChange {
+                slug: "provider/pro-cheap".to_string(),
+                from: CuratedTier::Free,
+                to: CuratedTier::Cheap,
+            }]
+        );
+        assert_eq!(slugs(&diff.removed), Vec::<String>::new());
+        assert_eq!(diff.price_changes.len(), 1);
+        assert!(diff_snapshots("{}", SAMPLE_SNAPSHOT).is_err());
+    }
+
     #[test]
     fn parses_sample_snapshot() {
         let catalog = catalog_for_testing(SAMPLE_SNAPSHOT).expect("catalog");
@@ -891,6 +1286,87 @@ mod tests {
         );
     }
 
+    fn strategy_catalog() -> CuratedCatalog {
+        let mut catalog = catalog_for_testing(SAMPLE_SNAPSHOT).expect("catalog");
+        let template = catalog.cheap[0].clone();
+        let entry = |slug: &str, aaii: f32, price_in: f64, price_out: f64| CuratedEntry {
+            slug: slug.to_string(),
+            display_name: slug.to_string(),
+            aaii,
+            price_in_per_million: Some(price_in),
+            price_out_per_million: Some(price_out),
+            ..template.clone()
+        };
+        catalog.cheap = vec![
+            entry("vendor/pro", 70.0, 1.0, 4.0),
+            entry("vendor/ultra", 75.0, 1.2, 5.0),
+            entry("vendor/budget", 66.0, 0.2, 0.8),
+            entry("vendor/pro-flash", 67.0, 0.5, 2.0),
+        ];
+        catalog
+    }
+
+    #[test]
+    fn auto_strategy_selects_by_order_price_quality_or_latency() {
+        let catalog = strategy_catalog();
+        let pick = |strategy| select_auto(&catalog, false, strategy).map(|entry| entry.slug);
+
+        assert_eq!(pick(None).as_deref(), Some("vendor/pro"));
+        assert_eq!(
+            pick(Some(AutoStrategy::Cheapest)).as_deref(),
+            Some("vendor/budget")
+        );
+        assert_eq!(
+            pick(Some(AutoStrategy::BestQuality)).as_deref(),
+            Some("vendor/ultra")
+        );
+        assert_eq!(
+            pick(Some(AutoStrategy::Fastest)).as_deref(),
+            Some("vendor/pro-flash")
+        );
+
+        // Free-only still prefers the free tier whatever the strategy.
+        assert_eq!(
+            select_auto(&catalog, true, Some(AutoStrategy::Cheapest)).map(|entry| entry.slug),
+            Some("provider/pro-free".to_string())
+        );
+    }
+
+    #[test]
+    fn fastest_strategy_matches_whole_name_segments() {
+        let mut catalog = strategy_catalog();
+        let template = catalog.cheap[0].clone();
+        let entry = |slug: &str| CuratedEntry {
+            slug: slug.to_string(),
+            display_name: slug.to_string(),
+            ..template.clone()
+        };
+        catalog.cheap = vec![
+            entry("google/gemini-2.5-pro"),
+            entry("vendor/elite"),
+            entry("openai/gpt-4o-mini"),
+        ];
+        assert_eq!(
+            select_auto(&catalog, false, Some(AutoStrategy::Fastest)).map(|entry| entry.slug),
+            Some("openai/gpt-4o-mini".to_string())
+        );
+    }
+
+    #[test]
+    fn fastest_strategy_falls_back_to_curated_order() {
+        let mut catalog = strategy_catalog();
+        catalog.cheap.retain(|entry| !entry.slug.contains("flash"));
+        assert_eq!(
+            select_auto(&catalog, false, Some(AutoStrategy::Fastest)).map(|entry| entry.slug),
+            Some("vendor/pro".to_string())
+        );
+        assert_eq!(
+            AutoStrategy::parse("Best-Quality"),
+            Some(AutoStrategy::BestQuality)
+        );
+        assert_eq!(AutoStrategy::parse("slowest"), None);
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn resolve_preference_falls_back_when_snapshot_missing() {
         let _test_guard = TEST_MUTEX.lock().unwrap();
@@ -904,7 +1380,7 @@ mod tests {
         });
         set_fetch_models_mock(|| panic!("fetch_models should not be called without a snapshot"));
 
-        let result = resolve_preference(&ModelPreference::Auto, false, None).await;
+        let result = resolve_preference(&ModelPreference::Auto, false, None, None).await;
 
         assert_eq!(result.source, ResolutionSource::FallbackNoSnapshot);
         assert!(result.entry.is_none());
@@ -914,6 +1390,11 @@ mod tests {
             "unexpected message: {}",
             result.message
         );
+
+        // With free models only, the paid default is never used.
+        let result = resolve_preference(&ModelPreference::Auto, true, None, None).await;
+        assert_eq!(result.source, ResolutionSource::NoFreeModel);
+        assert!(result.model_slug.is_empty());
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -924,7 +1405,7 @@ mod tests {
         set_load_catalog_mock(|| catalog_for_testing(SAMPLE_SNAPSHOT));
         set_fetch_models_mock(|| Err(openrouter::OpenRouterError::from("network failure")));
 
-        let result = resolve_preference(&ModelPreference::Auto, false, None).await;
+        let result = resolve_preference(&ModelPreference::Auto, false, None, None).await;
 
         assert_eq!(result.source, ResolutionSource::CuratedAuto);
         let entry = result.entry.expect("expected curated entry");
@@ -932,4 +1413,16 @@ mod tests {
         assert_eq!(result.model_slug, entry.slug);
         assert!(result.message.is_empty());
     }
+
+    #[test]
+    fn free_models_only_never_selects_a_paid_model() {
+        let mut catalog = strategy_catalog();
+        assert_eq!(
+            select_auto(&catalog, true, None).map(|entry| entry.slug),
+            Some("provider/pro-free".to_string())
+        );
+        catalog.free.clear();
+        assert!(select_auto(&catalog, true, None).is_none());
+        assert!(select_auto(&catalog, false, None).is_some());
+    }
 }
@@ -0,0 +1,124 @@
+//! The run history: one JSON record per completed or failed run, appended to
+//! `history.jsonl` under the configuration directory.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_directory;
+
+/// Run history, one JSON object per line, oldest first.
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+/// The previous history file, kept after the current one is rotated.
+pub const ROTATED_HISTORY_FILE_NAME: &str = "history.1.jsonl";
+
+/// What one run processed and how it ended.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// When the run started (RFC 3339, local time).
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<usize>,
+    /// The OpenRouter model used for corrections, when they were on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Path to the current history file.
+pub fn history_path() -> PathBuf {
+    config_directory().join(HISTORY_FILE_NAME)
+}
+
+/// Append `record` to the history, first rotating the file to [`ROTATED_HISTORY_FILE_NAME`]
+/// when it already holds `max_records` records. A limit of 0 never rotates.
+pub fn append_record(record: &HistoryRecord, max_records: usize) -> io::Result<()> {
+    append_record_in(&config_directory(), record, max_records)
+}
+
+/// Up to `last` of the most recent records (all of them when `None`), oldest first.
+pub fn read_history(last: Option<usize>) -> io::Result<Vec<HistoryRecord>> {
+    read_history_in(&config_directory(), last)
+}
+
+fn append_record_in(base: &Path, record: &HistoryRecord, max_records: usize) -> io::Result<()> {
+    fs::create_dir_all(base)?;
+    let path = base.join(HISTORY_FILE_NAME);
+    if max_records > 0 && count_records(&path)? >= max_records {
+        fs::rename(&path, base.join(ROTATED_HISTORY_FILE_NAME))?;
+    }
+    let line = serde_json::to_string(record).map_err(io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", line)
+}
+
+fn read_history_in(base: &Path, last: Option<usize>) -> io::Result<Vec<HistoryRecord>> {
+    let mut records = read_records(&base.join(ROTATED_HISTORY_FILE_NAME))?;
+    records.extend(read_records(&base.join(HISTORY_FILE_NAME))?);
+    if let Some(last) = last {
+        let skip = records.len().saturating_sub(last);
+        records.drain(..skip);
+    }
+    Ok(records)
+}
+
+fn count_records(path: &Path) -> io::Result<usize> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text.lines().filter(|line| !line.trim().is_empty()).count()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+/// Records in `path`; a missing file is empty and unreadable lines are skipped.
+fn read_records(path: &Path) -> io::Result<Vec<HistoryRecord>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(n: usize) -> HistoryRecord {
+        HistoryRecord {
+            timestamp: format!("2025-01-0{}T22:00:00-05:00", n),
+            messages: Some(n),
+            success: true,
+            ..HistoryRecord::default()
+        }
+    }
+
+    #[test]
+    fn history_rotates_at_the_record_limit_and_reads_the_newest() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        for n in 1..=5 {
+            append_record_in(dir.path(), &record(n), 2).expect("append");
+        }
+
+        let current = fs::read_to_string(dir.path().join(HISTORY_FILE_NAME)).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        let all = read_history_in(dir.path(), None).expect("read");
+        assert_eq!(all, vec![record(3), record(4), record(5)]);
+        let last = read_history_in(dir.path(), Some(2)).expect("read last");
+        assert_eq!(last, vec![record(4), record(5)]);
+    }
+}
@@ -1,22 +1,38 @@
 //! Core library crate exposing shared Convocations processing logic.
 
 pub mod config;
+pub mod cost;
 pub mod curator;
+pub mod history;
 pub mod logging;
 pub mod openrouter;
 pub mod runtime;
 pub mod secret_store;
+pub mod timestamp;
+pub mod validation;
+
+#[cfg(test)]
+mod test_support;
 
 pub use config::{
-    ConfigError, ConfigLoadResult, ConfigSource, DurationOverride, FRIDAY_6_PRESET_NAME,
-    FileConfig, PresetDefinition, RuntimeOverrides, RuntimePreferences, SATURDAY_PRESET_NAME,
-    TUESDAY_7_PRESET_NAME, TUESDAY_8_PRESET_NAME, ThemePreference, UiPreferences,
-    apply_runtime_overrides, config_directory, config_path, load_config,
-    runtime_overrides_from_convocations, runtime_preferences_to_convocations, save_config,
-    save_presets_and_ui_only,
+    ConfigError, ConfigLoadResult, ConfigSource, ConfigWarning, DurationOverride,
+    FRIDAY_6_PRESET_ID, FRIDAY_6_PRESET_NAME, FileConfig, PresetDefinition, RuntimeOverrides,
+    RuntimePreferences, SATURDAY_PRESET_ID, SATURDAY_PRESET_NAME, TUESDAY_7_PRESET_ID,
+    TUESDAY_7_PRESET_NAME, TUESDAY_8_PRESET_ID, TUESDAY_8_PRESET_NAME, ThemePreference,
+    UiPreferences, WarningCode, apply_runtime_overrides, config_directory, config_path,
+    find_preset, load_config, render_filename_template, runtime_overrides_from_convocations,
+    runtime_preferences_to_convocations, save_config, save_presets_and_ui_only, unique_preset_id,
+    validate_filename_template,
 };
+pub use cost::CostEstimate;
 pub use runtime::{
-    ConvocationsConfig, OutfileResolution, StageProgressCallback, StageProgressEvent,
-    StageProgressEventKind, calculate_event_dates, resolve_outfile_paths, run_cli, run_with_config,
-    run_with_config_with_progress,
+    ChatMessage, ConvocationsConfig, EventDateCount, FormatOptions, MAX_SPLIT_OCCURRENCES,
+    OutfileResolution, OutputFormat, ParseOptions, ProcessOutput, RunPlan, RuntimeError,
+    SplitEventEntry, SplitEventManifest, StageProgressCallback, StageProgressEvent,
+    StageProgressEventKind, calculate_custom_event_dates, calculate_event_dates,
+    effective_config_toml, explain_run, format_messages, next_occurrence, normalize_config,
+    parse_chatlog, process_text, redact_api_key, resolve_effective_config, resolve_outfile_paths,
+    run_cli, run_split_events, run_with_config, run_with_config_with_progress, scan_event_dates,
+    scan_event_dates_in_file,
 };
+pub use validation::ValidationResult;
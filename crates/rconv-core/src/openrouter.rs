@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::error::Error as StdError;
 use std::fmt;
+use std::time::Duration;
 
 /// Preferred providers for free models
 pub const PREFERRED_FREE_PROVIDERS: &[&str] =
@@ -33,6 +35,44 @@ impl ModelInfo {
 #[derive(Debug)]
 pub struct OpenRouterError {
     message: String,
+    timed_out: bool,
+    status: Option<u16>,
+}
+
+impl OpenRouterError {
+    /// Error for a request that got a non-success HTTP status.
+    pub fn from_status(status: u16, message: impl Into<String>) -> Self {
+        OpenRouterError {
+            message: message.into(),
+            timed_out: false,
+            status: Some(status),
+        }
+    }
+
+    /// Error for a request abandoned after waiting `after` for the server.
+    pub fn timed_out(after: Duration) -> Self {
+        OpenRouterError {
+            message: format!("no response data for {}s", after.as_secs()),
+            timed_out: true,
+            status: None,
+        }
+    }
+
+    /// Returns true when the request was aborted because it exceeded its timeout.
+    pub fn is_timeout(&self) -> bool {
+        self.timed_out
+    }
+
+    /// HTTP status of the failed request, when one was received.
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// Returns true when the model was not found (404) or its provider failed (5xx), so a
+    /// different model may succeed where retrying this one will not.
+    pub fn is_model_unavailable(&self) -> bool {
+        matches!(self.status, Some(404) | Some(500..=599))
+    }
 }
 
 impl fmt::Display for OpenRouterError {
@@ -45,7 +85,11 @@ impl StdError for OpenRouterError {}
 
 impl From<String> for OpenRouterError {
     fn from(msg: String) -> Self {
-        OpenRouterError { message: msg }
+        OpenRouterError {
+            message: msg,
+            timed_out: false,
+            status: None,
+        }
     }
 }
 
@@ -53,6 +97,8 @@ impl From<&str> for OpenRouterError {
     fn from(msg: &str) -> Self {
         OpenRouterError {
             message: msg.to_string(),
+            timed_out: false,
+            status: None,
         }
     }
 }
@@ -61,6 +107,8 @@ impl From<reqwest::Error> for OpenRouterError {
     fn from(err: reqwest::Error) -> Self {
         OpenRouterError {
             message: format!("HTTP error: {}", err),
+            timed_out: err.is_timeout(),
+            status: err.status().map(|status| status.as_u16()),
         }
     }
 }
@@ -123,90 +171,24 @@ pub async fn exchange_code_for_api_key(
     code: &str,
     code_verifier: &str,
 ) -> Result<String, OpenRouterError> {
-    #[derive(Serialize)]
-    struct ExchangeRequest<'a> {
-        code: &'a str,
-        code_verifier: &'a str,
-        code_challenge_method: &'a str,
-    }
-
-    #[derive(Deserialize)]
-    struct ExchangeResponse {
-        key: String,
-    }
-
-    let client = reqwest::Client::new();
-    let response = client
-        .post("https://openrouter.ai/api/v1/auth/keys")
-        .json(&ExchangeRequest {
-            code,
-            code_verifier,
-            code_challenge_method: "S256",
-        })
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        return Err(OpenRouterError::from(format!(
-            "Failed to exchange code: {}",
-            response.status()
-        )));
-    }
-
-    let body: ExchangeResponse = response.json().await?;
-    Ok(body.key)
+    OpenRouterClient::default()
+        .exchange_code_for_api_key(code, code_verifier)
+        .await
 }
 
 /// Fetch the list of available models from OpenRouter API
 pub async fn fetch_models() -> Result<Vec<ModelInfo>, OpenRouterError> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get("https://openrouter.ai/api/v1/models")
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        return Err(OpenRouterError::from(format!(
-            "Failed to fetch models: {}",
-            response.status()
-        )));
-    }
-
-    #[derive(Deserialize)]
-    struct ModelsResponse {
-        data: Vec<ApiModel>,
-    }
-
-    #[derive(Deserialize)]
-    struct ApiModel {
-        id: String,
-        name: Option<String>,
-        pricing: ApiPricing,
-        context_length: Option<u32>,
-    }
-
-    #[derive(Deserialize)]
-    struct ApiPricing {
-        prompt: String,
-        completion: String,
-    }
-
-    let body: ModelsResponse = response.json().await?;
-    let models = body
-        .data
-        .into_iter()
-        .map(|m| ModelInfo {
-            id: m.id.clone(),
-            name: m.name.unwrap_or(m.id),
-            pricing: ModelPricing {
-                prompt: m.pricing.prompt,
-                completion: m.pricing.completion,
-            },
-            context_length: m.context_length,
-        })
-        .collect();
+    OpenRouterClient::default().fetch_models().await
+}
 
-    Ok(models)
+/// [`fetch_models`] with a run's connect and read limits (see [`CompletionOptions`]).
+pub async fn fetch_models_with_timeouts(
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+) -> Result<Vec<ModelInfo>, OpenRouterError> {
+    OpenRouterClient::with_timeouts(connect_timeout, read_timeout)
+        .fetch_models()
+        .await
 }
 
 /// Filter models based on free/paid preference and preferred providers
@@ -241,79 +223,668 @@ pub fn filter_models(models: Vec<ModelInfo>, free_only: bool) -> Vec<ModelInfo>
     filtered
 }
 
+/// Base URL of the OpenRouter REST API.
+pub const OPENROUTER_API_BASE: &str = "https://openrouter.ai/api/v1";
+
+/// Default per-request timeout for completion calls.
+pub const DEFAULT_COMPLETION_TIMEOUT_SECS: u64 = 60;
+
+/// Default limit on establishing a connection to OpenRouter.
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Default limit on waiting for the response to start, or for the next chunk of its body.
+pub const DEFAULT_READ_TIMEOUT_SECS: u64 = DEFAULT_COMPLETION_TIMEOUT_SECS;
+
+/// Default sampling temperature for completion calls.
+pub const DEFAULT_TEMPERATURE: f32 = 0.3;
+
+/// Default `HTTP-Referer` sent for OpenRouter app attribution.
+pub const DEFAULT_APP_REFERER: &str = "https://github.com/allquixotic/convocations";
+
+/// Default `X-Title` sent for OpenRouter app attribution.
+pub const DEFAULT_APP_TITLE: &str = "Convocations";
+
+/// Tunables applied to a single completion request.
+#[derive(Debug, Clone)]
+pub struct CompletionOptions {
+    pub temperature: f32,
+    /// Sends `reasoning: { enabled: false }` so reasoning models skip their
+    /// thinking phase; models without reasoning ignore it.
+    pub disable_reasoning: bool,
+    /// Overall request timeout; `None` waits indefinitely.
+    pub timeout: Option<Duration>,
+    /// Limit on connecting, applied when [`complete`] builds its HTTP client.
+    pub connect_timeout: Option<Duration>,
+    /// Limit on waiting for the response to start or for the next chunk of its body, so a
+    /// stalled server fails even while the overall timeout has time left.
+    pub read_timeout: Option<Duration>,
+    /// Sent as `HTTP-Referer` when non-empty.
+    pub referer: String,
+    /// Sent as `X-Title` when non-empty.
+    pub title: String,
+    /// Additional headers attached verbatim (e.g. for proxies).
+    pub extra_headers: BTreeMap<String, String>,
+}
+
+impl Default for CompletionOptions {
+    fn default() -> Self {
+        Self {
+            temperature: DEFAULT_TEMPERATURE,
+            disable_reasoning: false,
+            timeout: Some(Duration::from_secs(DEFAULT_COMPLETION_TIMEOUT_SECS)),
+            connect_timeout: Some(Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS)),
+            read_timeout: Some(Duration::from_secs(DEFAULT_READ_TIMEOUT_SECS)),
+            referer: DEFAULT_APP_REFERER.to_string(),
+            title: DEFAULT_APP_TITLE.to_string(),
+            extra_headers: BTreeMap::new(),
+        }
+    }
+}
+
+/// Token counts OpenRouter reports in a completion's `usage` object.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub total_tokens: u64,
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// The text of a completion plus its token usage, when the response included it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Completion {
+    pub content: String,
+    pub usage: Option<TokenUsage>,
+}
+
+impl From<String> for Completion {
+    fn from(content: String) -> Self {
+        Self {
+            content,
+            usage: None,
+        }
+    }
+}
+
+impl From<&str> for Completion {
+    fn from(content: &str) -> Self {
+        content.to_string().into()
+    }
+}
+
 /// Send a completion request to OpenRouter
 pub async fn complete(
     api_key: &str,
     model: &str,
     prompt: &str,
-    temperature: f32,
-) -> Result<String, OpenRouterError> {
-    #[derive(Serialize)]
-    struct CompletionRequest {
-        model: String,
-        messages: Vec<Message>,
-        temperature: f32,
+    options: &CompletionOptions,
+) -> Result<Completion, OpenRouterError> {
+    let mut http = reqwest::Client::builder();
+    if let Some(connect_timeout) = options.connect_timeout {
+        http = http.connect_timeout(connect_timeout);
     }
+    OpenRouterClient::new(http.build()?, OPENROUTER_API_BASE)
+        .complete(api_key, model, prompt, options)
+        .await
+}
 
-    #[derive(Serialize)]
-    struct Message {
-        role: String,
-        content: String,
+/// `future`, failing as timed out when it takes longer than `limit`.
+async fn within<T>(
+    limit: Option<Duration>,
+    future: impl std::future::Future<Output = Result<T, reqwest::Error>>,
+) -> Result<T, OpenRouterError> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, future)
+            .await
+            .map_err(|_| OpenRouterError::timed_out(limit))?
+            .map_err(OpenRouterError::from),
+        None => future.await.map_err(OpenRouterError::from),
     }
+}
 
-    #[derive(Deserialize)]
-    struct CompletionResponse {
-        choices: Vec<Choice>,
+/// Read the rest of `response`, failing as timed out when no data arrives for `limit`.
+async fn read_body(
+    response: &mut reqwest::Response,
+    limit: Option<Duration>,
+) -> Result<Vec<u8>, OpenRouterError> {
+    let mut body = Vec::new();
+    while let Some(chunk) = within(limit, response.chunk()).await? {
+        body.extend_from_slice(&chunk);
     }
+    Ok(body)
+}
 
-    #[derive(Deserialize)]
-    struct Choice {
-        message: ResponseMessage,
+/// HTTP client and base URL used for OpenRouter API calls. The default talks to
+/// [`OPENROUTER_API_BASE`] with the default connect and read limits; tests point it at a local
+/// server instead.
+#[derive(Debug, Clone)]
+pub struct OpenRouterClient {
+    http: reqwest::Client,
+    base_url: String,
+    /// Read limit for calls other than [`Self::complete`], which takes its own from
+    /// [`CompletionOptions`].
+    read_timeout: Option<Duration>,
+}
+
+impl Default for OpenRouterClient {
+    fn default() -> Self {
+        Self::with_timeouts(
+            Some(Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS)),
+            Some(Duration::from_secs(DEFAULT_READ_TIMEOUT_SECS)),
+        )
     }
+}
 
-    #[derive(Deserialize)]
-    struct ResponseMessage {
-        content: String,
+impl OpenRouterClient {
+    pub fn new(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            read_timeout: None,
+        }
     }
 
-    let client = reqwest::Client::new();
+    /// A client for [`OPENROUTER_API_BASE`] with these connect and read limits.
+    pub fn with_timeouts(
+        connect_timeout: Option<Duration>,
+        read_timeout: Option<Duration>,
+    ) -> Self {
+        let mut http = reqwest::Client::builder();
+        if let Some(connect_timeout) = connect_timeout {
+            http = http.connect_timeout(connect_timeout);
+        }
+        Self::new(http.build().unwrap_or_default(), OPENROUTER_API_BASE)
+            .with_read_timeout(read_timeout)
+    }
 
-    let request_body = CompletionRequest {
-        model: model.to_string(),
-        messages: vec![Message {
-            role: "user".to_string(),
-            content: prompt.to_string(),
-        }],
-        temperature,
-    };
+    pub fn with_read_timeout(mut self, read_timeout: Option<Duration>) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
 
-    let response = client
-        .post("https://openrouter.ai/api/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        return Err(OpenRouterError::from(format!(
-            "OpenRouter API error: {}",
-            response.status()
-        )));
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+
+    /// Exchange an authorization code for an OpenRouter API key.
+    pub async fn exchange_code_for_api_key(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String, OpenRouterError> {
+        #[derive(Serialize)]
+        struct ExchangeRequest<'a> {
+            code: &'a str,
+            code_verifier: &'a str,
+            code_challenge_method: &'a str,
+        }
+
+        #[derive(Deserialize)]
+        struct ExchangeResponse {
+            key: String,
+        }
+
+        let response = self
+            .http
+            .post(self.url("auth/keys"))
+            .json(&ExchangeRequest {
+                code,
+                code_verifier,
+                code_challenge_method: "S256",
+            })
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(OpenRouterError::from_status(
+                response.status().as_u16(),
+                format!("Failed to exchange code: {}", response.status()),
+            ));
+        }
+
+        let body: ExchangeResponse = response.json().await?;
+        Ok(body.key)
     }
 
-    let completion: CompletionResponse = response.json().await?;
+    /// Fetch the list of available models.
+    pub async fn fetch_models(&self) -> Result<Vec<ModelInfo>, OpenRouterError> {
+        let mut response =
+            within(self.read_timeout, self.http.get(self.url("models")).send()).await?;
+
+        if !response.status().is_success() {
+            return Err(OpenRouterError::from_status(
+                response.status().as_u16(),
+                format!("Failed to fetch models: {}", response.status()),
+            ));
+        }
+
+        #[derive(Deserialize)]
+        struct ModelsResponse {
+            data: Vec<ApiModel>,
+        }
+
+        #[derive(Deserialize)]
+        struct ApiModel {
+            id: String,
+            name: Option<String>,
+            pricing: ApiPricing,
+            context_length: Option<u32>,
+        }
 
-    if let Some(choice) = completion.choices.first() {
-        return Ok(choice.message.content.clone());
+        #[derive(Deserialize)]
+        struct ApiPricing {
+            prompt: String,
+            completion: String,
+        }
+
+        let body = read_body(&mut response, self.read_timeout).await?;
+        let body: ModelsResponse = serde_json::from_slice(&body).map_err(|err| {
+            OpenRouterError::from(format!("HTTP error: error decoding response body: {}", err))
+        })?;
+        let models = body
+            .data
+            .into_iter()
+            .map(|m| ModelInfo {
+                id: m.id.clone(),
+                name: m.name.unwrap_or(m.id),
+                pricing: ModelPricing {
+                    prompt: m.pricing.prompt,
+                    completion: m.pricing.completion,
+                },
+                context_length: m.context_length,
+            })
+            .collect();
+
+        Ok(models)
     }
 
-    Err(OpenRouterError::from("No response content from OpenRouter"))
+    /// Send a single-message chat completion request and return the first choice's content
+    /// with the reported token usage.
+    pub async fn complete(
+        &self,
+        api_key: &str,
+        model: &str,
+        prompt: &str,
+        options: &CompletionOptions,
+    ) -> Result<Completion, OpenRouterError> {
+        #[derive(Serialize)]
+        struct CompletionRequest {
+            model: String,
+            messages: Vec<Message>,
+            temperature: f32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            reasoning: Option<Reasoning>,
+        }
+
+        #[derive(Serialize)]
+        struct Reasoning {
+            enabled: bool,
+        }
+
+        #[derive(Serialize)]
+        struct Message {
+            role: String,
+            content: String,
+        }
+
+        #[derive(Deserialize)]
+        struct CompletionResponse {
+            choices: Vec<Choice>,
+            #[serde(default)]
+            usage: Option<TokenUsage>,
+        }
+
+        #[derive(Deserialize)]
+        struct Choice {
+            message: ResponseMessage,
+        }
+
+        #[derive(Deserialize)]
+        struct ResponseMessage {
+            content: String,
+        }
+
+        let request_body = CompletionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            temperature: options.temperature,
+            reasoning: options
+                .disable_reasoning
+                .then_some(Reasoning { enabled: false }),
+        };
+
+        let mut request = self
+            .http
+            .post(self.url("chat/completions"))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json");
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+        if !options.referer.trim().is_empty() {
+            request = request.header("HTTP-Referer", options.referer.trim());
+        }
+        if !options.title.trim().is_empty() {
+            request = request.header("X-Title", options.title.trim());
+        }
+        for (name, value) in &options.extra_headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let mut response = within(options.read_timeout, request.json(&request_body).send()).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(OpenRouterError::from_status(
+                status.as_u16(),
+                format!("OpenRouter API error: {}", status),
+            ));
+        }
+
+        let body = read_body(&mut response, options.read_timeout).await?;
+        let completion: CompletionResponse = serde_json::from_slice(&body).map_err(|err| {
+            OpenRouterError::from(format!("HTTP error: error decoding response body: {}", err))
+        })?;
+
+        if let Some(choice) = completion.choices.first() {
+            return Ok(Completion {
+                content: choice.message.content.clone(),
+                usage: completion.usage,
+            });
+        }
+
+        Err(OpenRouterError::from("No response content from OpenRouter"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::spawn_mock_server;
+
+    const COMPLETION_OK: &str = r#"{"choices":[{"message":{"content":"Corrected."}}]}"#;
+
+    fn client_for(base_url: &str) -> OpenRouterClient {
+        OpenRouterClient::new(reqwest::Client::new(), base_url)
+    }
+
+    /// Body of a raw HTTP request.
+    fn request_body(request: &str) -> &str {
+        request.split_once("\r\n\r\n").map_or("", |(_, body)| body)
+    }
+
+    #[tokio::test]
+    async fn complete_sends_attribution_and_extra_headers() {
+        let (base_url, server) = spawn_mock_server(vec![("200 OK", COMPLETION_OK)]).await;
+        let options = CompletionOptions {
+            title: "Test Title".to_string(),
+            extra_headers: BTreeMap::from([("X-Proxy-Token".to_string(), "abc123".to_string())]),
+            ..CompletionOptions::default()
+        };
+
+        let completion = client_for(&base_url)
+            .complete("sk-test", "test/model", "Fix me", &options)
+            .await
+            .expect("completion succeeds");
+        assert_eq!(completion, Completion::from("Corrected."));
+
+        let requests = server.await.expect("server task");
+        let request = requests[0].to_ascii_lowercase();
+        assert!(request.starts_with("post /chat/completions"), "{request}");
+        assert!(request.contains(&format!(
+            "http-referer: {}",
+            DEFAULT_APP_REFERER.to_ascii_lowercase()
+        )));
+        assert!(request.contains("x-title: test title"), "{request}");
+        assert!(request.contains("x-proxy-token: abc123"), "{request}");
+        assert!(
+            request.contains("authorization: bearer sk-test"),
+            "{request}"
+        );
+    }
+
+    #[tokio::test]
+    async fn complete_shapes_request_and_parses_first_choice() {
+        let (base_url, server) = spawn_mock_server(vec![(
+            "200 OK",
+            r#"{"id":"gen-1","choices":[{"message":{"role":"assistant","content":"First."}},{"message":{"content":"Second."}}]}"#,
+        )])
+        .await;
+        let options = CompletionOptions {
+            temperature: 0.5,
+            ..CompletionOptions::default()
+        };
+
+        let completion = client_for(&format!("{base_url}/"))
+            .complete("sk-test", "test/model", "Fix me", &options)
+            .await
+            .expect("completion succeeds");
+        assert_eq!(completion.content, "First.");
+        assert_eq!(completion.usage, None);
+
+        let requests = server.await.expect("server task");
+        let body: serde_json::Value =
+            serde_json::from_str(request_body(&requests[0])).expect("JSON request body");
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "model": "test/model",
+                "messages": [{"role": "user", "content": "Fix me"}],
+                "temperature": 0.5,
+            })
+        );
+    }
+
+    /// A server that sends response headers at once, then stalls the body far longer than the
+    /// read timeouts under test.
+    async fn spawn_stalling_server() -> (String, tokio::task::JoinHandle<()>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.expect("accept");
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 100\r\n\r\n{")
+                .await;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+        (base_url, server)
+    }
+
+    #[tokio::test]
+    async fn read_timeout_fires_when_the_body_stalls_after_connecting() {
+        let (base_url, server) = spawn_stalling_server().await;
+        let options = CompletionOptions {
+            timeout: None,
+            read_timeout: Some(Duration::from_millis(200)),
+            ..CompletionOptions::default()
+        };
+
+        let started = std::time::Instant::now();
+        let err = client_for(&base_url)
+            .complete("sk-test", "test/model", "Fix me", &options)
+            .await
+            .expect_err("stalled body times out");
+        assert!(err.is_timeout(), "{err}");
+        assert_eq!(err.status(), None);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn complete_returns_token_usage() {
+        let (base_url, server) = spawn_mock_server(vec![(
+            "200 OK",
+            r#"{"choices":[{"message":{"content":"Done."}}],"usage":{"prompt_tokens":120,"completion_tokens":30,"total_tokens":150}}"#,
+        )])
+        .await;
+
+        let completion = client_for(&base_url)
+            .complete(
+                "sk-test",
+                "test/model",
+                "Fix me",
+                &CompletionOptions::default(),
+            )
+            .await
+            .expect("completion succeeds");
+        server.await.expect("server task");
+
+        let usage = completion.usage.expect("usage reported");
+        assert_eq!(
+            usage,
+            TokenUsage {
+                prompt_tokens: 120,
+                completion_tokens: 30,
+                total_tokens: 150,
+            }
+        );
+        let mut total = usage;
+        total += usage;
+        assert_eq!(total.total_tokens, 300);
+    }
+
+    #[tokio::test]
+    async fn complete_sends_temperature_and_disables_reasoning() {
+        let (base_url, server) = spawn_mock_server(vec![(
+            "200 OK",
+            r#"{"choices":[{"message":{"content":"Done."}}]}"#,
+        )])
+        .await;
+        let options = CompletionOptions {
+            temperature: 1.25,
+            disable_reasoning: true,
+            ..CompletionOptions::default()
+        };
+
+        client_for(&base_url)
+            .complete("sk-test", "test/model", "Fix me", &options)
+            .await
+            .expect("completion succeeds");
+
+        let requests = server.await.expect("server task");
+        let body: serde_json::Value =
+            serde_json::from_str(request_body(&requests[0])).expect("JSON request body");
+        assert_eq!(body["temperature"], serde_json::json!(1.25));
+        assert_eq!(body["reasoning"], serde_json::json!({"enabled": false}));
+    }
+
+    #[tokio::test]
+    async fn complete_rejects_malformed_json() {
+        let (base_url, server) = spawn_mock_server(vec![("200 OK", "{\"choices\": [")]).await;
+
+        let err = client_for(&base_url)
+            .complete(
+                "sk-test",
+                "test/model",
+                "Fix me",
+                &CompletionOptions::default(),
+            )
+            .await
+            .expect_err("malformed body is an error");
+        server.await.expect("server task");
+
+        assert_eq!(err.status(), None);
+        assert!(err.to_string().contains("decoding"), "{err}");
+
+        let (base_url, server) = spawn_mock_server(vec![("200 OK", r#"{"choices":[]}"#)]).await;
+        let err = client_for(&base_url)
+            .complete(
+                "sk-test",
+                "test/model",
+                "Fix me",
+                &CompletionOptions::default(),
+            )
+            .await
+            .expect_err("empty choices is an error");
+        server.await.expect("server task");
+        assert!(err.to_string().contains("No response content"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn complete_reports_missing_model_as_unavailable() {
+        let (base_url, server) = spawn_mock_server(vec![(
+            "404 Not Found",
+            r#"{"error":{"message":"No endpoints found"}}"#,
+        )])
+        .await;
+
+        let err = client_for(&base_url)
+            .complete(
+                "sk-test",
+                "gone/model",
+                "Fix me",
+                &CompletionOptions::default(),
+            )
+            .await
+            .expect_err("404 is an error");
+        server.await.expect("server task");
+
+        assert_eq!(err.status(), Some(404));
+        assert!(err.is_model_unavailable());
+        assert!(!OpenRouterError::from_status(401, "unauthorized").is_model_unavailable());
+        assert!(OpenRouterError::from_status(503, "unavailable").is_model_unavailable());
+    }
+
+    #[tokio::test]
+    async fn fetch_models_parses_listing() {
+        let (base_url, server) = spawn_mock_server(vec![(
+            "200 OK",
+            r#"{"data":[{"id":"google/gemma:free","pricing":{"prompt":"0","completion":"0"},"context_length":8192},{"id":"openai/gpt-4o-mini","name":"GPT-4o mini","pricing":{"prompt":"0.00000015","completion":"0.0000006"}}]}"#,
+        )])
+        .await;
+
+        let models = client_for(&base_url)
+            .fetch_models()
+            .await
+            .expect("models parse");
+        let requests = server.await.expect("server task");
+        assert!(requests[0].starts_with("GET /models"), "{}", requests[0]);
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "google/gemma:free");
+        assert!(models[0].is_free());
+        assert_eq!(models[0].context_length, Some(8192));
+        assert_eq!(models[1].name, "GPT-4o mini");
+        assert!(!models[1].is_free());
+    }
+
+    #[tokio::test]
+    async fn fetch_models_stops_waiting_after_the_read_timeout() {
+        let (base_url, server) = spawn_stalling_server().await;
+
+        let started = std::time::Instant::now();
+        let err = client_for(&base_url)
+            .with_read_timeout(Some(Duration::from_millis(200)))
+            .fetch_models()
+            .await
+            .expect_err("stalled body times out");
+        assert!(err.is_timeout(), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(2));
+        server.abort();
+    }
 
     #[test]
     fn test_pkce_generation() {
@@ -0,0 +1,266 @@
+//! Leading timestamps on `ChatLog.log` lines.
+//!
+//! The game writes `2025-09-09T21:04:27.785-05:00`, but client versions and addons can change
+//! the layout. A line is tried against each configured format in turn; a format is either one
+//! of [`KNOWN_TIMESTAMP_FORMATS`] or a custom regex with named capture groups:
+//!
+//! - `year`, `month`, `day`, `hour`, `minute` (required)
+//! - `second`, `fraction` (digits after the decimal point) and `offset` (`Z`, `±hh:mm` or
+//!   `±hhmm`) (optional)
+//!
+//! Custom regexes are anchored at the start of the line, and the whole match is removed before
+//! the rest of the line is parsed, so include any separator that follows the timestamp.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use regex::{Captures, Regex};
+
+/// Format tried when none are configured: the game's own timestamp layout.
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "eso";
+
+/// Named timestamp layouts accepted by `--timestamp-format`, with an example of each.
+pub const KNOWN_TIMESTAMP_FORMATS: &[(&str, &str)] = &[
+    ("eso", "2025-09-09T21:04:27.785-05:00"),
+    ("iso8601", "2025-09-09 21:04:27 or 2025-09-09T21:04:27.785Z"),
+    ("bracketed", "[2025-09-09 21:04:27]"),
+    ("us", "09/09/2025 21:04:27"),
+    ("eu", "09.09.2025 21:04:27"),
+];
+
+const REQUIRED_GROUPS: [&str; 5] = ["year", "month", "day", "hour", "minute"];
+
+fn known_pattern(name: &str) -> Option<&'static str> {
+    let pattern = match name.to_ascii_lowercase().as_str() {
+        "eso" => {
+            r"(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})T(?P<hour>\d{2}):(?P<minute>\d{2}):(?P<second>\d{2}).(?P<fraction>\d{3})(?P<offset>-\d{2}:\d{2}) "
+        }
+        "iso8601" => {
+            r"(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})[T ](?P<hour>\d{2}):(?P<minute>\d{2})(?::(?P<second>\d{2})(?:[.,](?P<fraction>\d+))?)?(?P<offset>Z|[+-]\d{2}:?\d{2})?\s+"
+        }
+        "bracketed" => {
+            r"\[(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})[T ](?P<hour>\d{2}):(?P<minute>\d{2})(?::(?P<second>\d{2}))?\]\s*"
+        }
+        "us" => {
+            r"(?P<month>\d{1,2})/(?P<day>\d{1,2})/(?P<year>\d{4}),? (?P<hour>\d{1,2}):(?P<minute>\d{2})(?::(?P<second>\d{2}))?\s+"
+        }
+        "eu" => {
+            r"(?P<day>\d{1,2})\.(?P<month>\d{1,2})\.(?P<year>\d{4}),? (?P<hour>\d{1,2}):(?P<minute>\d{2})(?::(?P<second>\d{2}))?\s+"
+        }
+        _ => return None,
+    };
+    Some(pattern)
+}
+
+fn compile(spec: &str) -> Result<Regex, String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Err("Timestamp format cannot be empty.".to_string());
+    }
+    let pattern = known_pattern(spec).unwrap_or(spec);
+    let regex = Regex::new(&format!("^(?:{})", pattern))
+        .map_err(|e| format!("Invalid timestamp format '{}': {}", spec, e))?;
+    let names: Vec<&str> = regex.capture_names().flatten().collect();
+    if let Some(missing) = REQUIRED_GROUPS.iter().find(|group| !names.contains(group)) {
+        return Err(format!(
+            "Timestamp format '{}' is not a known format ({}) and has no '{}' capture group.",
+            spec,
+            KNOWN_TIMESTAMP_FORMATS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            missing
+        ));
+    }
+    Ok(regex)
+}
+
+/// Check that `spec` names a known format or is a usable custom regex.
+pub fn validate_timestamp_format(spec: &str) -> Result<(), String> {
+    compile(spec).map(|_| ())
+}
+
+/// A timestamp read from the start of a log line, in the log's local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogTimestamp {
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+    /// UTC offset, when the format records one.
+    pub offset: Option<FixedOffset>,
+}
+
+impl LogTimestamp {
+    /// `YYYY-MM-DDThh:mm`, the form compared against `--start`/`--end` bounds.
+    pub fn window_key(&self) -> String {
+        format!(
+            "{}T{}",
+            self.date.format("%Y-%m-%d"),
+            self.time.format("%H:%M")
+        )
+    }
+
+    /// The timestamp as an instant. Timestamps without an offset are read as UTC, which keeps
+    /// the gaps between lines of the same log correct.
+    pub fn to_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        let offset = self.offset.unwrap_or(FixedOffset::east_opt(0)?);
+        offset
+            .from_local_datetime(&self.date.and_time(self.time))
+            .single()
+    }
+
+    fn from_captures(caps: &Captures) -> Option<Self> {
+        let number = |name: &str| caps.name(name).and_then(|m| m.as_str().parse::<u32>().ok());
+        let year = caps.name("year")?.as_str().parse::<i32>().ok()?;
+        let date = NaiveDate::from_ymd_opt(year, number("month")?, number("day")?)?;
+        let nanos = match caps.name("fraction") {
+            Some(m) => {
+                let digits: String = m.as_str().chars().take(9).collect();
+                format!("{:0<9}", digits).parse::<u32>().ok()?
+            }
+            None => 0,
+        };
+        let time = NaiveTime::from_hms_nano_opt(
+            number("hour")?,
+            number("minute")?,
+            number("second").unwrap_or(0),
+            nanos,
+        )?;
+        let offset = match caps.name("offset") {
+            Some(m) => Some(parse_offset(m.as_str())?),
+            None => None,
+        };
+        Some(Self { date, time, offset })
+    }
+}
+
+fn parse_offset(value: &str) -> Option<FixedOffset> {
+    if value.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    let sign = match value.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits: String = value[1..].chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// The ordered list of timestamp formats tried against each line.
+#[derive(Debug, Clone)]
+pub struct TimestampFormats {
+    formats: Vec<Regex>,
+}
+
+impl Default for TimestampFormats {
+    fn default() -> Self {
+        Self {
+            formats: vec![compile(DEFAULT_TIMESTAMP_FORMAT).expect("built-in format compiles")],
+        }
+    }
+}
+
+impl TimestampFormats {
+    /// Compile known format names or custom regexes, in order. An empty list selects
+    /// [`DEFAULT_TIMESTAMP_FORMAT`].
+    pub fn from_specs<S: AsRef<str>>(specs: &[S]) -> Result<Self, String> {
+        if specs.is_empty() {
+            return Ok(Self::default());
+        }
+        let formats = specs
+            .iter()
+            .map(|spec| compile(spec.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { formats })
+    }
+
+    /// Split `line` into its timestamp and the remainder after it, using the first format
+    /// that matches with a valid date and time.
+    pub fn split<'a>(&self, line: &'a str) -> Option<(LogTimestamp, &'a str)> {
+        self.formats.iter().find_map(|regex| {
+            let caps = regex.captures(line)?;
+            let timestamp = LogTimestamp::from_captures(&caps)?;
+            let end = caps.get(0).map_or(0, |m| m.end());
+            Some((timestamp, line[end..].trim_start()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_format_reads_game_timestamps() {
+        let formats = TimestampFormats::default();
+        let (timestamp, rest) = formats
+            .split("2025-09-09T21:04:27.785-05:00 0,Name,Hello")
+            .expect("matches");
+        assert_eq!(rest, "0,Name,Hello");
+        assert_eq!(timestamp.window_key(), "2025-09-09T21:04");
+        assert_eq!(
+            timestamp.to_datetime(),
+            DateTime::parse_from_rfc3339("2025-09-09T21:04:27.785-05:00").ok()
+        );
+        assert!(
+            formats
+                .split("[2025-09-09 21:04:27] 0,Name,Hello")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn formats_are_tried_in_order() {
+        let formats = TimestampFormats::from_specs(&["eso", "bracketed", "us"]).expect("compile");
+
+        let (timestamp, rest) = formats
+            .split("[2025-09-09 21:04:27] 6,Name,waves")
+            .expect("bracketed");
+        assert_eq!(rest, "6,Name,waves");
+        assert_eq!(timestamp.window_key(), "2025-09-09T21:04");
+        assert_eq!(timestamp.offset, None);
+
+        let (timestamp, _) = formats.split("9/9/2025 21:04:27 0,Name,Hello").expect("us");
+        assert_eq!(timestamp.date, NaiveDate::from_ymd_opt(2025, 9, 9).unwrap());
+
+        assert!(formats.split("13/40/2025 21:04:27 0,Name,Hello").is_none());
+    }
+
+    #[test]
+    fn custom_regex_uses_named_groups() {
+        let formats = TimestampFormats::from_specs(&[
+            r"(?P<day>\d{2})-(?P<month>\d{2})-(?P<year>\d{4})@(?P<hour>\d{2})h(?P<minute>\d{2}) ",
+        ])
+        .expect("compile");
+        let (timestamp, rest) = formats.split("04-01-2025@22h05 0,A,B").expect("custom");
+        assert_eq!(rest, "0,A,B");
+        assert_eq!(timestamp.window_key(), "2025-01-04T22:05");
+    }
+
+    #[test]
+    fn invalid_formats_are_rejected() {
+        assert!(validate_timestamp_format("ISO8601").is_ok());
+        assert!(validate_timestamp_format("").is_err());
+        assert!(validate_timestamp_format("(unclosed").is_err());
+        let err = validate_timestamp_format(r"(?P<year>\d{4})").unwrap_err();
+        assert!(err.contains("'month' capture group"), "{err}");
+    }
+
+    #[test]
+    fn offsets_accept_common_spellings() {
+        let formats = TimestampFormats::from_specs(&["iso8601"]).expect("compile");
+        for line in [
+            "2025-01-04T22:00:00Z 0,A,B",
+            "2025-01-04T17:00:00.5-05:00 0,A,B",
+            "2025-01-04T23:00:00+0100 0,A,B",
+        ] {
+            let (timestamp, _) = formats.split(line).expect(line);
+            let utc = timestamp.to_datetime().expect("instant").naive_utc();
+            assert_eq!(utc.format("%H:%M").to_string(), "22:00", "{line}");
+        }
+    }
+}
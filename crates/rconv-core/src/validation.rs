@@ -0,0 +1,528 @@
+//! Pre-flight checks of a resolved configuration, shared by runs, the CLI `validate` command
+//! and the GUI.
+//!
+//! A run stops at the first problem [`config_problems`] reports; [`validate`] reports all of
+//! them, plus missing input files and the output path a run would write. [`estimate_cost`]
+//! reads the input file, so it is kept out of the checks made before every run.
+
+use crate::config::{ConfigWarning, MAX_LLM_CONCURRENCY};
+use crate::cost::{CostEstimate, estimate_for_file};
+use crate::curator::select_from_snapshot;
+use crate::runtime::{
+    ConvocationsConfig, OutputFormat, effective_model_preference, expand_path, find_active_preset,
+    grep_regex, normalize_config, output_directory_conflict, resolve_outfile_paths,
+};
+use crate::timestamp::TimestampFormats;
+use chrono::Local;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Outcome of [`validate`]. Warnings never make a configuration invalid.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ValidationResult {
+    pub valid: bool,
+    /// Every problem that would stop a run, in the order a run reports them.
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Errors about a single setting, keyed by its config field name.
+    pub field_errors: BTreeMap<String, String>,
+    /// The file a run would write, when it could be resolved.
+    pub outfile: Option<String>,
+    /// Rough LLM token usage for the input file, filled in from [`estimate_cost`] by callers
+    /// that want it; `None` otherwise.
+    pub estimated_tokens: Option<u64>,
+    /// Cost of `estimated_tokens` at the curated pricing of the model a run would pick; `None`
+    /// when that pricing is unknown.
+    pub estimated_cost_usd: Option<f64>,
+}
+
+impl ValidationResult {
+    /// Record an error that stops a run, optionally tied to `field`.
+    pub fn push_error(&mut self, field: Option<&str>, message: impl Into<String>) {
+        let message = message.into();
+        if let Some(field) = field {
+            self.field_errors
+                .entry(field.to_string())
+                .or_insert_with(|| message.clone());
+        }
+        self.errors.push(message);
+        self.valid = false;
+    }
+}
+
+/// Check `config` the way a run would, reporting every problem rather than the first, then
+/// check that the input file exists and resolve the output path.
+/// `warnings` are the ones collected while loading the config and applying overrides.
+pub fn validate(config: &ConvocationsConfig, warnings: &[ConfigWarning]) -> ValidationResult {
+    let mut result = ValidationResult {
+        valid: true,
+        warnings: warnings.iter().map(ToString::to_string).collect(),
+        ..ValidationResult::default()
+    };
+    for problem in config_problems(config) {
+        result.push_error(problem.field, problem.message);
+    }
+    check_input_file(config, &mut result);
+
+    let mut normalized = config.clone();
+    normalize_config(&mut normalized);
+    let today = config.today.unwrap_or_else(|| Local::now().date_naive());
+    match resolve_outfile_paths(&normalized, None, Some(today)) {
+        Ok(outfile) => {
+            check_output_directory(config, &outfile.effective, &mut result);
+            result.outfile = Some(outfile.effective);
+        }
+        Err(message) => result.push_error(Some("outfile"), message),
+    }
+    result
+}
+
+/// Rough LLM token usage and cost for `config`'s input file, from its line count; `None` when
+/// corrections are off or the file cannot be read. Reads the whole file.
+pub fn estimate_cost(config: &ConvocationsConfig) -> Option<CostEstimate> {
+    let mut config = config.clone();
+    normalize_config(&mut config);
+    if !config.use_llm {
+        return None;
+    }
+    let path = config.process_file.as_deref().unwrap_or(&config.infile);
+    let model = select_from_snapshot(
+        &effective_model_preference(&config),
+        config.free_models_only,
+        config.auto_strategy,
+    );
+    estimate_for_file(path, model.as_ref()).ok()
+}
+
+/// Settings that cannot all hold at once. Each is reported without a field, since changing
+/// any one of the settings involved resolves it.
+pub fn detect_contradictions(config: &ConvocationsConfig) -> Vec<String> {
+    let mut contradictions = Vec::new();
+
+    let event_count = [config.rsm7, config.rsm8, config.tp6]
+        .iter()
+        .filter(|&&x| x)
+        .count();
+    if event_count > 1 {
+        contradictions
+            .push("Cannot specify more than one event type (--rsm7, --rsm8, --tp6)".to_string());
+    }
+
+    if config.one_hour && config.two_hours {
+        contradictions.push("Cannot specify both --1h and --2h".to_string());
+    }
+
+    let has_duration_override =
+        config.one_hour || config.two_hours || config.duration_override.enabled;
+    let has_event_or_duration_flags =
+        config.rsm7 || config.rsm8 || config.tp6 || has_duration_override;
+    let has_custom_dates = config.start.is_some() || config.end.is_some();
+    if has_event_or_duration_flags && has_custom_dates {
+        contradictions.push(
+            "Cannot use event flags (--rsm7, --rsm8, --tp6) or duration overrides (--1h, --2h, --duration-hours) with custom start/end dates (--start, --end)"
+                .to_string(),
+        );
+    }
+
+    if let Some((start, end)) = config
+        .start
+        .as_deref()
+        .zip(config.end.as_deref())
+        .filter(|(start, end)| start > end)
+    {
+        contradictions.push(format!("Start {} is after end {}.", start, end));
+    }
+
+    if config.split_by_speaker && config.process_file.is_some() {
+        contradictions
+            .push("Splitting by speaker is not supported with --process-file.".to_string());
+    }
+
+    if config.with_index && config.process_file.is_some() {
+        contradictions.push(
+            "A speaker index (--with-index) is not supported with --process-file.".to_string(),
+        );
+    }
+    if config.with_index && config.output_format == OutputFormat::Tsv {
+        contradictions
+            .push("A speaker index (--with-index) cannot be added to TSV output.".to_string());
+    }
+    if config.section_by_channel && config.output_format == OutputFormat::Tsv {
+        contradictions.push(
+            "Channel sections (--section-by-channel) cannot be added to TSV output.".to_string(),
+        );
+    }
+    if config.annotate_source && config.output_format == OutputFormat::Tsv {
+        contradictions.push(
+            "Source annotations (--annotate-source) cannot be added to TSV output.".to_string(),
+        );
+    }
+
+    contradictions
+}
+
+/// A reason [`crate::runtime`] rejects a configuration. `field` names the setting at fault,
+/// or is `None` when several settings conflict.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub field: Option<&'static str>,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn new(field: Option<&'static str>, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Every problem that stops a run, in the order a run reports them: contradictions first,
+/// then out-of-range settings.
+pub fn config_problems(config: &ConvocationsConfig) -> Vec<ConfigProblem> {
+    let mut problems: Vec<ConfigProblem> = detect_contradictions(config)
+        .into_iter()
+        .map(|message| ConfigProblem::new(None, message))
+        .collect();
+
+    if config.duration_override.enabled {
+        let hours = config.duration_override.hours;
+        if !hours.is_finite() {
+            problems.push(ConfigProblem::new(
+                Some("duration_override.hours"),
+                "Duration override hours must be a finite number.",
+            ));
+        } else if hours < 1.0 {
+            problems.push(ConfigProblem::new(
+                Some("duration_override.hours"),
+                "Duration override hours must be at least 1.0.",
+            ));
+        }
+    }
+
+    if config.llm_timeout_secs == 0 {
+        problems.push(ConfigProblem::new(
+            Some("llm_timeout_secs"),
+            "LLM timeout must be at least 1 second.",
+        ));
+    }
+
+    if config.connect_timeout_secs == 0 {
+        problems.push(ConfigProblem::new(
+            Some("connect_timeout_secs"),
+            "Connect timeout must be at least 1 second.",
+        ));
+    }
+
+    if config.read_timeout_secs == 0 {
+        problems.push(ConfigProblem::new(
+            Some("read_timeout_secs"),
+            "Read timeout must be at least 1 second.",
+        ));
+    }
+
+    if !(0.0..=2.0).contains(&config.llm_temperature) {
+        problems.push(ConfigProblem::new(
+            Some("llm_temperature"),
+            "LLM temperature must be between 0.0 and 2.0.",
+        ));
+    }
+
+    if let Err(message) = TimestampFormats::from_specs(&config.timestamp_formats) {
+        problems.push(ConfigProblem::new(Some("timestamp_formats"), message));
+    }
+
+    if let Err(err) = Regex::new(&config.channel_pattern) {
+        problems.push(ConfigProblem::new(
+            Some("channel_pattern"),
+            format!("Invalid channel pattern: {}", err),
+        ));
+    }
+
+    if let Some(Err(err)) = config
+        .grep
+        .as_deref()
+        .map(|pattern| grep_regex(pattern, config.grep_ignore_case))
+    {
+        problems.push(ConfigProblem::new(
+            Some("grep"),
+            format!("Invalid --grep pattern: {}", err),
+        ));
+    }
+
+    if !(1..=MAX_LLM_CONCURRENCY).contains(&config.llm_concurrency) {
+        problems.push(ConfigProblem::new(
+            Some("llm_concurrency"),
+            format!(
+                "LLM concurrency must be between 1 and {}.",
+                MAX_LLM_CONCURRENCY
+            ),
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&config.llm_min_retain_ratio) {
+        problems.push(ConfigProblem::new(
+            Some("llm_min_retain_ratio"),
+            "LLM minimum retain ratio must be between 0 and 1.",
+        ));
+    }
+
+    if !(0.0..1.0).contains(&config.context_safety_margin) {
+        problems.push(ConfigProblem::new(
+            Some("context_safety_margin"),
+            "Context safety margin must be at least 0 and below 1.",
+        ));
+    }
+
+    if let Some(path) = config
+        .output_directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|directory| !directory.is_empty())
+        .and_then(output_directory_conflict)
+    {
+        problems.push(ConfigProblem::new(
+            Some("output_directory"),
+            format!(
+                "output_directory: '{}' is a file, not a directory.",
+                path.display()
+            ),
+        ));
+    }
+
+    if let Some(preset) = find_active_preset(config).filter(|preset| preset.duration_minutes == 0) {
+        problems.push(ConfigProblem::new(
+            Some("active_preset"),
+            format!(
+                "Preset '{}' duration_minutes must be greater than zero.",
+                preset.name
+            ),
+        ));
+    }
+
+    problems
+}
+
+/// The input file (`process_file`, or `infile`) must exist.
+fn check_input_file(config: &ConvocationsConfig, result: &mut ValidationResult) {
+    let (field, path) = match config.process_file.as_deref() {
+        Some(path) => ("process_file", path),
+        None => ("infile", config.infile.as_str()),
+    };
+    match expand_path(path) {
+        Ok(expanded) if !Path::new(&expanded).is_file() => {
+            result.push_error(
+                Some(field),
+                format!("Input file '{}' was not found.", expanded),
+            );
+        }
+        Ok(_) => {}
+        Err(message) => result.push_error(Some(field), message),
+    }
+}
+
+/// Estimate the correction pass over the input file, priced at the model the curated snapshot
+/// would pick for `config`. Leaves both estimates empty when the input cannot be read.
+/// The output file's directory must exist unless `mkdir_output` creates it. Skipped when
+/// `output_directory` is already reported as unusable.
+fn check_output_directory(
+    config: &ConvocationsConfig,
+    outfile: &str,
+    result: &mut ValidationResult,
+) {
+    let missing = Path::new(outfile)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty() && !parent.is_dir());
+    let reported = result.field_errors.contains_key("output_directory");
+    if let Some(parent) = missing.filter(|_| !config.mkdir_output && !reported) {
+        result.push_error(
+            Some("outfile"),
+            format!(
+                "Output directory '{}' does not exist; create it or use --mkdir-output.",
+                parent.display()
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WarningCode;
+
+    fn contradictions_for(config: ConvocationsConfig) -> Vec<String> {
+        detect_contradictions(&config)
+    }
+
+    #[test]
+    fn contradictions_are_empty_for_the_default_config() {
+        assert!(contradictions_for(ConvocationsConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn contradiction_more_than_one_event_type() {
+        let found = contradictions_for(ConvocationsConfig {
+            rsm7: true,
+            tp6: true,
+            ..ConvocationsConfig::default()
+        });
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("more than one event type"), "{found:?}");
+    }
+
+    #[test]
+    fn contradiction_both_duration_flags() {
+        let found = contradictions_for(ConvocationsConfig {
+            one_hour: true,
+            two_hours: true,
+            ..ConvocationsConfig::default()
+        });
+        assert_eq!(found, vec!["Cannot specify both --1h and --2h".to_string()]);
+    }
+
+    #[test]
+    fn contradiction_event_flags_with_custom_dates() {
+        let found = contradictions_for(ConvocationsConfig {
+            rsm8: true,
+            start: Some("2025-01-04T21:00".to_string()),
+            ..ConvocationsConfig::default()
+        });
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("custom start/end dates"), "{found:?}");
+    }
+
+    #[test]
+    fn contradiction_start_after_end() {
+        let found = contradictions_for(ConvocationsConfig {
+            start: Some("2025-01-05T00:00".to_string()),
+            end: Some("2025-01-04T23:00".to_string()),
+            ..ConvocationsConfig::default()
+        });
+        assert_eq!(
+            found,
+            vec!["Start 2025-01-05T00:00 is after end 2025-01-04T23:00.".to_string()]
+        );
+    }
+
+    #[test]
+    fn contradiction_split_by_speaker_with_process_file() {
+        let found = contradictions_for(ConvocationsConfig {
+            split_by_speaker: true,
+            process_file: Some("exported.txt".to_string()),
+            ..ConvocationsConfig::default()
+        });
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("Splitting by speaker"), "{found:?}");
+    }
+
+    #[test]
+    fn contradiction_tsv_with_sections_or_annotations() {
+        let tsv = || ConvocationsConfig {
+            output_format: OutputFormat::Tsv,
+            ..ConvocationsConfig::default()
+        };
+        assert!(contradictions_for(tsv()).is_empty());
+
+        let found = contradictions_for(ConvocationsConfig {
+            section_by_channel: true,
+            ..tsv()
+        });
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("--section-by-channel"), "{found:?}");
+
+        let found = contradictions_for(ConvocationsConfig {
+            annotate_source: true,
+            ..tsv()
+        });
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("--annotate-source"), "{found:?}");
+    }
+
+    #[test]
+    fn validate_reports_every_problem_with_field_errors() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = ConvocationsConfig {
+            rsm7: true,
+            rsm8: true,
+            llm_timeout_secs: 0,
+            llm_concurrency: 0,
+            infile: dir
+                .path()
+                .join("missing.log")
+                .to_string_lossy()
+                .into_owned(),
+            outfile: Some(dir.path().join("out.txt").to_string_lossy().into_owned()),
+            ..ConvocationsConfig::default()
+        };
+        let warning = ConfigWarning::new(WarningCode::InvalidLlmSetting, "heads up");
+
+        let result = validate(&config, &[warning]);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 4, "{:?}", result.errors);
+        assert!(result.errors[0].contains("more than one event type"));
+        assert_eq!(
+            result.field_errors.keys().collect::<Vec<_>>(),
+            vec!["infile", "llm_concurrency", "llm_timeout_secs"]
+        );
+        assert!(result.field_errors["infile"].contains("was not found"));
+        assert_eq!(result.warnings, vec!["heads up".to_string()]);
+    }
+
+    #[test]
+    fn validate_summarizes_the_outfile_and_checks_its_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let infile = dir.path().join("ChatLog.log");
+        std::fs::write(&infile, "").expect("write log");
+        let outfile = dir.path().join("out.txt").to_string_lossy().into_owned();
+        let config = ConvocationsConfig {
+            infile: infile.to_string_lossy().into_owned(),
+            outfile: Some(outfile.clone()),
+            ..ConvocationsConfig::default()
+        };
+
+        let result = validate(&config, &[]);
+        assert!(result.valid, "{:?}", result.errors);
+        assert_eq!(result.outfile, Some(outfile));
+
+        let nested = dir.path().join("missing").join("out.txt");
+        let config = ConvocationsConfig {
+            outfile: Some(nested.to_string_lossy().into_owned()),
+            ..config
+        };
+        let result = validate(&config, &[]);
+        assert!(result.field_errors["outfile"].contains("does not exist"));
+        let result = validate(
+            &ConvocationsConfig {
+                mkdir_output: true,
+                ..config
+            },
+            &[],
+        );
+        assert!(result.valid, "{:?}", result.errors);
+    }
+
+    #[test]
+    fn estimate_cost_counts_tokens_only_when_corrections_are_on() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let infile = dir.path().join("ChatLog.log");
+        std::fs::write(&infile, "first line\n\nsecond line\n").expect("write log");
+        let config = ConvocationsConfig {
+            infile: infile.to_string_lossy().into_owned(),
+            outfile: Some(dir.path().join("out.txt").to_string_lossy().into_owned()),
+            ..ConvocationsConfig::default()
+        };
+
+        let estimate = estimate_cost(&config).expect("estimate");
+        assert_eq!(estimate.estimated_tokens, 120);
+        assert_eq!(validate(&config, &[]).estimated_tokens, None);
+
+        let disabled = ConvocationsConfig {
+            use_llm: false,
+            ..config
+        };
+        assert_eq!(estimate_cost(&disabled), None);
+    }
+}
@@ -2,8 +2,8 @@ use std::fs;
 use std::sync::{Arc, Mutex};
 
 use rconv_core::{
-    ConvocationsConfig, StageProgressEvent, StageProgressEventKind, run_with_config,
-    run_with_config_with_progress,
+    ConvocationsConfig, OutputFormat, RuntimeError, StageProgressEvent, StageProgressEventKind,
+    process_text, run_split_events, run_with_config, run_with_config_with_progress,
 };
 use tempfile::tempdir;
 
@@ -19,6 +19,7 @@ fn base_config(infile: &str, outfile: &str) -> ConvocationsConfig {
     config.end = Some("2025-01-04T23:30".to_string());
     config.use_llm = false;
     config.no_diff = true;
+    config.record_history = false;
     config.keep_orig = false;
     config.cleanup = true;
     config.format_dialogue = true;
@@ -50,20 +51,216 @@ async fn pipeline_formats_chatlog_output() {
 }
 
 #[tokio::test]
-async fn pipeline_emits_diff_event_when_llm_enabled() {
+async fn pipeline_reports_structured_errors() {
     let temp = tempdir().expect("tempdir");
     let infile_path = temp.path().join("ChatLog.log");
-    let outfile_path = temp.path().join("output_llm.txt");
+    let outfile_path = temp.path().join("output.txt");
+
+    let config = base_config(
+        infile_path.to_string_lossy().as_ref(),
+        outfile_path.to_string_lossy().as_ref(),
+    );
+    let err = run_with_config(config.clone())
+        .await
+        .expect_err("missing input should fail");
+    assert!(matches!(err, RuntimeError::Io { .. }), "got {err:?}");
+    assert!(err.to_string().starts_with("Error reading file "));
 
     fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+    let config = ConvocationsConfig {
+        start: Some("2024-01-01T00:00".to_string()),
+        end: Some("2024-01-01T01:00".to_string()),
+        ..config
+    };
+    run_with_config(config.clone())
+        .await
+        .expect("an empty window only warns by default");
+    assert!(!outfile_path.exists());
+
+    let config = ConvocationsConfig {
+        fail_on_empty: true,
+        ..config
+    };
+    let err = run_with_config(config)
+        .await
+        .expect_err("empty window should fail with fail_on_empty");
+    assert!(matches!(err, RuntimeError::EmptyResult(_)), "got {err:?}");
+    assert!(!outfile_path.exists());
+}
+
+#[tokio::test]
+async fn pipeline_prepends_output_header() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("output.txt");
+
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        header_template: Some(rconv_core::runtime::DEFAULT_HEADER_TEMPLATE.to_string()),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+
+    run_with_config(config.clone())
+        .await
+        .expect("pipeline completed successfully");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    let expected = "# Saturday 10pm-midnight — 2025-01-04 21:30 — 2 messages\n\n\
+Character One says, \"Hello there.\"\nCharacter Two gestures gracefully.\n";
+    assert_eq!(output, expected);
+
+    // Section headings are not counted as messages.
+    let sectioned = ConvocationsConfig {
+        section_by_channel: true,
+        ..config
+    };
+    let output = process_text(SAMPLE_LOG, &sectioned)
+        .await
+        .expect("pipeline completed");
+    assert!(
+        output
+            .text
+            .starts_with("# Saturday 10pm-midnight — 2025-01-04 21:30 — 2 messages\n"),
+        "{}",
+        output.text
+    );
+}
+
+#[tokio::test]
+async fn pipeline_splits_output_by_speaker() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("output.txt");
+    let split_dir = temp.path().join("speakers");
+
+    let log = "\
+2025-01-04T22:00:00.000-05:00 0,Character One,Hello there\n\
+2025-01-04T22:01:00.000-05:00 6,Character Two,gestures gracefully\n\
+2025-01-04T22:02:00.000-05:00 0,Character One,Goodbye\n";
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        split_by_speaker: true,
+        split_directory: Some(split_dir.to_string_lossy().to_string()),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+
+    run_with_config(config)
+        .await
+        .expect("pipeline completed successfully");
+
+    let one = fs::read_to_string(split_dir.join("Character_One.txt")).expect("first speaker");
+    assert_eq!(
+        one,
+        "Character One says, \"Hello there.\"\nCharacter One says, \"Goodbye.\"\n"
+    );
+    let two = fs::read_to_string(split_dir.join("Character_Two.txt")).expect("second speaker");
+    assert_eq!(two, "Character Two gestures gracefully.\n");
+    assert!(!outfile_path.exists());
+}
+
+#[tokio::test]
+async fn pipeline_merges_close_messages_from_same_speaker() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("merged.txt");
+
+    let log = "\
+2025-01-04T22:00:00.000-05:00 6,Character One,smiles warmly\n\
+2025-01-04T22:00:04.000-05:00 0,Character One,Welcome back\n\
+2025-01-04T22:05:00.000-05:00 0,Character One,Much later\n";
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        merge_gap_seconds: Some(10),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+
+    run_with_config(config)
+        .await
+        .expect("pipeline completed successfully");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    let expected =
+        "Character One smiles warmly. \"Welcome back.\"\nCharacter One says, \"Much later.\"\n";
+    assert_eq!(output, expected);
+}
+
+#[tokio::test]
+async fn unchanged_llm_output_skips_unedited_file_and_diff() {
+    // Without an API key the LLM stage returns the text as is, like a model that found
+    // nothing to correct.
+    for (keep_orig, no_diff) in [(false, false), (true, false), (false, true), (true, true)] {
+        let temp = tempdir().expect("tempdir");
+        let infile_path = temp.path().join("ChatLog.log");
+        let outfile_path = temp.path().join("output_llm.txt");
+        fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+
+        let mut config = base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        );
+        config.use_llm = true;
+        config.keep_orig = keep_orig;
+        config.no_diff = no_diff;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let collector = events.clone();
+        let callback = Arc::new(move |event: StageProgressEvent| {
+            collector.lock().unwrap().push(event);
+        });
+        run_with_config_with_progress(config, callback)
+            .await
+            .expect("pipeline completed");
+
+        let case = format!("keep_orig={keep_orig} no_diff={no_diff}");
+        assert!(outfile_path.exists(), "{case}");
+        assert!(
+            !temp.path().join("output_llm_unedited.txt").exists(),
+            "{case}"
+        );
+        let events = events.lock().unwrap();
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event.kind, StageProgressEventKind::Diff)),
+            "{case}"
+        );
+        assert!(
+            events.iter().any(|event| {
+                matches!(event.kind, StageProgressEventKind::Note)
+                    && event.message.as_deref() == Some("No corrections needed.")
+            }),
+            "{case}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn pipeline_diffs_against_previous_output() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("output.txt");
+    let previous_path = temp.path().join("last-week.txt");
+
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+    fs::write(&previous_path, "Character One says, \"Goodbye.\"\n").expect("write previous");
 
     let mut config = base_config(
         infile_path.to_string_lossy().as_ref(),
         outfile_path.to_string_lossy().as_ref(),
     );
-    config.use_llm = true;
-    config.no_diff = false;
-    config.keep_orig = true;
+    config.diff_against = Some(previous_path.to_string_lossy().to_string());
 
     let diff_events: Arc<Mutex<Vec<StageProgressEvent>>> = Arc::new(Mutex::new(Vec::new()));
     let collector = diff_events.clone();
@@ -78,19 +275,555 @@ async fn pipeline_emits_diff_event_when_llm_enabled() {
         .expect("pipeline completed");
 
     let events = diff_events.lock().unwrap();
-    assert_eq!(events.len(), 1, "expected exactly one diff event");
-    let diff_payload = events[0]
-        .diff
-        .clone()
-        .expect("diff payload should be present");
+    assert_eq!(
+        events.len(),
+        1,
+        "expected one diff against the previous file"
+    );
+    let diff_payload = events[0].diff.clone().expect("diff payload");
+    assert!(diff_payload.contains("Goodbye"));
+    assert!(diff_payload.contains("Hello there"));
+}
+
+#[tokio::test]
+async fn pipeline_notes_missing_previous_output() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("output.txt");
+
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+
+    let mut config = base_config(
+        infile_path.to_string_lossy().as_ref(),
+        outfile_path.to_string_lossy().as_ref(),
+    );
+    config.diff_against = Some(
+        temp.path()
+            .join("missing.txt")
+            .to_string_lossy()
+            .to_string(),
+    );
+
+    let notes: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let collector = notes.clone();
+    let callback = Arc::new(move |event: StageProgressEvent| {
+        if let (StageProgressEventKind::Note, Some(message)) = (&event.kind, event.message) {
+            collector.lock().unwrap().push(message);
+        }
+    });
+
+    run_with_config_with_progress(config, callback)
+        .await
+        .expect("missing previous output should not fail the run");
+
+    assert!(outfile_path.exists());
+    let notes = notes.lock().unwrap();
     assert!(
-        diff_payload.contains("---"),
-        "diff payload should include headers"
+        notes
+            .iter()
+            .any(|note| note.contains("Skipping diff against previous output")),
+        "expected a note about the missing file: {:?}",
+        *notes
     );
+}
+
+#[tokio::test]
+async fn split_events_records_empty_occurrences_as_skipped() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        outfile: None,
+        start: None,
+        end: None,
+        output_directory: Some(temp.path().to_string_lossy().into_owned()),
+        ..base_config(infile_path.to_string_lossy().as_ref(), "")
+    };
+    let to = chrono::Local::now().date_naive();
+    let from = to - chrono::Duration::days(20);
+
+    let manifest = run_split_events(config, from, to, false)
+        .await
+        .expect("split run completed");
 
-    let unedited_path = temp.path().join("output_llm_unedited.txt");
+    assert_eq!(manifest.entries.len(), 3);
     assert!(
-        unedited_path.exists(),
-        "unedited snapshot should be retained when keep_orig=true"
+        manifest
+            .entries
+            .windows(2)
+            .all(|pair| pair[0].event_date < pair[1].event_date)
+    );
+    for entry in &manifest.entries {
+        assert!(entry.skipped.is_some(), "unexpected output {entry:?}");
+        assert!(
+            entry
+                .output
+                .starts_with(temp.path().to_string_lossy().as_ref())
+        );
+        assert!(!std::path::Path::new(&entry.output).exists());
+    }
+}
+
+#[tokio::test]
+async fn anonymize_labels_speakers_consistently_and_writes_mapping() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.txt");
+    let map_path = temp.path().join("names.json");
+    let log = format!(
+        "{SAMPLE_LOG}\
+2025-01-04T22:02:00.000-05:00 0,Character One,Before we go >\n\
+2025-01-04T22:02:10.000-05:00 0,Character One,one more thing\n"
+    );
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        anonymize: true,
+        anon_map: Some(map_path.to_string_lossy().to_string()),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config).await.expect("pipeline completed");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    assert_eq!(
+        output,
+        "Speaker A says, \"Hello there.\"\n\
+Speaker B gestures gracefully.\n\
+Speaker A says, \"Before we go one more thing.\"\n"
+    );
+    let raw = fs::read_to_string(&map_path).expect("mapping written");
+    let mapping: serde_json::Value = serde_json::from_str(&raw).expect("valid json");
+    assert_eq!(mapping[0]["label"], "Speaker A");
+    assert_eq!(mapping[0]["name"], "Character One");
+    assert_eq!(mapping[1]["name"], "Character Two");
+}
+
+#[tokio::test]
+async fn debug_unparsed_writes_dropped_lines_with_reasons() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.txt");
+    let unparsed_path = temp.path().join("unparsed.tsv");
+    let log = format!(
+        "banner line\n\
+{SAMPLE_LOG}\
+2025-01-04T22:02:00.000-05:00 garbled\n\
+2025-01-04T22:03:00.000-05:00 3,Character Two,Guild chatter\n"
+    );
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        debug_unparsed: Some(unparsed_path.to_string_lossy().to_string()),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config).await.expect("pipeline completed");
+
+    let unparsed = fs::read_to_string(&unparsed_path).expect("unparsed lines written");
+    assert_eq!(
+        unparsed,
+        "no-timestamp\tbanner line\n\
+no-line-match\t2025-01-04T22:02:00.000-05:00 garbled\n\
+wrong-channel\t2025-01-04T22:03:00.000-05:00 3,Character Two,Guild chatter\n"
     );
 }
+
+#[tokio::test]
+async fn limit_stops_after_n_messages_but_finishes_continuations() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.txt");
+    let log = "\
+2025-01-04T22:00:00.000-05:00 0,Character One,First\n\
+2025-01-04T22:00:10.000-05:00 0,Character Two,Second begins >\n\
+2025-01-04T22:00:20.000-05:00 0,Character Three,Third\n\
+2025-01-04T22:00:30.000-05:00 0,Character Two,and ends\n\
+2025-01-04T22:00:40.000-05:00 0,Character One,Fourth\n\
+2025-01-04T22:00:50.000-05:00 0,Character Three,Fifth\n";
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        limit: Some(3),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config).await.expect("pipeline completed");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    assert_eq!(output.lines().count(), 3, "{output}");
+    assert!(output.contains("Second begins and ends."), "{output}");
+    assert!(!output.contains("Fourth"), "{output}");
+}
+
+#[tokio::test]
+async fn tsv_format_writes_escaped_channel_speaker_message_rows() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.tsv");
+    let log = "\
+2025-01-04T22:00:00.000-05:00 0,Character One,Column\tsplit C:\\path\n\
+2025-01-04T22:01:00.000-05:00 6,Character Two,waves\n";
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        format_dialogue: false,
+        cleanup: false,
+        output_format: OutputFormat::Tsv,
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config).await.expect("pipeline completed");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    let rows: Vec<Vec<&str>> = output
+        .lines()
+        .map(|row| row.split('\t').collect())
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            vec!["0", "Character One", "Column\\tsplit C:\\\\path."],
+            vec!["6", "Character Two", "waves."],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn annotate_source_appends_channel_and_time_to_each_line() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.txt");
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        annotate_source: true,
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config).await.expect("pipeline completed");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    assert_eq!(
+        output,
+        "Character One says, \"Hello there.\" [ch0 22:00]\n\
+Character Two gestures gracefully. [ch6 22:01]\n"
+    );
+}
+
+#[tokio::test]
+async fn annotate_source_labels_only_message_lines_under_channel_sections() {
+    let config = ConvocationsConfig {
+        annotate_source: true,
+        section_by_channel: true,
+        ..base_config("ChatLog.log", "event.txt")
+    };
+
+    let output = process_text(SAMPLE_LOG, &config)
+        .await
+        .expect("pipeline completed");
+
+    assert_eq!(
+        output.text,
+        "Dialogue\n\n\
+Character One says, \"Hello there.\" [ch0 22:00]\n\
+\n\
+Actions\n\n\
+Character Two gestures gracefully. [ch6 22:01]\n"
+    );
+}
+
+#[tokio::test]
+async fn output_size_guard_stops_oversized_runs_unless_forced() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.txt");
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        max_output_lines: 1,
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    let err = run_with_config(config.clone())
+        .await
+        .expect_err("guard should trip");
+    assert!(matches!(err, RuntimeError::Config(_)), "got {err:?}");
+    assert!(err.to_string().contains("2 lines (limit 1)"), "{err}");
+    assert!(!outfile_path.exists());
+
+    let forced = ConvocationsConfig {
+        force: true,
+        ..config
+    };
+    run_with_config(forced).await.expect("forced run completes");
+    assert!(outfile_path.exists());
+}
+
+#[tokio::test]
+async fn stats_json_sidecar_tallies_speakers_and_stages() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("event.txt");
+    let log = format!("{SAMPLE_LOG}2025-01-04T22:02:00.000-05:00 0,Character One,Goodbye\n");
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        stats_json: Some(String::new()),
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config).await.expect("pipeline completed");
+
+    let raw = fs::read_to_string(temp.path().join("event.stats.json")).expect("stats written");
+    let stats: serde_json::Value = serde_json::from_str(&raw).expect("valid json");
+    assert_eq!(stats["total_messages"], 3);
+    assert_eq!(stats["speakers"][0]["speaker"], "Character One");
+    assert_eq!(stats["speakers"][0]["messages"], 2);
+    assert_eq!(stats["speakers"][1]["messages"], 1);
+    assert_eq!(stats["start"], "2025-01-04T21:30");
+    assert_eq!(stats["end"], "2025-01-04T23:30");
+    let stages = stats["stages"].as_array().expect("stage timings");
+    assert!(
+        stages
+            .iter()
+            .any(|stage| stage["stage"] == "Parse and filter lines")
+    );
+    assert!(stats["total_ms"].as_f64().unwrap() > 0.0);
+}
+
+#[tokio::test]
+async fn strict_parse_rejects_mostly_unparseable_input() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("output.txt");
+    let garbage = "not a chat line\n\
+2025-01-04T22:02:00.000-05:00 missing separators\n\
+2025-01-04T22:03:00.000-05:00 also missing\n";
+    fs::write(&infile_path, format!("{SAMPLE_LOG}{garbage}")).expect("write fixture");
+
+    let config = base_config(
+        infile_path.to_string_lossy().as_ref(),
+        outfile_path.to_string_lossy().as_ref(),
+    );
+    run_with_config(config.clone())
+        .await
+        .expect("lenient parsing skips unmatched lines");
+    assert!(outfile_path.exists());
+
+    let config = ConvocationsConfig {
+        strict_parse: true,
+        ..config
+    };
+    let err = run_with_config(config)
+        .await
+        .expect_err("strict parsing should fail");
+    assert!(matches!(err, RuntimeError::Parse(_)), "got {err:?}");
+    assert!(err.to_string().starts_with("3 of 5 lines"), "got {err}");
+}
+
+#[tokio::test]
+async fn pipeline_reads_alternate_timestamp_formats() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    let outfile_path = temp.path().join("output.txt");
+    let log = "\
+[2025-01-04 22:00:00] 0,Character One,Hello there\n\
+2025-01-04T22:01:15.125-05:00 6,Character Two,gestures gracefully\n\
+[2025-01-05 01:00:00] 0,Character One,Too late\n";
+    fs::write(&infile_path, log).expect("write fixture");
+
+    let config = ConvocationsConfig {
+        timestamp_formats: vec!["eso".to_string(), "bracketed".to_string()],
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(config.clone())
+        .await
+        .expect("pipeline completed successfully");
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    let expected = "Character One says, \"Hello there.\"\nCharacter Two gestures gracefully.\n";
+    assert_eq!(output, expected);
+
+    let config = ConvocationsConfig {
+        timestamp_formats: vec!["(?P<year>\\d{4})".to_string()],
+        ..config
+    };
+    let err = run_with_config(config)
+        .await
+        .expect_err("regex without required groups");
+    assert!(matches!(err, RuntimeError::Config(_)), "got {err:?}");
+}
+
+#[tokio::test]
+async fn mkdir_output_creates_missing_output_directory() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+    let outdir = temp.path().join("nested").join("exports");
+
+    let config = ConvocationsConfig {
+        outfile: Some("output.txt".to_string()),
+        output_directory: Some(outdir.to_string_lossy().into_owned()),
+        ..base_config(infile_path.to_string_lossy().as_ref(), "")
+    };
+    let err = run_with_config(config.clone())
+        .await
+        .expect_err("missing directory without mkdir_output");
+    assert!(!outdir.exists(), "{err}");
+
+    run_with_config(ConvocationsConfig {
+        mkdir_output: true,
+        ..config
+    })
+    .await
+    .expect("pipeline completed successfully");
+    assert!(outdir.join("output.txt").exists());
+}
+
+#[tokio::test]
+async fn final_newline_ends_output_with_a_single_newline() {
+    let temp = tempdir().expect("tempdir");
+    let infile_path = temp.path().join("ChatLog.log");
+    fs::write(&infile_path, SAMPLE_LOG).expect("write fixture");
+    let outfile_path = temp.path().join("output.txt");
+
+    run_with_config(base_config(
+        infile_path.to_string_lossy().as_ref(),
+        outfile_path.to_string_lossy().as_ref(),
+    ))
+    .await
+    .expect("pipeline completed successfully");
+
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    assert!(output.ends_with('\n'), "{output:?}");
+    assert!(!output.ends_with("\n\n"), "{output:?}");
+
+    // Unformatted pre-filtered output is joined without a trailing newline of its own.
+    let unformatted = ConvocationsConfig {
+        process_file: Some(infile_path.to_string_lossy().into_owned()),
+        format_dialogue: false,
+        ..base_config(
+            infile_path.to_string_lossy().as_ref(),
+            outfile_path.to_string_lossy().as_ref(),
+        )
+    };
+    run_with_config(ConvocationsConfig {
+        final_newline: false,
+        ..unformatted.clone()
+    })
+    .await
+    .expect("pipeline completed successfully");
+    let bare = fs::read_to_string(&outfile_path).expect("read output");
+    assert!(!bare.ends_with('\n'), "{bare:?}");
+
+    run_with_config(unformatted)
+        .await
+        .expect("pipeline completed successfully");
+    let output = fs::read_to_string(&outfile_path).expect("read output");
+    assert_eq!(output, bare + "\n");
+}
+
+#[tokio::test]
+async fn process_text_runs_the_pipeline_in_memory() {
+    let temp = tempdir().expect("tempdir");
+    let outfile_path = temp.path().join("output.txt");
+    // Neither file exists; process_text must not read or write either.
+    let config = base_config(
+        temp.path().join("missing.log").to_string_lossy().as_ref(),
+        outfile_path.to_string_lossy().as_ref(),
+    );
+    let input = format!("{SAMPLE_LOG}not a chat log line\n");
+
+    let output = process_text(&input, &config)
+        .await
+        .expect("processing succeeds");
+    assert_eq!(
+        output.text,
+        "Character One says, \"Hello there.\"\nCharacter Two gestures gracefully.\n"
+    );
+    assert_eq!(output.unedited, output.text);
+    assert_eq!(output.total_messages, 2);
+    assert_eq!(
+        output.speakers,
+        [
+            ("Character One".to_string(), 1),
+            ("Character Two".to_string(), 1)
+        ]
+    );
+    assert_eq!(output.skipped_lines, 1);
+    assert!(!outfile_path.exists());
+
+    let empty_window = ConvocationsConfig {
+        start: Some("2024-01-01T00:00".to_string()),
+        end: Some("2024-01-01T01:00".to_string()),
+        ..config
+    };
+    let err = process_text(SAMPLE_LOG, &empty_window)
+        .await
+        .expect_err("empty window should fail");
+    assert!(matches!(err, RuntimeError::EmptyResult(_)), "got {err:?}");
+
+    // Pre-filtered text is not limited to the date window.
+    let prefiltered = ConvocationsConfig {
+        process_file: Some("unused.txt".to_string()),
+        ..empty_window
+    };
+    let output = process_text(SAMPLE_LOG, &prefiltered)
+        .await
+        .expect("pre-filtered processing succeeds");
+    assert_eq!(
+        output.text,
+        "Character One says, \"Hello there.\"\nCharacter Two gestures gracefully.\n"
+    );
+    assert!(output.speakers.is_empty());
+}
+
+#[tokio::test]
+async fn with_index_lists_every_speaker_with_message_counts() {
+    let config = ConvocationsConfig {
+        with_index: true,
+        header_template: Some("# {preset}".to_string()),
+        ..base_config("unused.log", "unused.txt")
+    };
+    let input = format!(
+        "{SAMPLE_LOG}\
+2025-01-04T22:02:00.000-05:00 0,Character Two,And again\n\
+2025-01-04T22:03:00.000-05:00 0,Character Two,And once more\n"
+    );
+
+    let output = process_text(&input, &config)
+        .await
+        .expect("processing succeeds");
+    let index = "Speakers:\n  Character Two (3)\n  Character One (1)\n\n";
+    let (header, rest) = output.text.split_once("\n\n").expect("header");
+    assert!(header.starts_with("# "), "{header}");
+    assert!(rest.starts_with(index), "{rest}");
+    assert!(rest[index.len()..].starts_with("Character One says, \"Hello there.\"\n"));
+}
+
+#[test]
+fn run_futures_can_be_spawned_on_a_multithreaded_runtime() {
+    fn assert_send<T: Send>(_: &T) {}
+    let callback: rconv_core::StageProgressCallback = Arc::new(|_event: StageProgressEvent| {});
+    let future = run_with_config_with_progress(ConvocationsConfig::default(), callback);
+    assert_send(&future);
+    assert_send(&run_with_config(ConvocationsConfig::default()));
+}
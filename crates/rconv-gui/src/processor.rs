@@ -2,6 +2,7 @@
 
 use crate::async_bridge::{AsyncBridge, ProgressKind, ProgressUpdate};
 use crate::state::AppState;
+use rconv_core::openrouter::TokenUsage;
 use rconv_core::{
     runtime_preferences_to_convocations, run_with_config_with_progress,
     StageProgressEvent, StageProgressEventKind,
@@ -19,10 +20,20 @@ pub fn start_processing(
         runtime_preferences_to_convocations(&state.config.runtime, &state.config.presets);
 
     // Log warnings
-    for warning in warnings {
+    for warning in &warnings {
         eprintln!("Warning: {}", warning);
     }
 
+    // Refuse to start a run that would fail validation
+    let problems = rconv_core::validation::config_problems(&runtime_config);
+    if !problems.is_empty() {
+        let messages: Vec<String> = problems
+            .into_iter()
+            .map(|problem| problem.message)
+            .collect();
+        return Err(messages.join("; "));
+    }
+
     // Create progress channel
     let (tx, rx) = mpsc::unbounded_channel();
 
@@ -47,9 +58,15 @@ pub fn start_processing(
         // Track diff for final completion event
         let diff_content = Arc::new(std::sync::Mutex::new(None));
         let diff_clone = diff_content.clone();
+        // Track tokens used by the LLM stages for the completion summary
+        let token_usage = Arc::new(std::sync::Mutex::new(None::<TokenUsage>));
+        let usage_clone = token_usage.clone();
 
         // Create progress callback
         let progress_callback = Arc::new(move |event: StageProgressEvent| {
+            if let Some(usage) = event.usage {
+                *usage_clone.lock().unwrap().get_or_insert_default() += usage;
+            }
             let update = match event.kind {
                 StageProgressEventKind::Begin => ProgressUpdate {
                     kind: ProgressKind::StageBegin {
@@ -102,12 +119,19 @@ pub fn start_processing(
         match run_with_config_with_progress(runtime_config, progress_callback).await {
             Ok(()) => {
                 let diff = diff_content.lock().unwrap().clone();
+                let summary = match *token_usage.lock().unwrap() {
+                    Some(usage) => format!(
+                        "Processing completed successfully ({} tokens used)",
+                        usage.total_tokens
+                    ),
+                    None => "Processing completed successfully".to_string(),
+                };
                 let _ = tx.send(ProgressUpdate {
                     kind: ProgressKind::Completed {
-                        summary: "Processing completed successfully".to_string(),
+                        summary: summary.clone(),
                         diff,
                     },
-                    message: Some("Processing completed successfully".to_string()),
+                    message: Some(summary),
                     stage: None,
                     elapsed_ms: None,
                 });
@@ -14,7 +14,9 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
             ui.label("Event Preset:")
                 .on_hover_text("Select a preset for recurring events (e.g., RSM7, WVM)");
 
-            let current_preset = &state.config.runtime.active_preset;
+            let current_preset = rconv_core::find_preset(&state.config.presets, &state.config.runtime.active_preset)
+                .map(|preset| preset.name.clone())
+                .unwrap_or_else(|| state.config.runtime.active_preset.clone());
 
             let response = egui::ComboBox::from_id_salt("preset_selector")
                 .selected_text(current_preset)
@@ -27,7 +29,7 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
                     for preset in &state.config.presets {
                         if ui.selectable_value(
                             &mut state.config.runtime.active_preset,
-                            preset.name.clone(),
+                            preset.id.clone(),
                             &preset.name
                         ).changed() {
                             preset_changed = true;
@@ -57,7 +59,7 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
         // Show calculated date/time range when a preset is selected
         if !state.config.runtime.active_preset.is_empty() {
             // Find the selected preset
-            if let Some(preset) = state.config.presets.iter().find(|p| p.name == state.config.runtime.active_preset) {
+            if let Some(preset) = rconv_core::find_preset(&state.config.presets, &state.config.runtime.active_preset) {
                 // Calculate duration to use
                 let duration_minutes = if state.config.runtime.duration_override.enabled {
                     (state.config.runtime.duration_override.hours * 60.0) as i64
@@ -65,24 +67,23 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
                     preset.duration_minutes as i64
                 };
 
-                // Determine event type from preset name
-                let event_type = if preset.name == rconv_core::TUESDAY_7_PRESET_NAME {
-                    "rsm7"
-                } else if preset.name == rconv_core::TUESDAY_8_PRESET_NAME {
-                    "rsm8"
-                } else if preset.name == rconv_core::FRIDAY_6_PRESET_NAME {
-                    "tp6"
+                // Built-in presets map to an event type; custom presets use their own schedule
+                let today = chrono::Local::now().date_naive();
+                let weeks_ago = state.config.runtime.weeks_ago;
+                let dates = if preset.id == rconv_core::TUESDAY_7_PRESET_ID {
+                    rconv_core::calculate_event_dates(today, weeks_ago, "rsm7", duration_minutes)
+                } else if preset.id == rconv_core::TUESDAY_8_PRESET_ID {
+                    rconv_core::calculate_event_dates(today, weeks_ago, "rsm8", duration_minutes)
+                } else if preset.id == rconv_core::FRIDAY_6_PRESET_ID {
+                    rconv_core::calculate_event_dates(today, weeks_ago, "tp6", duration_minutes)
+                } else if preset.builtin {
+                    rconv_core::calculate_event_dates(today, weeks_ago, "saturday", duration_minutes)
                 } else {
-                    "saturday"
+                    custom_preset_dates(preset, today, weeks_ago, duration_minutes)
                 };
 
                 // Calculate dates
-                if let Ok((start, end)) = rconv_core::calculate_event_dates(
-                    chrono::Local::now().date_naive(),
-                    state.config.runtime.weeks_ago,
-                    event_type,
-                    duration_minutes,
-                ) {
+                if let Ok((start, end)) = dates {
                     ui.group(|ui| {
                         ui.label(egui::RichText::new("Event Date Range:").strong());
                         ui.horizontal(|ui| {
@@ -239,3 +240,30 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
         changed
     }).inner
 }
+
+/// Event window for a user-defined preset, from its weekday, start time and timezone.
+fn custom_preset_dates(
+    preset: &rconv_core::PresetDefinition,
+    today: chrono::NaiveDate,
+    weeks_ago: u32,
+    duration_minutes: i64,
+) -> Result<(String, String), String> {
+    use chrono::Timelike;
+
+    let weekday: chrono::Weekday = preset
+        .weekday
+        .trim()
+        .parse()
+        .map_err(|_| format!("Unknown weekday: {}", preset.weekday))?;
+    let start = chrono::NaiveTime::parse_from_str(preset.start_time.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid start time: {}", preset.start_time))?;
+    rconv_core::calculate_custom_event_dates(
+        today,
+        weeks_ago,
+        weekday,
+        start.hour(),
+        start.minute(),
+        &preset.timezone,
+        duration_minutes,
+    )
+}
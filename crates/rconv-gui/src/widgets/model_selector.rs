@@ -48,8 +48,12 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
 
         ui.add_space(8.0);
 
-        // Free models only filter
-        ui.checkbox(&mut state.config.runtime.free_models_only, "Show Free Models Only");
+        // Free models only: filters the lists below and limits automatic selection
+        ui.checkbox(&mut state.config.runtime.free_models_only, "Free Models Only")
+            .on_hover_text(
+                "Only list free models, and let automatic selection pick only curated free \
+                 models. AI corrections are skipped when no free model is available.",
+            );
 
         ui.add_space(8.0);
 
@@ -152,7 +156,12 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
                 ui.add_space(4.0);
 
                 // Preview which model will be selected based on current settings
-                if let Some(ref models) = state.model_catalog_cache {
+                if let Some(pinned) = active_preset_model(state) {
+                    ui.group(|ui| {
+                        ui.label(egui::RichText::new("Pinned by Active Preset:").strong());
+                        ui.label(format!("Slug: {}", pinned));
+                    });
+                } else if let Some(ref models) = state.model_catalog_cache {
                     let free_models: Vec<_> = models.iter()
                         .filter(|m| matches!(m.tier, curator::CuratedTier::Free))
                         .collect();
@@ -282,6 +291,71 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
             }
         }
 
+        ui.add_space(8.0);
+        render_preset_model(ui, state);
+
         load_all_models
     }).inner
 }
+
+/// Model pinned by the active preset, if any.
+fn active_preset_model(state: &AppState) -> Option<String> {
+    rconv_core::find_preset(&state.config.presets, &state.config.runtime.active_preset)
+        .and_then(|preset| preset.llm_model.clone())
+        .filter(|model| !model.trim().is_empty())
+}
+
+/// Pin a curated model to the active preset, or clear the pin so the preset follows the
+/// global choice. Pins apply in Automatic mode, so switching presets switches the model.
+fn render_preset_model(ui: &mut egui::Ui, state: &mut AppState) {
+    let Some(active_id) = rconv_core::find_preset(&state.config.presets, &state.config.runtime.active_preset)
+        .map(|preset| preset.id.clone())
+    else {
+        return;
+    };
+    let Some(preset) = state.config.presets.iter().find(|preset| preset.id == active_id) else {
+        return;
+    };
+    let preset_name = preset.name.clone();
+    let mut pinned = preset.llm_model.clone().filter(|model| !model.trim().is_empty());
+    let original = pinned.clone();
+
+    ui.separator();
+    ui.label(egui::RichText::new(format!("Model for preset \"{}\"", preset_name)).strong());
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("preset_model_selector")
+            .selected_text(pinned.as_deref().unwrap_or("Auto / global"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut pinned, None, "Auto / global");
+                if let Some(ref models) = state.model_catalog_cache {
+                    let free_only = state.config.runtime.free_models_only;
+                    for model in models.iter().filter(|m| !free_only || matches!(m.tier, curator::CuratedTier::Free)) {
+                        ui.selectable_value(
+                            &mut pinned,
+                            Some(model.slug.clone()),
+                            format!("{} - {}", model.slug, model.display_name),
+                        );
+                    }
+                }
+            });
+
+        if pinned.is_some() && ui.button("Clear").clicked() {
+            pinned = None;
+        }
+    });
+
+    if state.model_selection_mode != crate::state::ModelSelectionMode::Auto {
+        ui.label("Used in Automatic mode; a model chosen above takes precedence.");
+    }
+
+    if pinned != original {
+        if let Some(preset) = state.config.presets.iter_mut().find(|preset| preset.id == active_id) {
+            preset.llm_model = pinned;
+        }
+        // Persist right away, like edits made in the preset manager
+        if let Err(e) = rconv_core::save_presets_and_ui_only(&state.config.presets, &state.config.ui) {
+            eprintln!("Error saving presets: {}", e);
+        }
+    }
+}
@@ -22,6 +22,7 @@ impl Default for PresetEditorState {
 
 #[derive(Default, Clone)]
 pub struct PresetForm {
+    pub id: String,
     pub name: String,
     pub weekday: String,
     pub timezone: String,
@@ -29,11 +30,14 @@ pub struct PresetForm {
     pub duration_minutes: u32,
     pub file_prefix: String,
     pub default_weeks_ago: u32,
+    pub filename_template: String,
+    pub llm_model: String,
 }
 
 impl PresetForm {
     fn from_preset(preset: &PresetDefinition) -> Self {
         Self {
+            id: preset.id.clone(),
             name: preset.name.clone(),
             weekday: preset.weekday.clone(),
             timezone: preset.timezone.clone(),
@@ -41,11 +45,14 @@ impl PresetForm {
             duration_minutes: preset.duration_minutes,
             file_prefix: preset.file_prefix.clone(),
             default_weeks_ago: preset.default_weeks_ago,
+            filename_template: preset.filename_template.clone().unwrap_or_default(),
+            llm_model: preset.llm_model.clone().unwrap_or_default(),
         }
     }
 
     fn to_preset(&self, builtin: bool) -> PresetDefinition {
         PresetDefinition {
+            id: self.id.clone(),
             name: self.name.clone(),
             weekday: self.weekday.clone(),
             timezone: self.timezone.clone(),
@@ -53,6 +60,12 @@ impl PresetForm {
             duration_minutes: self.duration_minutes,
             file_prefix: self.file_prefix.clone(),
             default_weeks_ago: self.default_weeks_ago,
+            filename_template: Some(self.filename_template.trim())
+                .filter(|template| !template.is_empty())
+                .map(str::to_string),
+            llm_model: Some(self.llm_model.trim())
+                .filter(|model| !model.is_empty())
+                .map(str::to_string),
             builtin,
         }
     }
@@ -86,8 +99,12 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState, editor_state: &mut Preset
                     }
 
                     if ui.button("Delete").clicked() {
-                        // Delete preset
-                        state.config.presets.retain(|p| p.name != preset.name);
+                        // Delete preset by id; presets saved before ids existed fall back to the name
+                        if preset.id.is_empty() {
+                            state.config.presets.retain(|p| !(p.id.is_empty() && p.name == preset.name));
+                        } else {
+                            state.config.presets.retain(|p| p.id != preset.id);
+                        }
                     }
                 });
             }
@@ -101,6 +118,7 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState, editor_state: &mut Preset
                 editor_state.editing = true;
                 editor_state.editing_name = None;
                 editor_state.form = PresetForm {
+                    id: String::new(),
                     name: String::new(),
                     weekday: "saturday".to_string(),
                     timezone: "America/New_York".to_string(),
@@ -108,6 +126,8 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState, editor_state: &mut Preset
                     duration_minutes: 120,
                     file_prefix: "conv".to_string(),
                     default_weeks_ago: 0,
+                    filename_template: String::new(),
+                    llm_model: String::new(),
                 };
             }
         }
@@ -162,15 +182,42 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState, editor_state: &mut Preset
                 ui.add(egui::DragValue::new(&mut editor_state.form.default_weeks_ago).range(0..=52));
             });
 
+            ui.horizontal(|ui| {
+                ui.label("Filename Template:");
+                ui.text_edit_singleline(&mut editor_state.form.filename_template)
+                    .on_hover_text("Optional, e.g. conv-{yyyy}-{mm}-{dd}.txt. Placeholders: {prefix}, {date}, {yyyy}, {mm}, {dd}, {preset}. Leave empty for {prefix}-{date}.txt.");
+            });
+            let template_error = Some(editor_state.form.filename_template.trim())
+                .filter(|template| !template.is_empty())
+                .and_then(|template| rconv_core::validate_filename_template(template).err());
+            if let Some(ref err) = template_error {
+                ui.colored_label(egui::Color32::from_rgb(220, 80, 80), err.as_str());
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("LLM Model:");
+                ui.text_edit_singleline(&mut editor_state.form.llm_model)
+                    .on_hover_text("Optional OpenRouter model id, e.g. openai/gpt-4o-mini. Used for this preset when the model setting is auto. Leave empty to use the global model.");
+            });
+            let model_error = Some(editor_state.form.llm_model.trim())
+                .filter(|model| !model.is_empty())
+                .and_then(|model| rconv_core::config::validate_model_slug(model).err());
+            if let Some(ref err) = model_error {
+                ui.colored_label(egui::Color32::from_rgb(220, 80, 80), err.as_str());
+            }
+
             ui.add_space(8.0);
 
             ui.horizontal(|ui| {
-                if ui.button("Save").clicked() {
-                    let preset = editor_state.form.to_preset(false);
+                if ui.add_enabled(template_error.is_none() && model_error.is_none(), egui::Button::new("Save")).clicked() {
+                    let mut preset = editor_state.form.to_preset(false);
+                    if preset.id.is_empty() {
+                        preset.id = rconv_core::unique_preset_id(&state.config.presets, &preset.name);
+                    }
 
                     if let Some(ref edit_name) = editor_state.editing_name {
-                        // Update existing preset
-                        if let Some(existing) = state.config.presets.iter_mut().find(|p| p.name == *edit_name) {
+                        // Update existing preset; match on id so renames keep the active selection
+                        if let Some(existing) = state.config.presets.iter_mut().find(|p| p.id == preset.id || (p.id.is_empty() && p.name == *edit_name)) {
                             *existing = preset;
                         }
                     } else {
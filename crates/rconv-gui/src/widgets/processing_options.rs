@@ -15,16 +15,19 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
             changed = true;
         }
 
-        // Keep original output
-        if ui.checkbox(&mut state.config.runtime.keep_original_output, "Keep Original Output")
-            .on_hover_text("Preserve the original processed file before AI corrections")
+        // Keep original output and show diff are independent, but both need AI corrections
+        let ai_enabled = state.config.runtime.use_ai_corrections;
+        if ui.add_enabled(ai_enabled, egui::Checkbox::new(&mut state.config.runtime.keep_original_output, "Keep Original Output"))
+            .on_hover_text("Save the text from before AI corrections as a separate _unedited file")
+            .on_disabled_hover_text("Only applies when AI corrections are on")
             .changed() {
             changed = true;
         }
 
         // Show diff
-        if ui.checkbox(&mut state.config.runtime.show_diff, "Show Diff After Processing")
-            .on_hover_text("Display a diff showing changes made during processing")
+        if ui.add_enabled(ai_enabled, egui::Checkbox::new(&mut state.config.runtime.show_diff, "Show Diff After Processing"))
+            .on_hover_text("Display a diff of the changes made by AI corrections")
+            .on_disabled_hover_text("Only applies when AI corrections are on")
             .changed() {
             changed = true;
         }
@@ -43,6 +46,36 @@ pub fn render(ui: &mut egui::Ui, state: &mut AppState) -> bool {
             changed = true;
         }
 
+        ui.add_space(8.0);
+
+        // AI request tuning
+        if ui.horizontal(|ui| {
+            ui.label("AI Concurrency:")
+                .on_hover_text("How many chunks are sent to the AI model at once. Higher is faster but may hit rate limits on free models.");
+            ui.add(egui::DragValue::new(&mut state.config.runtime.llm_concurrency)
+                .range(1..=rconv_core::config::MAX_LLM_CONCURRENCY))
+        }).inner.changed() {
+            changed = true;
+        }
+
+        if ui.horizontal(|ui| {
+            ui.label("AI Retries:")
+                .on_hover_text("How many more times a failed AI request is attempted before the original text is kept.");
+            ui.add(egui::DragValue::new(&mut state.config.runtime.llm_max_retries)
+                .range(0..=rconv_core::config::MAX_LLM_MAX_RETRIES))
+        }).inner.changed() {
+            changed = true;
+        }
+
+        if ui.horizontal(|ui| {
+            ui.label("AI Timeout (seconds):")
+                .on_hover_text("How long to wait for each AI request before giving up on it.");
+            ui.add(egui::DragValue::new(&mut state.config.runtime.llm_timeout_secs)
+                .range(1..=rconv_core::config::MAX_LLM_TIMEOUT_SECS))
+        }).inner.changed() {
+            changed = true;
+        }
+
         changed
     }).inner
 }